@@ -1,42 +1,74 @@
 #![allow(unused_imports)]
 #![cfg(feature = "server")]
 #![cfg(not(target_arch = "wasm32"))]
-use std::{env, fs::OpenOptions, io::ErrorKind, net::SocketAddr, str::FromStr};
+use std::{
+    collections::{BTreeSet, HashMap},
+    env,
+    fs::OpenOptions,
+    io::ErrorKind,
+    net::SocketAddr,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use axum::{
     body::Bytes,
-    extract::Path,
+    extract::{Path, RawQuery},
     headers::{authorization::Bearer, Authorization, CacheControl},
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{delete, get, post, put},
     Json, Router, TypedHeader,
 };
-use bitcoin_30::secp256k1::{ecdh::SharedSecret, PublicKey, SecretKey};
+use bitcoin_30::secp256k1::{ecdh::SharedSecret, PublicKey, Secp256k1, SecretKey};
 use bitmask_core::{
-    bitcoin::{save_mnemonic, sign_and_publish_psbt_file},
+    bitcoin::{
+        bump_fee, cpfp, create_payjoin_uri, discover_wallet, freeze_utxos, get_broadcast_queue,
+        get_fee_estimates, list_frozen_utxos, payjoin_proposal, plan_vault_utxos,
+        process_broadcast_queues, queue_psbt_file, save_mnemonic, sign_and_publish_psbt_file,
+        sign_and_queue_psbt_file, sign_bip322, subscribe_sync_events, sync_wallet_descriptors,
+        unfreeze_utxos, verify_bip322, wallet_key,
+    },
     carbonado::{handle_file, server_retrieve, server_store, store},
     constants::{
-        get_marketplace_nostr_key, get_marketplace_seed, get_network, get_udas_utxo, switch_network,
+        get_marketplace_nostr_key, get_marketplace_seed, get_network, get_udas_utxo,
+        switch_network, BITMASK_ENDPOINT,
     },
     rgb::{
-        accept_transfer, clear_watcher as rgb_clear_watcher, create_invoice, create_psbt,
-        create_watcher, full_transfer_asset, import as rgb_import, issue_contract, list_contracts,
-        list_interfaces, list_schemas, list_transfers as list_rgb_transfers, reissue_contract,
-        remove_transfer as remove_rgb_transfer, save_transfer as save_rgb_transfer, transfer_asset,
-        watcher_address, watcher_details as rgb_watcher_details, watcher_next_address,
-        watcher_next_utxo, watcher_utxo,
+        accept_transfer, airdrop_asset, burn_asset, burn_proof, clear_watcher as rgb_clear_watcher,
+        create_invoice, create_psbt, create_watcher, full_batch_transfer_asset,
+        full_transfer_asset, import as rgb_import, inflate_contract, issue_contract,
+        list_contracts, list_interfaces, list_schemas, list_transfers as list_rgb_transfers,
+        reissue_contract, remove_transfer as remove_rgb_transfer,
+        save_transfer as save_rgb_transfer, transfer_asset, verify_burn_proof, watcher_address,
+        watcher_details as rgb_watcher_details, watcher_next_address, watcher_next_utxo,
+        watcher_utxo,
     },
     structs::{
-        AcceptRequest, FileMetadata, FullRgbTransferRequest, ImportRequest, InvoiceRequest,
-        IssueRequest, PsbtFeeRequest, PsbtRequest, ReIssueRequest, RgbRemoveTransferRequest,
-        RgbSaveTransferRequest, RgbTransferRequest, SecretString, SelfFullRgbTransferRequest,
-        SelfInvoiceRequest, SelfIssueRequest, SignPsbtRequest, WatcherRequest,
+        AcceptRequest, BroadcastQueueRequest, BumpFeeRequest, CpfpRequest, FileMetadata,
+        FreezeUtxosRequest, FullRgbBatchTransferRequest, FullRgbTransferRequest, ImportRequest,
+        InflateRequest, InvoiceRequest, IssueRequest, PayjoinReceiverRequest, PsbtFeeRequest,
+        PsbtRequest, PublishPsbtRequest, ReIssueRequest, RgbAirdropRequest, RgbBurnProof,
+        RgbBurnProofRequest, RgbBurnRequest, RgbRemoveTransferRequest, RgbSaveTransferRequest,
+        RgbTransferRequest, SecretString, SelfFullRgbTransferRequest, SelfInvoiceRequest,
+        SelfIssueRequest, SignMessageRequest, SignPsbtRequest, VaultUtxoPlanRequest,
+        VerifyMessageRequest, WalletDiscoveryRequest, WalletSyncRequest, WalletSyncResponse,
+        WatcherRequest,
     },
 };
+use futures::{stream, Future, Stream};
 use log::{debug, error, info};
-use tokio::fs;
+use once_cell::sync::Lazy;
+use tokio::{
+    fs,
+    runtime::Handle,
+    sync::{broadcast::error::RecvError, RwLock, Semaphore},
+};
 use tower_http::cors::CorsLayer;
 
 async fn issue(
@@ -61,6 +93,17 @@ async fn reissue(
     Ok((StatusCode::OK, Json(issue_res)))
 }
 
+async fn inflate(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<InflateRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /inflate {}", request.contract_id);
+
+    let nostr_hex_sk = auth.token();
+    let inflate_res = inflate_contract(nostr_hex_sk, request).await?;
+    Ok((StatusCode::OK, Json(inflate_res)))
+}
+
 async fn self_issue(Json(issue): Json<SelfIssueRequest>) -> Result<impl IntoResponse, AppError> {
     info!("POST /self_issue {issue:?}");
     let issuer_keys = save_mnemonic(
@@ -80,6 +123,7 @@ async fn self_issue(Json(issue): Json<SelfIssueRequest>) -> Result<impl IntoResp
         seal: issue_seal.to_owned(),
         iface: "RGB21".to_string(),
         meta: issue.meta,
+        inflation: None,
     };
 
     let issue_res = issue_contract(sk, request).await?;
@@ -139,11 +183,13 @@ async fn _psbt(
 }
 
 async fn _sign_psbt(
-    TypedHeader(_auth): TypedHeader<Authorization<Bearer>>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
     Json(psbt_req): Json<SignPsbtRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     info!("POST /sign {psbt_req:?}");
-    let psbt_res = sign_and_publish_psbt_file(psbt_req).await?;
+
+    let nostr_hex_sk = auth.token();
+    let psbt_res = sign_and_publish_psbt_file(nostr_hex_sk, psbt_req).await?;
 
     Ok((StatusCode::OK, Json(psbt_res)))
 }
@@ -162,6 +208,70 @@ async fn pay(
     Ok((StatusCode::OK, Json(transfer_res)))
 }
 
+async fn batch_pay(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(batch_pay_req): Json<FullRgbBatchTransferRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /batchpay {} invoices", batch_pay_req.invoices.len());
+
+    let nostr_hex_sk = auth.token();
+
+    let transfer_res = full_batch_transfer_asset(nostr_hex_sk, batch_pay_req).await?;
+
+    Ok((StatusCode::OK, Json(transfer_res)))
+}
+
+async fn burn(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(burn_req): Json<RgbBurnRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /burn {burn_req:?}");
+
+    let nostr_hex_sk = auth.token();
+    let burn_res = burn_asset(nostr_hex_sk, burn_req).await?;
+
+    Ok((StatusCode::OK, Json(burn_res)))
+}
+
+async fn get_burn_proof(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(proof_req): Json<RgbBurnProofRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /burnproof {proof_req:?}");
+
+    let nostr_hex_sk = auth.token();
+    let proof_res = burn_proof(nostr_hex_sk, proof_req).await?;
+
+    Ok((StatusCode::OK, Json(proof_res)))
+}
+
+async fn verify_burn(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(proof): Json<RgbBurnProof>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /verifyburn {}", proof.contract_id);
+
+    let nostr_hex_sk = auth.token();
+    let verify_res = verify_burn_proof(nostr_hex_sk, proof).await?;
+
+    Ok((StatusCode::OK, Json(verify_res)))
+}
+
+#[axum_macros::debug_handler]
+async fn airdrop(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(airdrop_req): Json<RgbAirdropRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /airdrop {} recipients", airdrop_req.recipients.len());
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let airdrop_res =
+        run_wallet_task(move || async move { airdrop_asset(&nostr_hex_sk, airdrop_req).await })
+            .await??;
+
+    Ok((StatusCode::OK, Json(airdrop_res)))
+}
+
 #[axum_macros::debug_handler]
 async fn self_pay(
     Json(self_pay_req): Json<SelfFullRgbTransferRequest>,
@@ -404,6 +514,393 @@ async fn remove_transfer(
     Ok((StatusCode::OK, Json(import_res)))
 }
 
+async fn bitcoin_bump_fee(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<BumpFeeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/bumpfee {:?}", request.txid);
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let bump_res = run_wallet_task(move || async move {
+        bump_fee(
+            &nostr_hex_sk,
+            &request.descriptor,
+            &request.change_descriptor,
+            &request.txid,
+            request.fee_rate,
+        )
+        .await
+    })
+    .await??;
+
+    Ok((StatusCode::OK, Json(bump_res)))
+}
+
+async fn bitcoin_cpfp(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<CpfpRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/cpfp {:?}", request.txid);
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let cpfp_res = run_wallet_task(move || async move {
+        cpfp(
+            &nostr_hex_sk,
+            &request.descriptor,
+            &request.change_descriptor,
+            &request.txid,
+            request.fee_rate,
+        )
+        .await
+    })
+    .await??;
+
+    Ok((StatusCode::OK, Json(cpfp_res)))
+}
+
+async fn bitcoin_discover(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<WalletDiscoveryRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/discover {:?}", request.gap_limit);
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let discovery_res =
+        run_wallet_task(move || async move { discover_wallet(&nostr_hex_sk, request).await })
+            .await??;
+
+    Ok((StatusCode::OK, Json(discovery_res)))
+}
+
+async fn bitcoin_vault_slots(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<VaultUtxoPlanRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/vault/slots");
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let plan_res =
+        run_wallet_task(move || async move { plan_vault_utxos(&nostr_hex_sk, request).await })
+            .await??;
+
+    Ok((StatusCode::OK, Json(plan_res)))
+}
+
+async fn bitcoin_queue(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<PublishPsbtRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/queue");
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let queue_res =
+        run_wallet_task(move || async move { queue_psbt_file(&nostr_hex_sk, request).await })
+            .await??;
+
+    Ok((StatusCode::OK, Json(queue_res)))
+}
+
+async fn bitcoin_sign_and_queue(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<SignPsbtRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/queue/sign");
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let queue_res =
+        run_wallet_task(
+            move || async move { sign_and_queue_psbt_file(&nostr_hex_sk, request).await },
+        )
+        .await??;
+
+    Ok((StatusCode::OK, Json(queue_res)))
+}
+
+async fn bitcoin_queue_status(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<BroadcastQueueRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/queue/status");
+
+    let nostr_hex_sk = auth.token().to_owned();
+    let queue_res =
+        run_wallet_task(move || async move { get_broadcast_queue(&nostr_hex_sk, request).await })
+            .await??;
+
+    Ok((StatusCode::OK, Json(queue_res)))
+}
+
+/// Sync jobs running at the same time, each one holding a blocking thread
+const MAX_SYNC_TASKS: usize = 16;
+
+static SYNC_TASKS: Lazy<Arc<Semaphore>> = Lazy::new(|| Arc::new(Semaphore::new(MAX_SYNC_TASKS)));
+
+/// Callers with a running sync job, at most one each
+static SYNC_OWNERS: Lazy<RwLock<BTreeSet<String>>> = Lazy::new(Default::default);
+
+/// Public key of the nostr secret key of the bearer token, rejecting tokens that are not one
+fn sync_owner(auth: &Authorization<Bearer>) -> Result<String, StatusCode> {
+    let sk = SecretKey::from_str(auth.token()).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    Ok(sk.public_key(&Secp256k1::new()).to_string())
+}
+
+async fn bitcoin_sync(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<WalletSyncRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    info!("POST /bitcoin/sync");
+
+    let owner = sync_owner(&auth)?;
+    let permit = SYNC_TASKS
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| StatusCode::TOO_MANY_REQUESTS)?;
+    if !SYNC_OWNERS.write().await.insert(owner.clone()) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let wallets: Vec<String> = request
+        .wallets
+        .iter()
+        .map(|wallet| wallet_key(&wallet.descriptor, wallet.change_descriptor.as_ref()))
+        .collect();
+
+    // Progress is streamed on /bitcoin/sync/events, tagged with the caller as the job. Knowing
+    // the descriptors of a wallet is not enough to follow the syncs of someone else.
+    let handle = Handle::current();
+    tokio::task::spawn_blocking(move || {
+        handle.block_on(async move {
+            if let Err(err) = sync_wallet_descriptors(request, Some(owner.clone())).await {
+                error!("Wallet sync failed: {err}");
+            }
+            SYNC_OWNERS.write().await.remove(&owner);
+            drop(permit);
+        })
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(WalletSyncResponse { wallets })))
+}
+
+async fn bitcoin_sync_events(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, StatusCode> {
+    info!("GET /bitcoin/sync/events");
+
+    let owner = sync_owner(&auth)?;
+    let events = stream::unfold(
+        (subscribe_sync_events(), owner),
+        |(mut events, owner)| async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) if event.job.as_deref() == Some(owner.as_str()) => {
+                        return Some((Event::default().json_data(event), (events, owner)));
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!("Sync event stream skipped {skipped} events");
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn fee_estimates() -> Result<impl IntoResponse, AppError> {
+    info!("GET /bitcoin/fees");
+
+    let fees_res = get_fee_estimates().await?;
+
+    Ok((StatusCode::OK, Json(fees_res)))
+}
+
+async fn frozen_utxos(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("GET /bitcoin/frozen");
+
+    let nostr_hex_sk = auth.token();
+    let frozen_res = list_frozen_utxos(nostr_hex_sk).await?;
+
+    Ok((StatusCode::OK, Json(frozen_res)))
+}
+
+async fn freeze(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<FreezeUtxosRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/frozen {request:?}");
+
+    let nostr_hex_sk = auth.token();
+    let frozen_res = freeze_utxos(nostr_hex_sk, request.utxos).await?;
+
+    Ok((StatusCode::OK, Json(frozen_res)))
+}
+
+async fn unfreeze(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<FreezeUtxosRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("DELETE /bitcoin/frozen {request:?}");
+
+    let nostr_hex_sk = auth.token();
+    let frozen_res = unfreeze_utxos(nostr_hex_sk, request.utxos).await?;
+
+    Ok((StatusCode::OK, Json(frozen_res)))
+}
+
+async fn sign_message(
+    TypedHeader(_auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<SignMessageRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/message/sign {:?}", request.address);
+
+    let sign_res = sign_bip322(request).await?;
+
+    Ok((StatusCode::OK, Json(sign_res)))
+}
+
+async fn verify_message(
+    Json(request): Json<VerifyMessageRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /bitcoin/message/verify {:?}", request.address);
+
+    let verify_res = verify_bip322(request)?;
+
+    Ok((StatusCode::OK, Json(verify_res)))
+}
+
+/// Seconds a payjoin receiver waits for the original PSBT of the sender
+const PAYJOIN_RECEIVER_TTL: i64 = 30 * 60;
+/// Payjoin receivers waiting at the same time, per owner and in total
+const PAYJOIN_RECEIVERS_PER_OWNER: usize = 10;
+const PAYJOIN_RECEIVERS_LIMIT: usize = 1_000;
+
+#[derive(Clone)]
+struct PayjoinReceiver {
+    sk: String,
+    request: PayjoinReceiverRequest,
+    expires_at: i64,
+}
+
+/// Payjoin receivers waiting for an original PSBT, indexed by the address of their BIP-21 URI.
+/// Receivers expire after `PAYJOIN_RECEIVER_TTL`, so their descriptors are not kept in memory.
+static PAYJOIN_RECEIVERS: Lazy<RwLock<HashMap<String, PayjoinReceiver>>> =
+    Lazy::new(Default::default);
+
+fn prune_payjoin_receivers(receivers: &mut HashMap<String, PayjoinReceiver>) {
+    let now = chrono::Utc::now().timestamp();
+    receivers.retain(|_, receiver| receiver.expires_at > now);
+}
+
+async fn payjoin_receiver(
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<PayjoinReceiverRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("POST /payjoin {:?}", request.amount);
+
+    let nostr_hex_sk = auth.token();
+    let endpoint = match env::var("PAYJOIN_ENDPOINT") {
+        Ok(endpoint) => endpoint,
+        Err(_) => format!("{}/payjoin", BITMASK_ENDPOINT.read().await),
+    };
+
+    {
+        let mut receivers = PAYJOIN_RECEIVERS.write().await;
+        prune_payjoin_receivers(&mut receivers);
+        if receivers.len() >= PAYJOIN_RECEIVERS_LIMIT {
+            return Err(anyhow::anyhow!("Too many payjoin receivers, try again later").into());
+        }
+
+        // The oldest receivers of the owner make room for the new one
+        let mut owned: Vec<(String, i64)> = receivers
+            .iter()
+            .filter(|(_, receiver)| receiver.sk == nostr_hex_sk)
+            .map(|(address, receiver)| (address.clone(), receiver.expires_at))
+            .collect();
+        owned.sort_by_key(|(_, expires_at)| *expires_at);
+        let excess = (owned.len() + 1).saturating_sub(PAYJOIN_RECEIVERS_PER_OWNER);
+        for (address, _) in owned.into_iter().take(excess) {
+            receivers.remove(&address);
+        }
+    }
+
+    let payjoin_res = create_payjoin_uri(
+        &request.descriptor,
+        &request.change_descriptor,
+        request.amount,
+        &endpoint,
+    )
+    .await?;
+
+    let mut receivers = PAYJOIN_RECEIVERS.write().await;
+    if let Some(receiver) = receivers.get(&payjoin_res.address) {
+        if receiver.sk != nostr_hex_sk {
+            return Err(anyhow::anyhow!("Address has a payjoin receiver of another owner").into());
+        }
+    }
+    receivers.insert(
+        payjoin_res.address.clone(),
+        PayjoinReceiver {
+            sk: nostr_hex_sk.to_owned(),
+            request,
+            expires_at: chrono::Utc::now().timestamp() + PAYJOIN_RECEIVER_TTL,
+        },
+    );
+
+    Ok((StatusCode::OK, Json(payjoin_res)))
+}
+
+async fn payjoin_original(
+    Path(address): Path<String>,
+    RawQuery(query): RawQuery,
+    body: Bytes,
+) -> Response {
+    info!("POST /payjoin/{address}");
+
+    let receiver = {
+        let mut receivers = PAYJOIN_RECEIVERS.write().await;
+        prune_payjoin_receivers(&mut receivers);
+        receivers.get(&address).cloned()
+    };
+    let PayjoinReceiver { sk, request, .. } = match receiver {
+        Some(receiver) => receiver,
+        None => return payjoin_error("unavailable", "No payjoin receiver for this address"),
+    };
+
+    let proposal = run_wallet_task(move || async move {
+        payjoin_proposal(
+            &sk,
+            &request.descriptor,
+            &request.change_descriptor,
+            &body,
+            &query.unwrap_or_default(),
+            request.coin_control,
+        )
+        .await
+    })
+    .await;
+
+    match proposal.and_then(|proposal| Ok(proposal?)) {
+        Ok(psbt) => {
+            PAYJOIN_RECEIVERS.write().await.remove(&address);
+            (StatusCode::OK, psbt).into_response()
+        }
+        Err(err) => {
+            error!("Payjoin to {address} failed: {err}");
+            payjoin_error("unavailable", &err.to_string())
+        }
+    }
+}
+
+/// Well-known BIP-78 error response
+fn payjoin_error(error_code: &str, message: &str) -> Response {
+    let body = serde_json::json!({ "errorCode": error_code, "message": message });
+    (StatusCode::BAD_REQUEST, Json(body)).into_response()
+}
+
 async fn co_store(
     Path((pk, name)): Path<(String, String)>,
     body: Bytes,
@@ -651,6 +1148,9 @@ async fn send_coins(
     Ok("Ok")
 }
 
+/// Seconds between two runs of the broadcast queues
+const BROADCAST_QUEUE_INTERVAL: u64 = 60;
+
 #[tokio::main]
 async fn main() -> Result<()> {
     if env::var("RUST_LOG").is_err() {
@@ -662,12 +1162,18 @@ async fn main() -> Result<()> {
     let mut app = Router::new()
         .route("/issue", post(issue))
         .route("/reissue", post(reissue))
+        .route("/inflate", post(inflate))
         .route("/selfissue", post(self_issue))
         .route("/invoice", post(invoice))
         .route("/selfinvoice", post(self_invoice))
         // .route("/psbt", post(psbt))
         // .route("/sign", post(sign_psbt))
         .route("/pay", post(pay))
+        .route("/batchpay", post(batch_pay))
+        .route("/airdrop", post(airdrop))
+        .route("/burn", post(burn))
+        .route("/burnproof", post(get_burn_proof))
+        .route("/verifyburn", post(verify_burn))
         .route("/selfpay", post(self_pay))
         .route("/accept", post(accept))
         .route("/selfaccept", post(self_accept))
@@ -689,6 +1195,23 @@ async fn main() -> Result<()> {
         .route("/transfers/:id", get(list_transfers))
         .route("/transfers/", post(save_transfer))
         .route("/transfers/", delete(remove_transfer))
+        .route("/bitcoin/bumpfee", post(bitcoin_bump_fee))
+        .route("/bitcoin/cpfp", post(bitcoin_cpfp))
+        .route("/bitcoin/fees", get(fee_estimates))
+        .route("/bitcoin/frozen", get(frozen_utxos))
+        .route("/bitcoin/frozen", post(freeze))
+        .route("/bitcoin/frozen", delete(unfreeze))
+        .route("/bitcoin/discover", post(bitcoin_discover))
+        .route("/bitcoin/vault/slots", post(bitcoin_vault_slots))
+        .route("/bitcoin/queue", post(bitcoin_queue))
+        .route("/bitcoin/queue/sign", post(bitcoin_sign_and_queue))
+        .route("/bitcoin/queue/status", post(bitcoin_queue_status))
+        .route("/bitcoin/sync", post(bitcoin_sync))
+        .route("/bitcoin/sync/events", get(bitcoin_sync_events))
+        .route("/bitcoin/message/sign", post(sign_message))
+        .route("/bitcoin/message/verify", post(verify_message))
+        .route("/payjoin", post(payjoin_receiver))
+        .route("/payjoin/:address", post(payjoin_original))
         .route("/key/:pk", get(key))
         .route("/carbonado/status", get(status))
         .route("/carbonado/server/:name", get(co_server_retrieve))
@@ -707,6 +1230,19 @@ async fn main() -> Result<()> {
             .route("/regtest/send/:address/:amount", get(send_coins));
     }
 
+    // Queued broadcasts are retried until their transactions confirm
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(Duration::from_secs(BROADCAST_QUEUE_INTERVAL));
+        loop {
+            interval.tick().await;
+            match run_wallet_task(process_broadcast_queues).await {
+                Ok(Err(err)) => error!("Broadcast queue processing failed: {err}"),
+                Err(err) => error!("Broadcast queue task failed: {err}"),
+                Ok(Ok(())) => {}
+            }
+        }
+    });
+
     let app = app.layer(CorsLayer::permissive());
     let addr = SocketAddr::from(([0, 0, 0, 0], 7070));
 
@@ -719,6 +1255,18 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// BDK wallet syncs keep a `RefCell` borrowed across awaits, so their futures are not `Send`
+/// and cannot run on the handler task. They are driven to completion on a blocking thread.
+async fn run_wallet_task<F, Fut, T>(task: F) -> Result<T>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = T>,
+    T: Send + 'static,
+{
+    let handle = Handle::current();
+    Ok(tokio::task::spawn_blocking(move || handle.block_on(task())).await?)
+}
+
 // https://github.com/tokio-rs/axum/blob/fef95bf37a138cdf94985e17f27fd36481525171/examples/anyhow-error-response/src/main.rs
 // Make our own error that wraps `anyhow::Error`.
 struct AppError(anyhow::Error);
@@ -4,8 +4,11 @@ use ::bitcoin::util::address::Address;
 use ::psbt::Psbt;
 use amplify::hex::ToHex;
 use argon2::Argon2;
-use bdk::{wallet::AddressIndex, FeeRate, LocalUtxo, SignOptions, TransactionDetails};
-use bitcoin::{consensus::encode, psbt::PartiallySignedTransaction};
+use bdk::{
+    database::Database, wallet::AddressIndex, FeeRate, KeychainKind, LocalUtxo, SignOptions,
+    TransactionDetails,
+};
+use bitcoin::{consensus::encode, psbt::PartiallySignedTransaction, Script, Transaction, Txid};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde_encrypt::{
     serialize::impls::BincodeSerializer, shared_key::SharedKey, traits::SerdeEncryptSharedKey,
@@ -15,31 +18,84 @@ use thiserror::Error;
 use zeroize::Zeroize;
 
 mod assets;
+mod broadcast;
+mod chain;
+mod coins;
+mod fees;
 mod keys;
+mod labels;
+mod message;
 mod payment;
 mod psbt;
+mod slip39;
+mod vault;
 mod wallet;
 
 pub use crate::bitcoin::{
     assets::dust_tx,
-    keys::{new_mnemonic, save_mnemonic, BitcoinKeysError},
-    payment::{create_payjoin, create_transaction, BitcoinPaymentError},
+    broadcast::{
+        process_broadcast_queue, process_broadcast_queues, queue_transaction, replace_transaction,
+        BitcoinBroadcastError,
+    },
+    chain::{
+        broadcast_tx, chain_backend, BitcoinChainError, ChainBackend, ChainOutputStatus,
+        ChainScriptTx, ChainTxStatus,
+    },
+    coins::{BitcoinCoinsError, UtxoSelection},
+    fees::{
+        check_fee_rate, estimate_fee_rate, get_fee_estimates, resolve_fee_rate, resolve_psbt_fee,
+        BitcoinFeesError, MAX_FEE_RATE, MIN_RELAY_FEE_RATE,
+    },
+    keys::{derive_bip85, get_account, get_bip85, new_mnemonic, save_mnemonic, BitcoinKeysError},
+    labels::{format_labels, parse_labels, BitcoinLabelsError},
+    message::{sign_message, verify_message, BitcoinMessageError},
+    payment::{
+        build_transaction, create_cpfp, create_payjoin, create_transaction, receive_payjoin,
+        BitcoinPaymentError,
+    },
     psbt::{
+        finalize_and_publish_psbt, finalize_psbt, multi_sign_and_finalize_psbt,
         multi_sign_and_publish_psbt, multi_sign_psbt, sign_and_publish_psbt, sign_psbt,
         BitcoinPsbtError,
     },
+    slip39::{recover_mnemonic, split_mnemonic, BitcoinSlip39Error},
+    vault::{compile_vault_descriptor, recover_vault, BitcoinVaultError},
     wallet::{
-        get_blockchain, get_wallet, sync_wallet, sync_wallets, BitcoinWalletError, MemoryWallet,
+        discovered_keychains, get_blockchain, get_wallet, subscribe_sync_events, sync_wallet,
+        sync_wallet_descriptors, sync_wallet_with_backend, sync_wallet_with_gap, sync_wallets,
+        wallet_key, BitcoinWalletError, WalletHandle,
     },
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::bitcoin::chain::{chain_source, get_electrum, ChainSource};
+
 use crate::{
-    bitcoin::keys::get_marketplace_descriptor,
-    constants::{DIBA_DESCRIPTOR, DIBA_DESCRIPTOR_VERSION, DIBA_MAGIC_NO, NETWORK},
+    bitcoin::{
+        coins::{parse_outpoint, retrieve_frozen_utxos, store_frozen_utxos},
+        keys::get_marketplace_descriptor,
+        labels::{merge_labels, retrieve_labels, store_labels, wallet_id},
+    },
+    constants::{
+        DIBA_ARGON2_MAX_M_COST, DIBA_ARGON2_MAX_P_COST, DIBA_ARGON2_MAX_T_COST, DIBA_ARGON2_M_COST,
+        DIBA_ARGON2_P_COST, DIBA_ARGON2_T_COST, DIBA_DESCRIPTOR, DIBA_DESCRIPTOR_VERSION,
+        DIBA_DESCRIPTOR_VERSION_ARGON2, DIBA_MAGIC_NO, DIBA_SALT_LEN, DISCOVERY_GAP_LIMIT, NETWORK,
+    },
     debug, info,
+    rgb::{
+        constants::RGB_DEFAULT_NAME, list_rgb_utxos, watcher_discovery, watcher_next_index,
+        WatcherError,
+    },
     structs::{
-        DecryptedWalletData, EncryptedWalletDataV04, FundVaultDetails, PublishedPsbtResponse,
-        SatsInvoice, SecretString, SignPsbtRequest, SignedPsbtResponse, WalletData,
+        AssetType, BroadcastQueueRequest, BroadcastQueueResponse, BumpFeeResponse, CoinControl,
+        ConfirmPreviewRequest, CpfpResponse, DecryptedWalletData, DecryptedWalletDataV06,
+        EncryptedWalletDataV04, FeeRateRequest, FrozenUtxosResponse, FundVaultDetails,
+        FundVaultPreview, PayjoinUriResponse, PreviewInput, PreviewOutput, PublishPsbtRequest,
+        PublishedPsbtResponse, QueuedTransaction, SatsInvoice, SecretString, SignMessageRequest,
+        SignMessageResponse, SignPsbtRequest, SignedPsbtResponse, TransactionPreview,
+        UnsignedFundVaultResponse, UnsignedPsbtResponse, VaultRequest, VaultResponse,
+        VaultSlotOutpoints, VaultUtxoPlanRequest, VaultUtxoPlanResponse, VerifyMessageRequest,
+        VerifyMessageResponse, WalletData, WalletDiscoveryRequest, WalletDiscoveryResponse,
         WalletTransaction,
     },
     trace,
@@ -47,11 +103,19 @@ use crate::{
 
 // Minimum amount of satoshis to funding vault
 const MIN_FUNDS_SATS: u64 = 10000;
+// Minimum amount of satoshis of a vault slot (dust limit)
+const MIN_VAULT_SLOT_SATS: u64 = 546;
+/// Selector of the account stored in the top level of the wallet data (index 0)
+pub const DEFAULT_ACCOUNT: &str = "default";
 
 impl SerdeEncryptSharedKey for DecryptedWalletData {
     type S = BincodeSerializer<Self>;
 }
 
+impl SerdeEncryptSharedKey for DecryptedWalletDataV06 {
+    type S = BincodeSerializer<Self>;
+}
+
 impl SerdeEncryptSharedKey for EncryptedWalletDataV04 {
     type S = BincodeSerializer<Self>;
 }
@@ -66,6 +130,9 @@ pub enum BitcoinError {
     /// Wrong Encrypted Descriptor Version
     #[error("Wrong Version: Encrypted descriptor is the wrong version. The version byte was: {0}")]
     WrongEncryptedDescriptorVersion(u8),
+    /// Invalid key derivation parameters in the encrypted descriptor header
+    #[error("Invalid encrypted descriptor key derivation parameters: {0}")]
+    InvalidKdfParams(String),
     #[error("Wrong Descriptor: Wallet supports only taproot descriptor")]
     WrongDescriptor,
     /// Upgrade unnecessary
@@ -80,18 +147,66 @@ pub enum BitcoinError {
     /// Drain wallet unable to finalize PSBT
     #[error("Drain wallet was unable to finalize PSBT")]
     DrainWalletUnfinalizedPsbt,
-    /// Drain wallet was unable to find tx details
-    #[error("No wallet transaction details were found when draining wallet")]
-    DrainWalletNoTxDetails,
+    /// Transaction not found in wallet
+    #[error("Transaction {0} was not found in the wallet")]
+    TransactionNotFound(String),
+    /// Transaction already confirmed
+    #[error("Transaction {0} is already confirmed and cannot be replaced")]
+    TransactionAlreadyConfirmed(String),
+    /// Invalid payjoin endpoint
+    #[error("Invalid payjoin BIP-21 URI: {0}")]
+    InvalidPayjoinUri(String),
+    /// Account not found in the wallet
+    #[error("Account {0} was not found in the wallet")]
+    InvalidAccount(String),
+    /// Discovery needs at least one unused address to stop
+    #[error("Gap limit must be at least 1")]
+    InvalidGapLimit,
+    /// Asset type without RGB vault
+    #[error("Asset type {0} has no RGB vault")]
+    InvalidVaultAssetType(String),
+    /// Vault slot value below dust
+    #[error("Vault slot of {0} sats is below the minimum of {1} sats")]
+    VaultSlotBelowDust(u64, u64),
+    /// Empty UTXO plan
+    #[error("No vault slots were requested")]
+    NoVaultSlots,
+    /// Account name or index already used
+    #[error("Account {0} or index {1} is already used in the wallet")]
+    AccountExists(String, u32),
+    /// BitMask Core Bitcoin Broadcast error
+    #[error(transparent)]
+    BitcoinBroadcastError(#[from] BitcoinBroadcastError),
+    /// BitMask Core Bitcoin Chain error
+    #[error(transparent)]
+    BitcoinChainError(#[from] BitcoinChainError),
+    /// BitMask Core Bitcoin Coins error
+    #[error(transparent)]
+    BitcoinCoinsError(#[from] BitcoinCoinsError),
+    /// BitMask Core Bitcoin Fees error
+    #[error(transparent)]
+    BitcoinFeesError(#[from] BitcoinFeesError),
     /// BitMask Core Bitcoin Keys error
     #[error(transparent)]
     BitcoinKeysError(#[from] BitcoinKeysError),
+    /// BitMask Core Bitcoin Labels error
+    #[error(transparent)]
+    BitcoinLabelsError(#[from] BitcoinLabelsError),
+    /// BitMask Core Bitcoin Message error
+    #[error(transparent)]
+    BitcoinMessageError(#[from] BitcoinMessageError),
     /// BitMask Core Bitcoin Payment error
     #[error(transparent)]
     BitcoinPaymentError(#[from] BitcoinPaymentError),
     /// BitMask Core Bitcoin Psbt error
     #[error(transparent)]
     BitcoinPsbtError(#[from] BitcoinPsbtError),
+    /// BitMask Core Bitcoin SLIP-39 error
+    #[error(transparent)]
+    BitcoinSlip39Error(#[from] BitcoinSlip39Error),
+    /// BitMask Core Bitcoin Vault error
+    #[error(transparent)]
+    BitcoinVaultError(#[from] BitcoinVaultError),
     /// BitMask Core Bitcoin Wallet error
     #[error(transparent)]
     BitcoinWalletError(#[from] BitcoinWalletError),
@@ -110,9 +225,15 @@ pub enum BitcoinError {
     /// Bitcoin address error
     #[error(transparent)]
     BitcoinAddressError(#[from] bitcoin::util::address::Error),
+    /// Bitcoin hex decode error
+    #[error(transparent)]
+    BitcoinHexError(#[from] bitcoin::hashes::hex::Error),
     /// PSBT decode error
     #[error(transparent)]
     BitcoinPsbtDecodeError(#[from] bitcoin::consensus::encode::Error),
+    /// RGB watcher error
+    #[error(transparent)]
+    RgbWatcherError(#[from] WatcherError),
 }
 
 /// Bitcoin Wallet Operations
@@ -135,53 +256,174 @@ pub fn hash_password(password: &SecretString) -> SecretString {
     hash
 }
 
+/// Key derivation parameters stored in the header of encrypted descriptors since
+/// `DIBA_DESCRIPTOR_VERSION_ARGON2`, following the magic number and version byte
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescriptorKdfParams {
+    pub salt: [u8; DIBA_SALT_LEN],
+    /// Argon2 memory cost (KiB)
+    pub m_cost: u32,
+    /// Argon2 iterations
+    pub t_cost: u32,
+    /// Argon2 parallelism
+    pub p_cost: u32,
+}
+
+impl DescriptorKdfParams {
+    const LEN: usize = DIBA_SALT_LEN + 12;
+
+    /// Random salt with the current Argon2 costs
+    pub fn generate() -> Self {
+        let mut salt = [0u8; DIBA_SALT_LEN];
+        StdRng::from_entropy().fill(&mut salt);
+
+        Self {
+            salt,
+            m_cost: DIBA_ARGON2_M_COST,
+            t_cost: DIBA_ARGON2_T_COST,
+            p_cost: DIBA_ARGON2_P_COST,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.salt.to_vec();
+        bytes.extend_from_slice(&self.m_cost.to_le_bytes());
+        bytes.extend_from_slice(&self.t_cost.to_le_bytes());
+        bytes.extend_from_slice(&self.p_cost.to_le_bytes());
+        bytes
+    }
+
+    /// Parses the parameters, returning them with the encrypted message that follows
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), BitcoinError> {
+        if bytes.len() < Self::LEN {
+            return Err(BitcoinError::InvalidKdfParams(
+                "header is truncated".to_owned(),
+            ));
+        }
+        let (params, encrypted_message) = bytes.split_at(Self::LEN);
+        let (salt, costs) = params.split_at(DIBA_SALT_LEN);
+        let cost = |i: usize| u32::from_le_bytes(costs[i * 4..i * 4 + 4].try_into().unwrap());
+
+        let kdf = Self {
+            salt: salt.try_into().expect("salt is of fixed size"),
+            m_cost: cost(0),
+            t_cost: cost(1),
+            p_cost: cost(2),
+        };
+
+        // Costs come from untrusted input, so they are bounded before Argon2 allocates memory
+        if kdf.m_cost > DIBA_ARGON2_MAX_M_COST
+            || kdf.t_cost > DIBA_ARGON2_MAX_T_COST
+            || kdf.p_cost > DIBA_ARGON2_MAX_P_COST
+        {
+            return Err(BitcoinError::InvalidKdfParams(format!(
+                "costs m={}, t={}, p={} exceed the maximum of m={DIBA_ARGON2_MAX_M_COST}, t={DIBA_ARGON2_MAX_T_COST}, p={DIBA_ARGON2_MAX_P_COST}",
+                kdf.m_cost, kdf.t_cost, kdf.p_cost
+            )));
+        }
+
+        Ok((kdf, encrypted_message))
+    }
+
+    /// Stretches the password hash with the salt and costs of the wallet into its encryption key
+    fn derive_key(&self, hash: &SecretString) -> Result<[u8; 32], BitcoinError> {
+        use argon2::{Algorithm, Params, Version};
+
+        let mut password = hex::decode(&hash.0)?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|err| BitcoinError::InvalidKdfParams(err.to_string()))?;
+
+        let mut key = [0u8; 32];
+        let result = Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(&password, &self.salt, &mut key);
+        password.zeroize();
+        result.map_err(|err| BitcoinError::InvalidKdfParams(err.to_string()))?;
+
+        Ok(key)
+    }
+}
+
 pub fn decrypt_wallet(
     hash: &SecretString,
     encrypted_descriptors: &SecretString,
 ) -> Result<DecryptedWalletData, BitcoinError> {
-    let mut shared_key: [u8; 32] = hex::decode(&hash.0)?
-        .try_into()
-        .expect("hash is of fixed size");
     let encrypted_descriptors: Vec<u8> = hex::decode(&encrypted_descriptors.0)?;
-    let (version_prefix, encrypted_descriptors) = encrypted_descriptors.split_at(5);
 
-    if !version_prefix.starts_with(&DIBA_MAGIC_NO) {
+    if encrypted_descriptors.len() < DIBA_DESCRIPTOR.len()
+        || !encrypted_descriptors.starts_with(&DIBA_MAGIC_NO)
+    {
+        let prefix =
+            &encrypted_descriptors[..encrypted_descriptors.len().min(DIBA_DESCRIPTOR.len())];
         return Err(BitcoinError::WrongEncryptedDescriptorMagicNo(format!(
-            "{version_prefix:#?}"
+            "{prefix:#?}"
         )));
     }
 
-    if version_prefix[4] != DIBA_DESCRIPTOR_VERSION {
-        return Err(BitcoinError::WrongEncryptedDescriptorVersion(
-            version_prefix[4],
-        ));
-    }
+    let (version_prefix, encrypted_descriptors) =
+        encrypted_descriptors.split_at(DIBA_DESCRIPTOR.len());
+
+    let (mut shared_key, encrypted_descriptors) = match version_prefix[4] {
+        DIBA_DESCRIPTOR_VERSION => {
+            let shared_key: [u8; 32] = hex::decode(&hash.0)?
+                .try_into()
+                .expect("hash is of fixed size");
+            (shared_key, encrypted_descriptors)
+        }
+        DIBA_DESCRIPTOR_VERSION_ARGON2 => {
+            let (kdf, encrypted_descriptors) =
+                DescriptorKdfParams::from_bytes(encrypted_descriptors)?;
+            (kdf.derive_key(hash)?, encrypted_descriptors)
+        }
+        version => return Err(BitcoinError::WrongEncryptedDescriptorVersion(version)),
+    };
 
     let encrypted_message = EncryptedMessage::deserialize(encrypted_descriptors.to_owned())?;
 
     let decrypted_wallet_data =
-        DecryptedWalletData::decrypt_owned(&encrypted_message, &SharedKey::from_array(shared_key))?;
+        decrypt_wallet_data(&encrypted_message, &SharedKey::from_array(shared_key));
 
     shared_key.zeroize();
 
-    Ok(decrypted_wallet_data)
+    Ok(decrypted_wallet_data?)
+}
+
+/// Decrypts the wallet data, also when encrypted before named accounts
+fn decrypt_wallet_data(
+    encrypted_message: &EncryptedMessage,
+    shared_key: &SharedKey,
+) -> Result<DecryptedWalletData, serde_encrypt::Error> {
+    DecryptedWalletData::decrypt_owned(encrypted_message, shared_key).or_else(|_| {
+        DecryptedWalletDataV06::decrypt_owned(encrypted_message, shared_key)
+            .map(DecryptedWalletData::from)
+    })
 }
 
+/// Migrates encrypted descriptors to the latest version. Descriptors of the previous
+/// version are re-encrypted as is, older unversioned ones are recovered from their mnemonic.
 pub async fn upgrade_wallet(
     hash: &SecretString,
     encrypted_descriptors: &SecretString,
     seed_password: &SecretString,
 ) -> Result<SecretString, BitcoinError> {
+    let encrypted_bytes: Vec<u8> = hex::decode(&encrypted_descriptors.0)?;
+
+    if encrypted_bytes.starts_with(&DIBA_MAGIC_NO) {
+        if encrypted_bytes.get(4) == Some(&DIBA_DESCRIPTOR_VERSION_ARGON2) {
+            return Err(BitcoinError::UpgradeUnnecessary);
+        }
+
+        let wallet_data = decrypt_wallet(hash, encrypted_descriptors)?;
+        return encrypt_wallet_data(&wallet_data, hash);
+    }
+
     // read hash digest and consume hasher
     let shared_key: [u8; 32] = hex::decode(&hash.0)?
         .try_into()
         .expect("hash is of fixed size");
-    let encrypted_descriptors: Vec<u8> = hex::decode(&encrypted_descriptors.0)?;
-    let encrypted_message = EncryptedMessage::deserialize(encrypted_descriptors)?;
+    let encrypted_message = EncryptedMessage::deserialize(encrypted_bytes)?;
 
-    match DecryptedWalletData::decrypt_owned(&encrypted_message, &SharedKey::from_array(shared_key))
-    {
-        Ok(_data) => Err(BitcoinError::UpgradeUnnecessary),
+    match decrypt_wallet_data(&encrypted_message, &SharedKey::from_array(shared_key)) {
+        Ok(wallet_data) => encrypt_wallet_data(&wallet_data, hash),
         Err(_err) => {
             // If there's a deserialization error, attempt to recover just the mnemnonic.
             let recovered_wallet_data = EncryptedWalletDataV04::decrypt_owned(
@@ -190,7 +432,6 @@ pub async fn upgrade_wallet(
             )?;
 
             // println!("Recovered wallet data: {recovered_wallet_data:?}"); // Keep commented out for security
-            // todo!("Add later version migrations here");
 
             let upgraded_descriptor = encrypt_wallet(
                 &SecretString(recovered_wallet_data.mnemonic),
@@ -204,8 +445,13 @@ pub async fn upgrade_wallet(
     }
 }
 
-pub fn versioned_descriptor(encrypted_message: EncryptedMessage) -> SecretString {
-    let mut descriptor_data = DIBA_DESCRIPTOR.to_vec();
+pub fn versioned_descriptor(
+    kdf: &DescriptorKdfParams,
+    encrypted_message: EncryptedMessage,
+) -> SecretString {
+    let mut descriptor_data = DIBA_MAGIC_NO.to_vec();
+    descriptor_data.push(DIBA_DESCRIPTOR_VERSION_ARGON2);
+    descriptor_data.append(&mut kdf.to_bytes());
     let mut encrypted_descriptors = encrypted_message.serialize();
     descriptor_data.append(&mut encrypted_descriptors);
 
@@ -216,19 +462,25 @@ pub fn versioned_descriptor(encrypted_message: EncryptedMessage) -> SecretString
     encrypted
 }
 
+/// Encrypts the wallet data in the latest descriptor version, with a fresh salt
+fn encrypt_wallet_data(
+    wallet_data: &DecryptedWalletData,
+    hash: &SecretString,
+) -> Result<SecretString, BitcoinError> {
+    let kdf = DescriptorKdfParams::generate();
+    let mut shared_key = kdf.derive_key(hash)?;
+    let encrypted_message = wallet_data.encrypt(&SharedKey::from_array(shared_key));
+    shared_key.zeroize();
+
+    Ok(versioned_descriptor(&kdf, encrypted_message?))
+}
+
 pub async fn new_wallet(
     hash: &SecretString,
     seed_password: &SecretString,
 ) -> Result<SecretString, BitcoinError> {
-    let mut shared_key: [u8; 32] = hex::decode(&hash.0)?
-        .try_into()
-        .expect("hash is of fixed size");
     let wallet_data = new_mnemonic(seed_password).await?;
-    let encrypted_message = wallet_data.encrypt(&SharedKey::from_array(shared_key))?;
-    let encrypted_descriptors = versioned_descriptor(encrypted_message);
-
-    shared_key.zeroize();
-    Ok(encrypted_descriptors)
+    encrypt_wallet_data(&wallet_data, hash)
 }
 
 pub async fn encrypt_wallet(
@@ -236,24 +488,132 @@ pub async fn encrypt_wallet(
     hash: &SecretString,
     seed_password: &SecretString,
 ) -> Result<SecretString, BitcoinError> {
-    let shared_key: [u8; 32] = hex::decode(&hash.0)?
-        .try_into()
-        .expect("hash is of fixed size");
-
     let wallet_data = save_mnemonic(mnemonic_phrase, seed_password).await?;
-    let encrypted_message = wallet_data.encrypt(&SharedKey::from_array(shared_key))?;
-    let encrypted_descriptors = versioned_descriptor(encrypted_message);
-    Ok(encrypted_descriptors)
+    encrypt_wallet_data(&wallet_data, hash)
 }
 
+/// Adds a named account to the encrypted wallet, derived at the account index
+pub async fn add_account(
+    hash: &SecretString,
+    encrypted_descriptors: &SecretString,
+    seed_password: &SecretString,
+    name: &str,
+    index: u32,
+) -> Result<SecretString, BitcoinError> {
+    let mut wallet_data = decrypt_wallet(hash, encrypted_descriptors)?;
+
+    if name.is_empty() || name == DEFAULT_ACCOUNT {
+        return Err(BitcoinError::InvalidAccount(name.to_owned()));
+    }
+    if index == 0
+        || wallet_data
+            .accounts
+            .iter()
+            .any(|account| account.name == name || account.index == index)
+    {
+        return Err(BitcoinError::AccountExists(name.to_owned(), index));
+    }
+
+    let account = get_account(
+        &SecretString(wallet_data.mnemonic.clone()),
+        seed_password,
+        name,
+        index,
+    )
+    .await?;
+    wallet_data.accounts.push(account);
+
+    encrypt_wallet_data(&wallet_data, hash)
+}
+
+/// Wallet data with the private and public data of the selected account in place of the
+/// default one. Accounts are selected by name, or by index.
+pub fn select_account(
+    wallet_data: &DecryptedWalletData,
+    account: Option<&str>,
+) -> Result<DecryptedWalletData, BitcoinError> {
+    let mut selected = wallet_data.clone();
+
+    let account = match account {
+        None | Some(DEFAULT_ACCOUNT) | Some("0") => return Ok(selected),
+        Some(account) => wallet_data
+            .accounts
+            .iter()
+            .find(|data| data.name == account || data.index.to_string() == account)
+            .ok_or_else(|| BitcoinError::InvalidAccount(account.to_owned()))?,
+    };
+
+    selected.private = account.private.clone();
+    selected.public = account.public.clone();
+
+    Ok(selected)
+}
+
+/// Restores the history of a wallet and its RGB watcher. Each BTC keychain and RGB terminal is
+/// scanned until `gap_limit` consecutive addresses are unused, so funds received past the
+/// regular sync window are found.
+pub async fn discover_wallet(
+    sk: &str,
+    request: WalletDiscoveryRequest,
+) -> Result<WalletDiscoveryResponse, BitcoinError> {
+    let gap_limit = request.gap_limit.unwrap_or(DISCOVERY_GAP_LIMIT);
+    if gap_limit == 0 {
+        return Err(BitcoinError::InvalidGapLimit);
+    }
+
+    let wallet = get_wallet(&request.descriptor, Some(&request.change_descriptor)).await?;
+    sync_wallet_with_gap(&wallet, gap_limit).await?;
+    let bitcoin = discovered_keychains(&wallet, gap_limit).await?;
+
+    let name = request
+        .watcher_name
+        .unwrap_or_else(|| RGB_DEFAULT_NAME.to_owned());
+    let rgb = match watcher_discovery(sk, &name, gap_limit).await {
+        Ok(rgb) => rgb,
+        Err(WatcherError::NoWatcher) => vec![],
+        Err(err) => return Err(err.into()),
+    };
+
+    let used: u32 = bitcoin
+        .iter()
+        .chain(&rgb)
+        .map(|keychain| keychain.used_addresses)
+        .sum();
+    info!(format!(
+        "Discovered {used} used addresses with a gap of {gap_limit}"
+    ));
+
+    Ok(WalletDiscoveryResponse {
+        gap_limit,
+        bitcoin,
+        rgb,
+    })
+}
+
+/// Wallet data with the labels of the wallet attached
 pub async fn get_wallet_data(
+    sk: &str,
     descriptor: &SecretString,
     change_descriptor: Option<&SecretString>,
 ) -> Result<WalletData, BitcoinError> {
     info!("get_wallet_data");
 
     let wallet = get_wallet(descriptor, change_descriptor).await?;
-    sync_wallet(&wallet).await?;
+    let mut wallet_data = wallet_data(&wallet).await?;
+
+    let labels = retrieve_labels(sk, &wallet_id(&wallet).await?).await?;
+    for tx in wallet_data.transactions.iter_mut() {
+        tx.label = labels
+            .get(&(LabelType::Tx, tx.txid.to_string()))
+            .and_then(|label| label.label.clone());
+    }
+    wallet_data.labels = labels.into_values().collect();
+
+    Ok(wallet_data)
+}
+
+async fn wallet_data(wallet: &WalletHandle) -> Result<WalletData, BitcoinError> {
+    sync_wallet(wallet).await?;
 
     let address = wallet
         .lock()
@@ -290,6 +650,7 @@ pub async fn get_wallet_data(
             fee: tx.fee,
             confirmed: tx.confirmation_time.is_some(),
             confirmation_time: tx.confirmation_time,
+            label: None,
         })
         .collect();
 
@@ -300,9 +661,85 @@ pub async fn get_wallet_data(
         balance,
         transactions,
         utxos,
+        labels: vec![],
     })
 }
 
+/// Creates the wallet of a timelocked vault, see `compile_vault_descriptor`
+pub async fn create_vault(request: VaultRequest) -> Result<VaultResponse, BitcoinError> {
+    let descriptor =
+        compile_vault_descriptor(&request.primary, &request.recovery, request.timelock)?;
+    let wallet = get_wallet(&descriptor, None).await?;
+    let wallet = wallet_data(&wallet).await?;
+
+    Ok(VaultResponse { descriptor, wallet })
+}
+
+pub async fn list_wallet_labels(
+    sk: &str,
+    descriptor: &SecretString,
+) -> Result<WalletLabelsResponse, BitcoinError> {
+    let wallet = get_wallet(descriptor, None).await?;
+    let labels = retrieve_labels(sk, &wallet_id(&wallet).await?).await?;
+
+    Ok(WalletLabelsResponse {
+        labels: labels.into_values().collect(),
+    })
+}
+
+/// Inserts, replaces or removes labels. The spendable flag of output labels
+/// freezes or unfreezes the output.
+pub async fn set_wallet_labels(
+    sk: &str,
+    descriptor: &SecretString,
+    labels: Vec<WalletLabel>,
+) -> Result<WalletLabelsResponse, BitcoinError> {
+    let wallet = get_wallet(descriptor, None).await?;
+    let wallet_id = wallet_id(&wallet).await?;
+
+    let mut frozen = retrieve_frozen_utxos(sk).await?;
+    for label in labels.iter().filter(|l| l.label_type == LabelType::Output) {
+        let outpoint = parse_outpoint(&label.reference)?.to_string();
+        match label.spendable {
+            Some(false) => frozen.utxos.insert(outpoint),
+            Some(true) => frozen.utxos.remove(&outpoint),
+            None => false,
+        };
+    }
+    store_frozen_utxos(sk, &frozen).await?;
+
+    let mut wallet_labels = retrieve_labels(sk, &wallet_id).await?;
+    merge_labels(&mut wallet_labels, labels);
+    store_labels(sk, &wallet_id, &wallet_labels).await?;
+
+    Ok(WalletLabelsResponse {
+        labels: wallet_labels.into_values().collect(),
+    })
+}
+
+/// Imports labels in the BIP-329 format (JSON Lines)
+pub async fn import_wallet_labels(
+    sk: &str,
+    descriptor: &SecretString,
+    jsonl: &str,
+) -> Result<WalletLabelsResponse, BitcoinError> {
+    let labels = parse_labels(jsonl)?;
+    info!(format!("Importing {} labels", labels.len()));
+
+    set_wallet_labels(sk, descriptor, labels).await
+}
+
+/// Exports labels in the BIP-329 format (JSON Lines)
+pub async fn export_wallet_labels(
+    sk: &str,
+    descriptor: &SecretString,
+) -> Result<String, BitcoinError> {
+    let wallet = get_wallet(descriptor, None).await?;
+    let labels = retrieve_labels(sk, &wallet_id(&wallet).await?).await?;
+
+    Ok(format_labels(labels.values())?)
+}
+
 pub async fn get_swap_new_address() -> Result<Option<String>, BitcoinError> {
     info!("get_swap_new_address");
 
@@ -335,17 +772,80 @@ pub async fn validate_address(address: &Address) -> Result<(), BitcoinError> {
     }
 }
 
+/// Coin selection of the caller, never spending the frozen UTXOs of the wallet nor the
+/// outpoints holding RGB allocations
+async fn utxo_selection(
+    sk: &str,
+    coin_control: &CoinControl,
+) -> Result<UtxoSelection, BitcoinError> {
+    let mut frozen = retrieve_frozen_utxos(sk).await?.utxos;
+    frozen.extend(list_rgb_utxos(sk).await?);
+
+    let selection = UtxoSelection::new(coin_control, &frozen)?;
+    debug!(format!("Coin selection: {selection:#?}"));
+
+    Ok(selection)
+}
+
+/// Send sats without spending frozen UTXOs, including every outpoint holding RGB allocations
 pub async fn send_sats(
+    sk: &str,
     descriptor: &SecretString,
     change_descriptor: &SecretString,
     destination: &str, // bip21 uri or address
     amount: u64,
-    fee_rate: Option<f32>,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<TransactionDetails, BitcoinError> {
+    send_sats_with_coin_control(
+        sk,
+        descriptor,
+        change_descriptor,
+        destination,
+        amount,
+        fee_rate,
+        CoinControl::default(),
+    )
+    .await
+}
+
+/// Send sats honoring the coin control of the caller and the frozen UTXOs of the wallet,
+/// including every outpoint holding RGB allocations.
+pub async fn send_sats_with_coin_control(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    destination: &str, // bip21 uri or address
+    amount: u64,
+    fee_rate: Option<FeeRateRequest>,
+    coin_control: CoinControl,
+) -> Result<TransactionDetails, BitcoinError> {
+    let selection = utxo_selection(sk, &coin_control).await?;
+
+    internal_send_sats(
+        sk,
+        descriptor,
+        change_descriptor,
+        destination,
+        amount,
+        fee_rate,
+        &selection,
+    )
+    .await
+}
+
+async fn internal_send_sats(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    destination: &str,
+    amount: u64,
+    fee_rate: Option<FeeRateRequest>,
+    selection: &UtxoSelection,
 ) -> Result<TransactionDetails, BitcoinError> {
     use payjoin::UriExt;
 
     let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
-    let fee_rate = fee_rate.map(FeeRate::from_sat_per_vb);
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
 
     let transaction = match payjoin::Uri::try_from(destination) {
         Ok(uri) => {
@@ -353,38 +853,394 @@ pub async fn send_sats(
             validate_address(&address).await?;
             if let Ok(pj_uri) = uri.check_pj_supported() {
                 create_payjoin(
+                    sk,
                     vec![SatsInvoice { address, amount }],
                     &wallet,
                     fee_rate,
                     pj_uri,
+                    Some(selection),
                 )
                 .await?
             } else {
-                create_transaction(vec![SatsInvoice { address, amount }], &wallet, fee_rate).await?
+                create_transaction(
+                    sk,
+                    vec![SatsInvoice { address, amount }],
+                    &wallet,
+                    fee_rate,
+                    Some(selection),
+                )
+                .await?
             }
         }
         _ => {
             let address = Address::from_str(destination)?;
             validate_address(&address).await?;
-            create_transaction(vec![SatsInvoice { address, amount }], &wallet, fee_rate).await?
+            create_transaction(
+                sk,
+                vec![SatsInvoice { address, amount }],
+                &wallet,
+                fee_rate,
+                Some(selection),
+            )
+            .await?
         }
     };
 
     Ok(transaction)
 }
 
+/// BIP-21 URI requesting a payjoin to a new address of the wallet. The original PSBT
+/// is expected at `{endpoint}/{address}`.
+pub async fn create_payjoin_uri(
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    amount: Option<u64>,
+    endpoint: &str,
+) -> Result<PayjoinUriResponse, BitcoinError> {
+    use payjoin::UriExt;
+
+    let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
+    let address = wallet.lock().await.get_address(AddressIndex::New)?;
+
+    let pj = format!("{}/{address}", endpoint.trim_end_matches('/'));
+    let uri = match amount {
+        Some(amount) => {
+            let amount =
+                ::bitcoin::Amount::from_sat(amount).to_string_in(::bitcoin::Denomination::Bitcoin);
+            format!("bitcoin:{address}?amount={amount}&pj={pj}")
+        }
+        None => format!("bitcoin:{address}?pj={pj}"),
+    };
+
+    payjoin::Uri::try_from(uri.as_str())
+        .map_err(|err| BitcoinError::InvalidPayjoinUri(err.to_string()))?
+        .check_pj_supported()
+        .map_err(|_| BitcoinError::InvalidPayjoinUri(uri.clone()))?;
+    info!(format!("Payjoin URI: {uri}"));
+
+    Ok(PayjoinUriResponse {
+        address: address.to_string(),
+        uri,
+    })
+}
+
+/// Answer the original PSBT of a payjoin sender with a proposal PSBT. Only the UTXOs allowed
+/// by the coin control are contributed, never frozen ones nor those holding RGB allocations.
+pub async fn payjoin_proposal(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    body: &[u8],
+    query: &str,
+    coin_control: CoinControl,
+) -> Result<String, BitcoinError> {
+    let selection = utxo_selection(sk, &coin_control).await?;
+
+    let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+
+    let psbt = receive_payjoin(&wallet, body, query, &selection).await?;
+
+    Ok(base64::encode(&encode::serialize(&psbt)))
+}
+
+/// Unsigned PSBT paying the destination, built from watch-only descriptors for an external
+/// signer. Payjoin needs the wallet to sign the original PSBT, so BIP-21 URIs are paid to
+/// their address. Frozen outputs, including those holding RGB allocations, are never spent.
+pub async fn build_send_sats(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    destination: &str, // bip21 uri or address
+    amount: u64,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<UnsignedPsbtResponse, BitcoinError> {
+    let address = match payjoin::Uri::try_from(destination) {
+        Ok(uri) => uri.address,
+        _ => Address::from_str(destination)?,
+    };
+    validate_address(&address).await?;
+
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
+
+    let (psbt, details) = build_transaction(
+        vec![SatsInvoice { address, amount }],
+        &wallet,
+        fee_rate,
+        Some(&selection),
+    )
+    .await?;
+
+    Ok(unsigned_psbt(&psbt, &details))
+}
+
+/// Dry run of `send_sats`: runs the coin selection and returns the unsigned PSBT with its
+/// inputs, outputs and fee. Nothing is broadcast, see `confirm_preview`.
+pub async fn preview_send_sats(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    destination: &str, // bip21 uri or address
+    amount: u64,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<TransactionPreview, BitcoinError> {
+    let address = match payjoin::Uri::try_from(destination) {
+        Ok(uri) => uri.address,
+        _ => Address::from_str(destination)?,
+    };
+    validate_address(&address).await?;
+
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
+
+    let recipients = vec![address.script_pubkey()];
+    let (psbt, details) = build_transaction(
+        vec![SatsInvoice { address, amount }],
+        &wallet,
+        fee_rate,
+        Some(&selection),
+    )
+    .await?;
+
+    preview_psbt(&wallet, &psbt, &details, &recipients).await
+}
+
+fn unsigned_psbt(
+    psbt: &PartiallySignedTransaction,
+    details: &TransactionDetails,
+) -> UnsignedPsbtResponse {
+    UnsignedPsbtResponse {
+        psbt: base64::encode(&encode::serialize(psbt)),
+        txid: details.txid.to_string(),
+        fee: details.fee,
+    }
+}
+
+/// Inputs, outputs and fee of an unsigned PSBT of the wallet. Outputs of the wallet other
+/// than the recipients are change. The vsize adds the witness of each input, estimated from
+/// the satisfaction weight of its descriptor.
+async fn preview_psbt(
+    wallet: &WalletHandle,
+    psbt: &PartiallySignedTransaction,
+    details: &TransactionDetails,
+    recipients: &[Script],
+) -> Result<TransactionPreview, BitcoinError> {
+    let wallet = wallet.lock().await;
+    let tx = &psbt.unsigned_tx;
+
+    let mut inputs = vec![];
+    let mut witness_weight = 0;
+    for (txin, input) in tx.input.iter().zip(&psbt.inputs) {
+        let outpoint = txin.previous_output;
+        let amount = match (&input.witness_utxo, &input.non_witness_utxo) {
+            (Some(txout), _) => txout.value,
+            (None, Some(prev_tx)) => prev_tx.output[outpoint.vout as usize].value,
+            _ => 0,
+        };
+        if let Some(utxo) = wallet.get_utxo(outpoint)? {
+            witness_weight += wallet
+                .get_descriptor_for_keychain(utxo.keychain)
+                .max_satisfaction_weight()
+                .map_err(bdk::Error::from)?;
+        }
+
+        inputs.push(PreviewInput {
+            outpoint: outpoint.to_string(),
+            amount,
+        });
+    }
+
+    let mut outputs = vec![];
+    for txout in &tx.output {
+        let is_change =
+            !recipients.contains(&txout.script_pubkey) && wallet.is_mine(&txout.script_pubkey)?;
+        outputs.push(PreviewOutput {
+            address: Address::from_script(&txout.script_pubkey, wallet.network())
+                .map(|address| address.to_string()),
+            amount: txout.value,
+            is_change,
+        });
+    }
+
+    // Segwit marker and flag
+    if witness_weight > 0 {
+        witness_weight += 2;
+    }
+    let vsize = ((tx.weight() + witness_weight + 3) / 4) as u64;
+    let fee = details.fee.unwrap_or_default();
+
+    Ok(TransactionPreview {
+        psbt: base64::encode(&encode::serialize(psbt)),
+        txid: details.txid.to_string(),
+        inputs,
+        outputs,
+        fee,
+        fee_rate: fee as f32 / vsize as f32,
+        vsize,
+    })
+}
+
+pub async fn list_frozen_utxos(sk: &str) -> Result<FrozenUtxosResponse, BitcoinError> {
+    let frozen = retrieve_frozen_utxos(sk).await?;
+    let rgb = list_rgb_utxos(sk).await?;
+
+    Ok(FrozenUtxosResponse {
+        frozen: frozen.utxos.into_iter().collect(),
+        rgb: rgb.into_iter().collect(),
+    })
+}
+
+pub async fn freeze_utxos(
+    sk: &str,
+    utxos: Vec<String>,
+) -> Result<FrozenUtxosResponse, BitcoinError> {
+    let mut frozen = retrieve_frozen_utxos(sk).await?;
+    for utxo in utxos {
+        let outpoint = parse_outpoint(&utxo)?;
+        frozen.utxos.insert(outpoint.to_string());
+    }
+    store_frozen_utxos(sk, &frozen).await?;
+
+    list_frozen_utxos(sk).await
+}
+
+/// Unfreeze outpoints frozen by the user. Outpoints holding RGB allocations stay frozen.
+pub async fn unfreeze_utxos(
+    sk: &str,
+    utxos: Vec<String>,
+) -> Result<FrozenUtxosResponse, BitcoinError> {
+    let mut frozen = retrieve_frozen_utxos(sk).await?;
+    for utxo in utxos {
+        let outpoint = parse_outpoint(&utxo)?;
+        frozen.utxos.remove(&outpoint.to_string());
+    }
+    store_frozen_utxos(sk, &frozen).await?;
+
+    list_frozen_utxos(sk).await
+}
+
+/// Funds the RGB vault without spending frozen UTXOs, including every outpoint holding RGB
+/// allocations
+#[allow(clippy::too_many_arguments)]
 pub async fn fund_vault(
+    sk: &str,
     btc_descriptor_xprv: &SecretString,
     btc_change_descriptor_xprv: &SecretString,
     assets_address_1: &str,
     assets_address_2: &str,
     uda_address_1: &str,
     uda_address_2: &str,
-    fee_rate: Option<f32>,
+    fee_rate: Option<FeeRateRequest>,
 ) -> Result<FundVaultDetails, BitcoinError> {
     let wallet = get_wallet(btc_descriptor_xprv, Some(btc_change_descriptor_xprv)).await?;
-    let fee_rate = fee_rate.map(FeeRate::from_sat_per_vb);
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
+
+    let invoices = fund_vault_invoices(
+        &wallet,
+        assets_address_1,
+        assets_address_2,
+        uda_address_1,
+        uda_address_2,
+    )
+    .await?;
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let asset_tx_details =
+        create_transaction(sk, invoices, &wallet, fee_rate, Some(&selection)).await?;
+
+    let asset_txid = asset_tx_details.txid;
+
+    info!(format!("asset txid: {asset_txid}"));
+
+    let asset_tx = asset_tx_details
+        .transaction
+        .expect("asset tx should exist but doesn't");
+
+    Ok(fund_vault_details(&asset_tx, true))
+}
+
+/// Unsigned PSBT funding the vault, built from watch-only descriptors for an external signer.
+/// Frozen outputs, including those holding RGB allocations, are never spent.
+#[allow(clippy::too_many_arguments)]
+pub async fn build_fund_vault(
+    sk: &str,
+    btc_descriptor: &SecretString,
+    btc_change_descriptor: &SecretString,
+    assets_address_1: &str,
+    assets_address_2: &str,
+    uda_address_1: &str,
+    uda_address_2: &str,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<UnsignedFundVaultResponse, BitcoinError> {
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let wallet = get_wallet(btc_descriptor, Some(btc_change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
+
+    let invoices = fund_vault_invoices(
+        &wallet,
+        assets_address_1,
+        assets_address_2,
+        uda_address_1,
+        uda_address_2,
+    )
+    .await?;
+    let (psbt, details) = build_transaction(invoices, &wallet, fee_rate, Some(&selection)).await?;
+
+    Ok(UnsignedFundVaultResponse {
+        vault: fund_vault_details(&psbt.unsigned_tx, false),
+        psbt: unsigned_psbt(&psbt, &details),
+    })
+}
+
+/// Dry run of `fund_vault`, nothing is broadcast. See `confirm_preview`.
+#[allow(clippy::too_many_arguments)]
+pub async fn preview_fund_vault(
+    sk: &str,
+    btc_descriptor: &SecretString,
+    btc_change_descriptor: &SecretString,
+    assets_address_1: &str,
+    assets_address_2: &str,
+    uda_address_1: &str,
+    uda_address_2: &str,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<FundVaultPreview, BitcoinError> {
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let wallet = get_wallet(btc_descriptor, Some(btc_change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
+
+    let invoices = fund_vault_invoices(
+        &wallet,
+        assets_address_1,
+        assets_address_2,
+        uda_address_1,
+        uda_address_2,
+    )
+    .await?;
+    let recipients: Vec<Script> = invoices
+        .iter()
+        .map(|invoice| invoice.address.script_pubkey())
+        .collect();
+    let (psbt, details) = build_transaction(invoices, &wallet, fee_rate, Some(&selection)).await?;
+
+    Ok(FundVaultPreview {
+        vault: fund_vault_details(&psbt.unsigned_tx, false),
+        preview: preview_psbt(&wallet, &psbt, &details, &recipients).await?,
+    })
+}
 
+async fn fund_vault_invoices(
+    wallet: &WalletHandle,
+    assets_address_1: &str,
+    assets_address_2: &str,
+    uda_address_1: &str,
+    uda_address_2: &str,
+) -> Result<Vec<SatsInvoice>, BitcoinError> {
     let balance = wallet.lock().await.get_balance()?;
     if balance.confirmed < MIN_FUNDS_SATS {
         return Err(BitcoinError::InsufficientFundSats(MIN_FUNDS_SATS));
@@ -414,37 +1270,198 @@ pub async fn fund_vault(
         amount: rng.gen_range(600..1500),
     };
 
-    let asset_tx_details = create_transaction(
-        vec![
-            asset_invoice_1,
-            asset_invoice_2,
-            uda_invoice_1,
-            uda_invoice_2,
-        ],
-        &wallet,
-        fee_rate,
-    )
-    .await?;
-
-    let asset_txid = asset_tx_details.txid;
-
-    info!(format!("asset txid: {asset_txid}"));
+    Ok(vec![
+        asset_invoice_1,
+        asset_invoice_2,
+        uda_invoice_1,
+        uda_invoice_2,
+    ])
+}
 
-    let asset_outputs: Vec<String> = asset_tx_details
-        .transaction
-        .expect("asset tx should exist but doesn't")
+fn fund_vault_details(asset_tx: &Transaction, is_funded: bool) -> FundVaultDetails {
+    let asset_txid = asset_tx.txid();
+    let asset_outputs: Vec<String> = asset_tx
         .output
         .iter()
         .enumerate()
         .map(|(i, _)| format!("{asset_txid}:{i}"))
         .collect();
 
-    Ok(FundVaultDetails {
+    FundVaultDetails {
         assets_output: Some(asset_outputs[0].to_owned()),
         assets_change_output: Some(asset_outputs[1].to_owned()),
         udas_output: Some(asset_outputs[2].to_owned()),
         udas_change_output: Some(asset_outputs[3].to_owned()),
-        is_funded: true,
+        is_funded,
+    }
+}
+
+fn push_vault_slot(slots: &mut Vec<VaultSlotOutpoints>, asset_type: AssetType, outpoint: String) {
+    match slots.iter_mut().find(|slot| slot.asset_type == asset_type) {
+        Some(slot) => slot.outpoints.push(outpoint),
+        None => slots.push(VaultSlotOutpoints {
+            asset_type,
+            outpoints: vec![outpoint],
+        }),
+    }
+}
+
+/// Tops up the RGB vault with the requested slots in a single transaction.
+/// Free vault UTXOs worth at least the slot amount count towards the requested slots, the
+/// missing ones are paid to fresh addresses of the vault descriptor of their asset type, after
+/// the last address used by the RGB watcher. Frozen UTXOs neither fund the transaction nor
+/// count as free slots.
+pub async fn plan_vault_utxos(
+    sk: &str,
+    request: VaultUtxoPlanRequest,
+) -> Result<VaultUtxoPlanResponse, BitcoinError> {
+    let VaultUtxoPlanRequest {
+        descriptor,
+        change_descriptor,
+        rgb_assets_descriptor_xpub,
+        rgb_udas_descriptor_xpub,
+        slots,
+        fee_rate,
+    } = request;
+
+    if slots.iter().all(|slot| slot.count == 0) {
+        return Err(BitcoinError::NoVaultSlots);
+    }
+
+    let wallet = get_wallet(&descriptor, Some(&change_descriptor)).await?;
+    let assets_wallet = get_wallet(&rgb_assets_descriptor_xpub, None).await?;
+    let udas_wallet = get_wallet(&rgb_udas_descriptor_xpub, None).await?;
+    sync_wallet(&wallet).await?;
+    sync_wallet(&assets_wallet).await?;
+    sync_wallet(&udas_wallet).await?;
+    let fee_rate = Some(resolve_fee_rate(fee_rate).await?);
+
+    // Frozen UTXOs and UTXOs holding RGB allocations neither fund the slots nor count as free slots
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let vaults = [
+        (&assets_wallet, AssetType::RGB20),
+        (&udas_wallet, AssetType::RGB21),
+    ];
+    let mut unspent = vec![];
+    let mut next_index = vec![];
+    for (vault, asset_type) in &vaults {
+        // The watcher index is stored with the RGB account. The BDK one also counts the
+        // addresses reserved by a previous plan that the watcher did not register yet.
+        let watcher_index =
+            match watcher_next_index(sk, RGB_DEFAULT_NAME, asset_type.clone() as u32).await {
+                Ok(index) => index,
+                Err(WatcherError::NoWatcher) => 0,
+                Err(err) => return Err(err.into()),
+            };
+
+        let vault = vault.lock().await;
+        let utxos: Vec<LocalUtxo> = vault
+            .list_unspent()?
+            .into_iter()
+            .filter(|utxo| !selection.exclude.contains(&utxo.outpoint))
+            .collect();
+        unspent.push(utxos);
+        let wallet_index = vault
+            .database()
+            .get_last_index(KeychainKind::External)?
+            .map_or(0, |index| index + 1);
+        next_index.push(watcher_index.max(wallet_index));
+    }
+
+    let mut invoices = vec![];
+    let mut planned = vec![];
+    let mut existing: Vec<(AssetType, String)> = vec![];
+    let mut reserved = vec![0u32; vaults.len()];
+    for slot in slots {
+        let vault = match slot.asset_type {
+            AssetType::RGB20 | AssetType::RGB25 => 0,
+            AssetType::RGB21 => 1,
+            asset_type => return Err(BitcoinError::InvalidVaultAssetType(asset_type.to_string())),
+        };
+        if slot.amount < MIN_VAULT_SLOT_SATS {
+            return Err(BitcoinError::VaultSlotBelowDust(
+                slot.amount,
+                MIN_VAULT_SLOT_SATS,
+            ));
+        }
+
+        // Free slots already in the vault count towards the requested ones
+        let mut missing = slot.count;
+        while missing > 0 {
+            let Some(position) = unspent[vault]
+                .iter()
+                .position(|utxo| utxo.txout.value >= slot.amount)
+            else {
+                break;
+            };
+            let utxo = unspent[vault].swap_remove(position);
+            existing.push((slot.asset_type.clone(), utxo.outpoint.to_string()));
+            missing -= 1;
+        }
+
+        // Fresh addresses are only peeked, they are reserved once the transaction is sent
+        for _ in 0..missing {
+            let index = next_index[vault] + reserved[vault];
+            let address = vaults[vault]
+                .0
+                .lock()
+                .await
+                .get_address(AddressIndex::Peek(index))?;
+            reserved[vault] += 1;
+            planned.push((slot.asset_type.clone(), address.script_pubkey()));
+            invoices.push(SatsInvoice {
+                address: address.address,
+                amount: slot.amount,
+            });
+        }
+    }
+
+    let mut slots: Vec<VaultSlotOutpoints> = vec![];
+    for (asset_type, outpoint) in existing {
+        push_vault_slot(&mut slots, asset_type, outpoint);
+    }
+
+    if invoices.is_empty() {
+        info!("vault slots are already topped up");
+        return Ok(VaultUtxoPlanResponse {
+            txid: None,
+            fee: None,
+            slots,
+        });
+    }
+
+    let details = create_transaction(sk, invoices, &wallet, fee_rate, Some(&selection)).await?;
+    let txid = details.txid;
+    info!(format!("vault slots txid: {txid}"));
+
+    for (vault, count) in reserved.into_iter().enumerate() {
+        if count > 0 {
+            let last_index = next_index[vault] + count - 1;
+            vaults[vault]
+                .0
+                .lock()
+                .await
+                .get_address(AddressIndex::Reset(last_index))?;
+        }
+    }
+
+    let tx = details
+        .transaction
+        .ok_or_else(|| BitcoinError::TransactionNotFound(txid.to_string()))?;
+
+    for (asset_type, script) in planned {
+        let vout = tx
+            .output
+            .iter()
+            .position(|output| output.script_pubkey == script)
+            .expect("planned vault slot should be paid by the transaction");
+        push_vault_slot(&mut slots, asset_type, format!("{txid}:{vout}"));
+    }
+
+    Ok(VaultUtxoPlanResponse {
+        txid: Some(txid.to_string()),
+        fee: details.fee,
+        slots,
     })
 }
 
@@ -490,11 +1507,19 @@ pub async fn get_assets_vault(
     })
 }
 
+/// Parses a PSBT encoded in Base64, or in hex as returned by `sign_psbt_file`
+fn parse_psbt(psbt: &str) -> Result<PartiallySignedTransaction, BitcoinError> {
+    if let Ok(bytes) = hex::decode(psbt) {
+        return Ok(encode::deserialize(&bytes)?);
+    }
+
+    Ok(PartiallySignedTransaction::from(Psbt::from_str(psbt)?))
+}
+
 pub async fn sign_psbt_file(request: SignPsbtRequest) -> Result<SignedPsbtResponse, BitcoinError> {
     let SignPsbtRequest { psbt, descriptors } = request;
 
-    let original_psbt = Psbt::from_str(&psbt)?;
-    let final_psbt = PartiallySignedTransaction::from(original_psbt);
+    let final_psbt = parse_psbt(&psbt)?;
 
     let mut wallets = vec![];
     for descriptor in descriptors {
@@ -513,12 +1538,12 @@ pub async fn sign_psbt_file(request: SignPsbtRequest) -> Result<SignedPsbtRespon
 }
 
 pub async fn sign_and_publish_psbt_file(
+    sk: &str,
     request: SignPsbtRequest,
 ) -> Result<PublishedPsbtResponse, BitcoinError> {
     let SignPsbtRequest { psbt, descriptors } = request;
 
-    let original_psbt = Psbt::from_str(&psbt)?;
-    let final_psbt = PartiallySignedTransaction::from(original_psbt);
+    let final_psbt = parse_psbt(&psbt)?;
 
     let mut wallets = vec![];
     for descriptor in descriptors {
@@ -526,7 +1551,7 @@ pub async fn sign_and_publish_psbt_file(
         wallets.push(wallet);
     }
 
-    let sign = multi_sign_and_publish_psbt(wallets, final_psbt).await?;
+    let sign = multi_sign_and_publish_psbt(sk, wallets, final_psbt).await?;
     let resp = match sign.transaction {
         Some(tx) => PublishedPsbtResponse {
             sign: true,
@@ -541,31 +1566,125 @@ pub async fn sign_and_publish_psbt_file(
     Ok(resp)
 }
 
+/// Finalize and broadcast a PSBT signed by an external signer, using the watch-only
+/// descriptors of the wallet
+pub async fn publish_psbt_file(
+    sk: &str,
+    request: PublishPsbtRequest,
+) -> Result<PublishedPsbtResponse, BitcoinError> {
+    let PublishPsbtRequest {
+        psbt,
+        descriptor,
+        change_descriptor,
+    } = request;
+
+    let psbt = parse_psbt(&psbt)?;
+    let wallet = get_wallet(&descriptor, change_descriptor.as_ref()).await?;
+    let details = finalize_and_publish_psbt(sk, &wallet, psbt).await?;
+
+    Ok(PublishedPsbtResponse {
+        sign: true,
+        txid: details.txid.to_string(),
+    })
+}
+
+/// Finalizes a PSBT signed by an external signer and adds it to the broadcast queue of the
+/// wallet, so it is retried until it confirms
+pub async fn queue_psbt_file(
+    sk: &str,
+    request: PublishPsbtRequest,
+) -> Result<QueuedTransaction, BitcoinError> {
+    let PublishPsbtRequest {
+        psbt,
+        descriptor,
+        change_descriptor,
+    } = request;
+
+    let psbt = parse_psbt(&psbt)?;
+    let wallet = get_wallet(&descriptor, change_descriptor.as_ref()).await?;
+    let psbt = finalize_psbt(&wallet, psbt).await?;
+
+    Ok(queue_transaction(sk, &wallet, psbt.extract_tx()).await?)
+}
+
+/// Signs a PSBT with the descriptors and adds it to the broadcast queue of the first one.
+/// RGB anchor transactions go through the queue, their consignments are only valid once
+/// the transaction confirms.
+pub async fn sign_and_queue_psbt_file(
+    sk: &str,
+    request: SignPsbtRequest,
+) -> Result<QueuedTransaction, BitcoinError> {
+    let SignPsbtRequest { psbt, descriptors } = request;
+
+    let psbt = parse_psbt(&psbt)?;
+
+    let mut wallets = vec![];
+    for descriptor in descriptors {
+        let wallet = get_wallet(&descriptor, None).await?;
+        wallets.push(wallet);
+    }
+    let wallet = match wallets.first() {
+        Some(wallet) => wallet.clone(),
+        None => return Err(BitcoinPsbtError::CouldNotFinalizePsbt.into()),
+    };
+
+    let psbt = multi_sign_and_finalize_psbt(wallets, psbt).await?;
+    Ok(queue_transaction(sk, &wallet, psbt.extract_tx()).await?)
+}
+
+/// Outgoing transactions of the wallet. Pending ones are broadcast again when due.
+pub async fn get_broadcast_queue(
+    sk: &str,
+    request: BroadcastQueueRequest,
+) -> Result<BroadcastQueueResponse, BitcoinError> {
+    let wallet = get_wallet(&request.descriptor, request.change_descriptor.as_ref()).await?;
+    let transactions = process_broadcast_queue(sk, &wallet).await?;
+
+    Ok(BroadcastQueueResponse { transactions })
+}
+
+pub async fn sign_bip322(request: SignMessageRequest) -> Result<SignMessageResponse, BitcoinError> {
+    let SignMessageRequest {
+        descriptor,
+        address,
+        message,
+        format,
+    } = request;
+
+    let signature = sign_message(&descriptor, &address, &message, format).await?;
+
+    Ok(SignMessageResponse {
+        address,
+        message,
+        signature,
+    })
+}
+
+pub fn verify_bip322(request: VerifyMessageRequest) -> Result<VerifyMessageResponse, BitcoinError> {
+    let VerifyMessageRequest {
+        address,
+        message,
+        signature,
+    } = request;
+
+    let valid = verify_message(&address, &message, &signature)?;
+
+    Ok(VerifyMessageResponse { valid })
+}
+
+/// Drains the wallet to the destination, leaving frozen UTXOs and outpoints holding RGB
+/// allocations unspent
 pub async fn drain_wallet(
+    sk: &str,
     destination: &str,
     descriptor: &SecretString,
     change_descriptor: Option<&SecretString>,
-    fee_rate: Option<f32>,
+    fee_rate: Option<FeeRateRequest>,
 ) -> Result<TransactionDetails, BitcoinError> {
-    let address = Address::from_str(destination)?;
-    validate_address(&address).await?;
-    debug!(format!("Create drain wallet tx to: {address:#?}"));
-
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
     let wallet = get_wallet(descriptor, change_descriptor).await?;
-    sync_wallet(&wallet).await?;
-
-    let fee_rate = fee_rate.map(FeeRate::from_sat_per_vb);
-
-    let (mut psbt, details) = {
-        let locked_wallet = wallet.lock().await;
-        let mut builder = locked_wallet.build_tx();
-        if let Some(fee_rate) = fee_rate {
-            builder.fee_rate(fee_rate);
-        }
-        builder.drain_wallet();
-        builder.drain_to(address.script_pubkey());
-        builder.finish()?
-    };
+    let (mut psbt, details) =
+        build_drain_psbt(&wallet, destination, fee_rate, Some(&selection)).await?;
 
     debug!("Signing PSBT...");
     let finalized = wallet
@@ -578,32 +1697,195 @@ pub async fn drain_wallet(
     }
     debug!(format!("Finalized: {finalized}"));
 
-    let blockchain = get_blockchain().await;
-    let tx = psbt.extract_tx();
-    blockchain.broadcast(&tx).await?;
-    let tx = blockchain.get_tx(&details.txid).await?;
+    let transaction = psbt.extract_tx();
+    queue_transaction(sk, &wallet, transaction.clone()).await?;
 
-    if let Some(transaction) = tx.clone() {
-        let sent = transaction
-            .output
-            .iter()
-            .fold(0, |sum, output| output.value + sum);
-
-        let details = TransactionDetails {
-            transaction: tx,
-            txid: transaction.txid(),
-            received: 0,
-            sent,
-            fee: details.fee,
-            confirmation_time: None,
-        };
+    let sent = transaction
+        .output
+        .iter()
+        .fold(0, |sum, output| output.value + sum);
+
+    let details = TransactionDetails {
+        txid: transaction.txid(),
+        transaction: Some(transaction),
+        received: 0,
+        sent,
+        fee: details.fee,
+        confirmation_time: None,
+    };
 
-        info!(format!(
-            "Drain wallet transaction submitted with details: {details:#?}"
-        ));
+    info!(format!(
+        "Drain wallet transaction submitted with details: {details:#?}"
+    ));
 
-        Ok(details)
-    } else {
-        Err(BitcoinError::DrainWalletNoTxDetails)
+    Ok(details)
+}
+
+/// Unsigned PSBT draining the wallet, built from watch-only descriptors for an external signer.
+/// Frozen outputs, including those holding RGB allocations, are left in the wallet.
+pub async fn build_drain_wallet(
+    sk: &str,
+    destination: &str,
+    descriptor: &SecretString,
+    change_descriptor: Option<&SecretString>,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<UnsignedPsbtResponse, BitcoinError> {
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let wallet = get_wallet(descriptor, change_descriptor).await?;
+    let (psbt, details) =
+        build_drain_psbt(&wallet, destination, fee_rate, Some(&selection)).await?;
+
+    Ok(unsigned_psbt(&psbt, &details))
+}
+
+/// Dry run of `drain_wallet`, nothing is broadcast. See `confirm_preview`.
+pub async fn preview_drain_wallet(
+    sk: &str,
+    destination: &str,
+    descriptor: &SecretString,
+    change_descriptor: Option<&SecretString>,
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<TransactionPreview, BitcoinError> {
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+    let wallet = get_wallet(descriptor, change_descriptor).await?;
+    let (psbt, details) =
+        build_drain_psbt(&wallet, destination, fee_rate, Some(&selection)).await?;
+
+    let recipients = vec![Address::from_str(destination)?.script_pubkey()];
+    preview_psbt(&wallet, &psbt, &details, &recipients).await
+}
+
+/// Signs and broadcasts the PSBT of a preview
+pub async fn confirm_preview(
+    sk: &str,
+    request: ConfirmPreviewRequest,
+) -> Result<TransactionDetails, BitcoinError> {
+    let ConfirmPreviewRequest {
+        psbt,
+        descriptor,
+        change_descriptor,
+    } = request;
+
+    let psbt = parse_psbt(&psbt)?;
+    let wallet = get_wallet(&descriptor, change_descriptor.as_ref()).await?;
+    let details = sign_and_publish_psbt(sk, &wallet, psbt).await?;
+    info!(format!("Preview {} confirmed", details.txid));
+
+    Ok(details)
+}
+
+async fn build_drain_psbt(
+    wallet: &WalletHandle,
+    destination: &str,
+    fee_rate: Option<FeeRateRequest>,
+    selection: Option<&UtxoSelection>,
+) -> Result<(PartiallySignedTransaction, TransactionDetails), BitcoinError> {
+    let address = Address::from_str(destination)?;
+    validate_address(&address).await?;
+    debug!(format!("Create drain wallet tx to: {address:#?}"));
+
+    sync_wallet(wallet).await?;
+
+    let fee_rate = resolve_fee_rate(fee_rate).await?;
+
+    let locked_wallet = wallet.lock().await;
+    let mut builder = locked_wallet.build_tx();
+    builder.fee_rate(fee_rate);
+    builder.drain_wallet();
+    builder.drain_to(address.script_pubkey());
+    if let Some(selection) = selection {
+        selection.apply(&mut builder)?;
     }
+
+    Ok(builder.finish()?)
+}
+
+/// Replace an unconfirmed transaction sent by the wallet with one paying a higher fee rate (BIP-125).
+/// Inputs added to pay the fee are never frozen outputs, including those holding RGB allocations.
+/// The original transaction is marked as replaced in the broadcast queue, so it is not broadcast
+/// again once it leaves the mempool.
+pub async fn bump_fee(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    txid: &str,
+    new_fee_rate: f32,
+) -> Result<BumpFeeResponse, BitcoinError> {
+    let original_txid = Txid::from_str(txid)?;
+    let new_fee_rate = check_fee_rate(new_fee_rate)?;
+
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+
+    let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+
+    let (psbt, original_fee) = {
+        let locked_wallet = wallet.lock().await;
+        let original = locked_wallet
+            .get_tx(&original_txid, false)?
+            .ok_or_else(|| BitcoinError::TransactionNotFound(txid.to_owned()))?;
+        if original.confirmation_time.is_some() {
+            return Err(BitcoinError::TransactionAlreadyConfirmed(txid.to_owned()));
+        }
+
+        let mut builder = locked_wallet.build_fee_bump(original_txid)?;
+        builder
+            .enable_rbf()
+            .fee_rate(FeeRate::from_sat_per_vb(new_fee_rate));
+        selection.apply(&mut builder)?;
+        let (psbt, details) = builder.finish()?;
+        debug!(format!("Fee bump transaction: {details:#?}"));
+
+        (psbt, original.fee)
+    };
+
+    // A replacement waiting for a retry still evicts the original once it is broadcast
+    let replacement_txid = psbt.unsigned_tx.txid().to_string();
+    let published = sign_and_publish_psbt(sk, &wallet, psbt).await;
+    if let Ok(_) | Err(BitcoinPsbtError::Queued(..)) = &published {
+        replace_transaction(sk, &wallet, txid, &replacement_txid).await?;
+    }
+
+    let details = published?;
+    info!(format!(
+        "Transaction {original_txid} replaced by {} (fee: {original_fee:?} -> {:?} sats)",
+        details.txid, details.fee
+    ));
+
+    Ok(BumpFeeResponse {
+        original_txid: original_txid.to_string(),
+        txid: details.txid.to_string(),
+        original_fee,
+        fee: details.fee,
+        fee_rate: new_fee_rate,
+    })
+}
+
+/// Accelerate an unconfirmed transaction paying the wallet by spending its output in a child
+/// transaction (CPFP). Frozen outputs, including those holding RGB allocations, are never spent.
+pub async fn cpfp(
+    sk: &str,
+    descriptor: &SecretString,
+    change_descriptor: &SecretString,
+    txid: &str,
+    target_fee_rate: f32,
+) -> Result<CpfpResponse, BitcoinError> {
+    let parent_txid = Txid::from_str(txid)?;
+    let target_fee_rate = check_fee_rate(target_fee_rate)?;
+
+    let selection = utxo_selection(sk, &CoinControl::default()).await?;
+
+    let wallet = get_wallet(descriptor, Some(change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+
+    let resp = create_cpfp(
+        sk,
+        &wallet,
+        parent_txid,
+        FeeRate::from_sat_per_vb(target_fee_rate),
+        Some(&selection),
+    )
+    .await?;
+
+    Ok(resp)
 }
@@ -1,9 +1,11 @@
 use anyhow::Result;
-use bdk::{database::AnyDatabase, LocalUtxo, SignOptions, Wallet};
+use bdk::{database::AnyDatabase, wallet::AddressIndex, LocalUtxo, SignOptions, Wallet};
 use bitcoin::psbt::PartiallySignedTransaction;
 
 use crate::debug;
 
+/// Dust output paid back to the wallet, to the address of `first_utxo` when given,
+/// otherwise to a fresh address
 pub fn dust_tx(
     btc_wallet: &Wallet<AnyDatabase>,
     fee_rate: f32,
@@ -17,7 +19,7 @@ pub fn dust_tx(
 
     let pubkey = match first_utxo {
         Some(utxo) => utxo.txout.script_pubkey.to_owned(),
-        None => todo!(),
+        None => btc_wallet.get_address(AddressIndex::New)?.script_pubkey(),
     };
 
     let mut tx_builder = btc_wallet.build_tx();
@@ -0,0 +1,471 @@
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use bdk::database::Database;
+#[cfg(not(target_arch = "wasm32"))]
+use bdk::KeychainKind;
+use bitcoin::{
+    consensus::{deserialize, serialize},
+    hashes::hex::{FromHex, ToHex},
+    Transaction, Txid,
+};
+use once_cell::sync::Lazy;
+use postcard::{from_bytes, to_allocvec};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{bitcoin::wallet::open_tree, constants::NETWORK};
+use crate::{
+    bitcoin::{
+        chain::{broadcast_tx, BitcoinChainError},
+        labels::{wallet_id, BitcoinLabelsError},
+        wallet::{get_wallet, sync_wallet, BitcoinWalletError, WalletHandle},
+    },
+    carbonado::{error::CarbonadoError, retrieve, store},
+    constants::storage_keys::BITCOIN_BROADCAST_QUEUE,
+    debug, info,
+    structs::{BroadcastStatus, QueuedTransaction, SecretString},
+    warn,
+};
+
+/// Delay before retrying a failed broadcast, doubled after each attempt
+const BROADCAST_RETRY_SECS: i64 = 30;
+const BROADCAST_MAX_RETRY_SECS: i64 = 3_600;
+const BROADCAST_MAX_ATTEMPTS: u32 = 20;
+/// Confirmations after which a transaction leaves the queue
+const BROADCAST_PRUNE_CONFIRMATIONS: u32 = 6;
+/// Seconds a failed, rejected or replaced transaction is still reported before it leaves
+/// the queue
+const BROADCAST_FAILED_RETENTION_SECS: i64 = 7 * 24 * 3_600;
+
+/// Tree of the wallet database where the active queues are kept across restarts
+#[cfg(not(target_arch = "wasm32"))]
+const BROADCAST_QUEUES_TREE: &str = "broadcast_queues";
+
+/// Queues with transactions waiting for a broadcast or a confirmation, by queue name, with
+/// the key and wallet they are processed with by `process_broadcast_queues`
+static ACTIVE_QUEUES: Lazy<Mutex<BTreeMap<String, (String, WalletHandle)>>> =
+    Lazy::new(Default::default);
+
+/// Serializes the updates of each queue, by queue name
+static QUEUE_LOCKS: Lazy<Mutex<BTreeMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(Default::default);
+
+#[derive(Error, Debug)]
+pub enum BitcoinBroadcastError {
+    /// Queued transaction cannot be decoded
+    #[error("Invalid queued transaction {0}")]
+    InvalidTransaction(String),
+    /// BDK error
+    #[error(transparent)]
+    BdkError(#[from] bdk::Error),
+    /// BitMask Core Bitcoin Labels error
+    #[error(transparent)]
+    BitcoinLabelsError(#[from] BitcoinLabelsError),
+    /// BitMask Core Bitcoin Wallet error
+    #[error(transparent)]
+    BitcoinWalletError(#[from] BitcoinWalletError),
+    /// Postcard error
+    #[error(transparent)]
+    PostcardError(#[from] postcard::Error),
+    /// Carbonado error
+    #[error(transparent)]
+    CarbonadoError(#[from] CarbonadoError),
+    /// Sled wallet database error
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    SledError(#[from] bdk::sled::Error),
+}
+
+/// Outgoing transactions of a wallet, by txid
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BroadcastQueue {
+    pub txs: BTreeMap<String, QueuedTransaction>,
+}
+
+/// Active queue as persisted in the wallet database, reopened with the public descriptors
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PersistedQueue {
+    sk: String,
+    descriptor: String,
+    change_descriptor: Option<String>,
+}
+
+async fn queue_name(wallet: &WalletHandle) -> Result<String, BitcoinBroadcastError> {
+    Ok(format!(
+        "{}-{BITCOIN_BROADCAST_QUEUE}",
+        wallet_id(wallet).await?
+    ))
+}
+
+fn queue_lock(name: &str) -> Arc<tokio::sync::Mutex<()>> {
+    QUEUE_LOCKS
+        .lock()
+        .expect("broadcast queue locks are not poisoned")
+        .entry(name.to_owned())
+        .or_default()
+        .clone()
+}
+
+pub async fn retrieve_broadcast_queue(
+    sk: &str,
+    wallet: &WalletHandle,
+) -> Result<BroadcastQueue, BitcoinBroadcastError> {
+    let (data, _) = retrieve(sk, &queue_name(wallet).await?, vec![]).await?;
+    if data.is_empty() {
+        return Ok(BroadcastQueue::default());
+    }
+
+    Ok(from_bytes(&data)?)
+}
+
+pub async fn store_broadcast_queue(
+    sk: &str,
+    wallet: &WalletHandle,
+    queue: &BroadcastQueue,
+) -> Result<(), BitcoinBroadcastError> {
+    let data = to_allocvec(queue)?;
+    store(sk, &queue_name(wallet).await?, &data, true, None).await?;
+    Ok(())
+}
+
+fn retry_delay(attempts: u32) -> i64 {
+    BROADCAST_RETRY_SECS
+        .saturating_mul(1 << attempts.min(16))
+        .min(BROADCAST_MAX_RETRY_SECS)
+}
+
+fn queued_tx(queued: &QueuedTransaction) -> Result<Transaction, BitcoinBroadcastError> {
+    Vec::<u8>::from_hex(&queued.tx)
+        .ok()
+        .and_then(|tx| deserialize(&tx).ok())
+        .ok_or_else(|| BitcoinBroadcastError::InvalidTransaction(queued.txid.clone()))
+}
+
+/// Broadcasts the queued transaction and schedules the next attempt
+async fn attempt_broadcast(queued: &mut QueuedTransaction, tx: &Transaction, now: i64) {
+    queued.attempts += 1;
+    queued.next_attempt = now + retry_delay(queued.attempts);
+
+    match broadcast_tx(tx).await {
+        Ok(()) => {
+            queued.status = BroadcastStatus::Mempool;
+            queued.last_error = None;
+        }
+        Err(BitcoinChainError::Rejected(reason)) => {
+            warn!(format!("Transaction {} rejected: {reason}", queued.txid));
+            queued.status = BroadcastStatus::Rejected;
+            queued.next_attempt = now;
+            queued.last_error = Some(reason);
+        }
+        Err(err) => {
+            warn!(format!(
+                "Broadcast {} of {} failed: {err}",
+                queued.attempts, queued.txid
+            ));
+            queued.status = if queued.attempts >= BROADCAST_MAX_ATTEMPTS {
+                BroadcastStatus::Failed
+            } else {
+                BroadcastStatus::Pending
+            };
+            queued.last_error = Some(err.to_string());
+        }
+    }
+}
+
+/// Adds the transaction to the outgoing queue of the wallet and broadcasts it.
+/// A failed broadcast does not lose the transaction, `process_broadcast_queue` retries it,
+/// unless the node rejected it. The returned status tells which of both happened.
+pub async fn queue_transaction(
+    sk: &str,
+    wallet: &WalletHandle,
+    tx: Transaction,
+) -> Result<QueuedTransaction, BitcoinBroadcastError> {
+    let lock = queue_lock(&queue_name(wallet).await?);
+    let _guard = lock.lock().await;
+
+    let mut queue = retrieve_broadcast_queue(sk, wallet).await?;
+    let txid = tx.txid().to_string();
+    let now = chrono::Utc::now().timestamp();
+
+    let mut queued = match queue.txs.get(&txid) {
+        Some(queued)
+            if !matches!(
+                queued.status,
+                BroadcastStatus::Failed | BroadcastStatus::Rejected
+            ) =>
+        {
+            queued.clone()
+        }
+        _ => QueuedTransaction {
+            txid: txid.clone(),
+            tx: serialize(&tx).to_hex(),
+            status: BroadcastStatus::Pending,
+            attempts: 0,
+            next_attempt: now,
+            last_error: None,
+        },
+    };
+    if queued.status == BroadcastStatus::Pending {
+        attempt_broadcast(&mut queued, &tx, now).await;
+    }
+
+    queue.txs.insert(txid, queued.clone());
+    store_broadcast_queue(sk, wallet, &queue).await?;
+    activate_queue(sk, wallet, true).await?;
+
+    Ok(queued)
+}
+
+/// Marks a queued transaction as replaced by a fee bump, so it is not broadcast again once
+/// the replacement evicts it from the mempool
+pub async fn replace_transaction(
+    sk: &str,
+    wallet: &WalletHandle,
+    txid: &str,
+    replacement_txid: &str,
+) -> Result<(), BitcoinBroadcastError> {
+    let lock = queue_lock(&queue_name(wallet).await?);
+    let _guard = lock.lock().await;
+
+    let mut queue = retrieve_broadcast_queue(sk, wallet).await?;
+    if let Some(queued) = queue.txs.get_mut(txid) {
+        queued.status = BroadcastStatus::Replaced(replacement_txid.to_owned());
+        queued.next_attempt = chrono::Utc::now().timestamp();
+        store_broadcast_queue(sk, wallet, &queue).await?;
+    }
+
+    Ok(())
+}
+
+async fn activate_queue(
+    sk: &str,
+    wallet: &WalletHandle,
+    active: bool,
+) -> Result<(), BitcoinBroadcastError> {
+    let name = queue_name(wallet).await?;
+    #[cfg(not(target_arch = "wasm32"))]
+    persist_queue(&name, sk, wallet, active).await?;
+
+    let mut queues = ACTIVE_QUEUES
+        .lock()
+        .expect("broadcast queues lock is not poisoned");
+    if active {
+        queues.insert(name, (sk.to_owned(), wallet.clone()));
+    } else {
+        queues.remove(&name);
+    }
+
+    Ok(())
+}
+
+/// Keeps the active queue in the wallet database, so a restarted process resumes it.
+/// The nostr key of the queue is stored with the public descriptors, in the clear, under
+/// `BITMASK_WALLET_DIR`.
+#[cfg(not(target_arch = "wasm32"))]
+async fn persist_queue(
+    name: &str,
+    sk: &str,
+    wallet: &WalletHandle,
+    active: bool,
+) -> Result<(), BitcoinBroadcastError> {
+    let network = *NETWORK.read().await;
+    let Some(tree) = open_tree(network, BROADCAST_QUEUES_TREE)? else {
+        return Ok(());
+    };
+
+    if !active {
+        tree.remove(name)?;
+        return Ok(());
+    }
+
+    let (descriptor, change_descriptor) = {
+        let wallet = wallet.lock().await;
+        (
+            wallet.public_descriptor(KeychainKind::External)?,
+            wallet.public_descriptor(KeychainKind::Internal)?,
+        )
+    };
+    if let Some(descriptor) = descriptor {
+        let persisted = PersistedQueue {
+            sk: sk.to_owned(),
+            descriptor: descriptor.to_string(),
+            change_descriptor: change_descriptor.map(|descriptor| descriptor.to_string()),
+        };
+        tree.insert(name, to_allocvec(&persisted)?)?;
+    }
+
+    Ok(())
+}
+
+/// Active queues persisted by a previous process
+#[cfg(not(target_arch = "wasm32"))]
+async fn persisted_queues() -> Result<BTreeMap<String, PersistedQueue>, BitcoinBroadcastError> {
+    let network = *NETWORK.read().await;
+    let Some(tree) = open_tree(network, BROADCAST_QUEUES_TREE)? else {
+        return Ok(BTreeMap::new());
+    };
+
+    let mut queues = BTreeMap::new();
+    for entry in tree.iter() {
+        let (name, data) = entry?;
+        match from_bytes::<PersistedQueue>(&data) {
+            Ok(persisted) => {
+                queues.insert(String::from_utf8_lossy(&name).into_owned(), persisted);
+            }
+            Err(err) => warn!(format!("Invalid persisted broadcast queue: {err}")),
+        }
+    }
+
+    Ok(queues)
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn persisted_queues() -> Result<BTreeMap<String, PersistedQueue>, BitcoinBroadcastError> {
+    Ok(BTreeMap::new())
+}
+
+/// Processes the queue of every wallet with a transaction queued, by this process or by a
+/// previous one, until its transactions confirm. A failing queue does not stop the others;
+/// the first error is returned once all of them were processed.
+pub async fn process_broadcast_queues() -> Result<(), BitcoinBroadcastError> {
+    let mut queues = ACTIVE_QUEUES
+        .lock()
+        .expect("broadcast queues lock is not poisoned")
+        .clone();
+
+    let mut result = Ok(());
+    for (name, persisted) in persisted_queues().await? {
+        if queues.contains_key(&name) {
+            continue;
+        }
+
+        let descriptor = SecretString(persisted.descriptor);
+        let change_descriptor = persisted.change_descriptor.map(SecretString);
+        match get_wallet(&descriptor, change_descriptor.as_ref()).await {
+            Ok(wallet) => {
+                queues.insert(name, (persisted.sk, wallet));
+            }
+            Err(err) => {
+                warn!(format!("Broadcast queue {name} cannot be resumed: {err}"));
+                if result.is_ok() {
+                    result = Err(err.into());
+                }
+            }
+        }
+    }
+
+    for (sk, wallet) in queues.into_values() {
+        if let Err(err) = process_broadcast_queue(&sk, &wallet).await {
+            warn!(format!("Broadcast queue processing failed: {err}"));
+            if result.is_ok() {
+                result = Err(err);
+            }
+        }
+    }
+
+    result
+}
+
+/// Updates the queue from the wallet history. Pending transactions, including those dropped
+/// from the mempool, are broadcast again once their backoff elapsed. Transactions leave the
+/// queue once they are deeply confirmed, or some days after they failed, were rejected or
+/// were replaced.
+pub async fn process_broadcast_queue(
+    sk: &str,
+    wallet: &WalletHandle,
+) -> Result<Vec<QueuedTransaction>, BitcoinBroadcastError> {
+    let lock = queue_lock(&queue_name(wallet).await?);
+    let _guard = lock.lock().await;
+
+    let mut queue = retrieve_broadcast_queue(sk, wallet).await?;
+    if queue.txs.is_empty() {
+        activate_queue(sk, wallet, false).await?;
+        return Ok(vec![]);
+    }
+
+    sync_wallet(wallet).await?;
+    let now = chrono::Utc::now().timestamp();
+
+    // Confirmation height of each queued transaction known by the wallet
+    let (heights, tip) = {
+        let wallet = wallet.lock().await;
+        let tip = wallet
+            .database()
+            .get_sync_time()?
+            .map(|sync_time| sync_time.block_time.height)
+            .unwrap_or_default();
+
+        let mut heights = BTreeMap::new();
+        for txid in queue.txs.keys() {
+            let Ok(parsed) = Txid::from_str(txid) else {
+                continue;
+            };
+            if let Some(details) = wallet.get_tx(&parsed, false)? {
+                let height = details.confirmation_time.map(|time| time.height);
+                heights.insert(txid.clone(), height);
+            }
+        }
+        (heights, tip)
+    };
+
+    let mut transactions = vec![];
+    for (txid, mut queued) in std::mem::take(&mut queue.txs) {
+        match heights.get(&txid) {
+            Some(Some(height)) => {
+                queued.status = BroadcastStatus::Confirmed(*height);
+                if tip + 1 >= height + BROADCAST_PRUNE_CONFIRMATIONS {
+                    debug!(format!("Transaction {txid} confirmed, leaving the queue"));
+                    transactions.push(queued);
+                    continue;
+                }
+            }
+            // The wallet keeps a replaced transaction until the next sync drops it
+            Some(None) if matches!(queued.status, BroadcastStatus::Replaced(_)) => {}
+            Some(None) => queued.status = BroadcastStatus::Mempool,
+            None if matches!(
+                queued.status,
+                BroadcastStatus::Failed | BroadcastStatus::Rejected | BroadcastStatus::Replaced(_)
+            ) =>
+            {
+                if now >= queued.next_attempt + BROADCAST_FAILED_RETENTION_SECS {
+                    debug!(format!(
+                        "Transaction {txid} is not broadcast anymore, leaving the queue"
+                    ));
+                    transactions.push(queued);
+                    continue;
+                }
+            }
+            None => {
+                if queued.status != BroadcastStatus::Pending {
+                    info!(format!("Transaction {txid} dropped from the mempool"));
+                    queued.status = BroadcastStatus::Pending;
+                }
+                if now >= queued.next_attempt {
+                    // An undecodable entry fails on its own, without blocking the others
+                    match queued_tx(&queued) {
+                        Ok(tx) => attempt_broadcast(&mut queued, &tx, now).await,
+                        Err(err) => {
+                            warn!(format!("Transaction {txid} cannot be broadcast: {err}"));
+                            queued.status = BroadcastStatus::Failed;
+                            queued.next_attempt = now;
+                            queued.last_error = Some(err.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        transactions.push(queued.clone());
+        queue.txs.insert(txid, queued);
+    }
+
+    store_broadcast_queue(sk, wallet, &queue).await?;
+    activate_queue(sk, wallet, !queue.txs.is_empty()).await?;
+
+    Ok(transactions)
+}
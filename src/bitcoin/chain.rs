@@ -0,0 +1,772 @@
+use std::{fmt, str::FromStr};
+
+use bitcoin::{Script, Transaction, TxOut, Txid};
+use thiserror::Error;
+
+use crate::{
+    bitcoin::wallet::get_blockchain,
+    constants::{BITCOIN_CHAIN_BACKEND, BITCOIN_ELECTRUM_API},
+    debug,
+};
+
+#[derive(Error, Debug)]
+pub enum BitcoinChainError {
+    /// Unknown chain backend in the configuration
+    #[error("Unknown chain backend: {0}. Supported backends are esplora and electrum")]
+    UnknownBackend(String),
+    /// Chain backend cannot be used on this platform
+    #[error("Chain backend {0} is not supported on this platform")]
+    UnsupportedBackend(ChainBackend),
+    /// BDK error
+    #[error(transparent)]
+    BdkError(#[from] bdk::Error),
+    /// BDK esplora error
+    #[error(transparent)]
+    BdkEsploraError(#[from] bdk::esplora_client::Error),
+    /// Esplora error
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    EsploraError(#[from] esplora_block::Error),
+    /// Electrum error
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    ElectrumError(#[from] electrum_client::Error),
+    /// Transaction without outputs indexed by the chain backend
+    #[error("Transaction {0} has only OP_RETURN outputs, which Electrum servers do not index")]
+    NoIndexedOutput(String),
+    /// Node refused the transaction, so broadcasting it again fails the same way
+    #[error("Transaction rejected by the node: {0}")]
+    Rejected(String),
+}
+
+/// Source of chain data for the wallet sync, broadcasting and the RGB resolvers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChainBackend {
+    /// Esplora HTTP API (BITCOIN_EXPLORER_API)
+    #[default]
+    Esplora,
+    /// Electrum protocol server, such as electrs or Fulcrum (BITCOIN_ELECTRUM_API).
+    /// Only available on native targets.
+    Electrum,
+}
+
+impl FromStr for ChainBackend {
+    type Err = BitcoinChainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "esplora" => Ok(Self::Esplora),
+            "electrum" => Ok(Self::Electrum),
+            backend => Err(BitcoinChainError::UnknownBackend(backend.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for ChainBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Esplora => write!(f, "esplora"),
+            Self::Electrum => write!(f, "electrum"),
+        }
+    }
+}
+
+/// Chain backend selected by the BITCOIN_CHAIN_BACKEND configuration
+pub async fn chain_backend() -> Result<ChainBackend, BitcoinChainError> {
+    ChainBackend::from_str(&BITCOIN_CHAIN_BACKEND.read().await)
+}
+
+/// Confirmation status of a transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainTxStatus {
+    pub confirmed: bool,
+    pub block_height: Option<u32>,
+}
+
+/// Spending status of a transaction output. The status is the one of the spending transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainOutputStatus {
+    pub spent: bool,
+    pub status: Option<ChainTxStatus>,
+}
+
+/// Transaction related to a script
+#[derive(Clone, Debug)]
+pub struct ChainScriptTx {
+    pub txid: Txid,
+    pub output: Vec<TxOut>,
+    pub status: ChainTxStatus,
+}
+
+/// Blocking chain queries of the RGB resolvers
+#[cfg(not(target_arch = "wasm32"))]
+pub trait ChainSource: Send + Sync {
+    fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinChainError>;
+
+    fn get_tx_status(&self, txid: &Txid) -> Result<Option<ChainTxStatus>, BitcoinChainError>;
+
+    fn get_output_status(
+        &self,
+        txid: &Txid,
+        index: u64,
+    ) -> Result<Option<ChainOutputStatus>, BitcoinChainError>;
+
+    fn script_txs(&self, script: &Script) -> Result<Vec<ChainScriptTx>, BitcoinChainError>;
+}
+
+/// Chain source of the backend, connected to the given esplora or electrum server
+#[cfg(not(target_arch = "wasm32"))]
+pub fn chain_source(
+    backend: ChainBackend,
+    explorer_url: &str,
+    electrum_url: &str,
+) -> Result<Box<dyn ChainSource>, BitcoinChainError> {
+    let source: Box<dyn ChainSource> = match backend {
+        ChainBackend::Esplora => {
+            Box::new(esplora_block::Builder::new(explorer_url).build_blocking()?)
+        }
+        ChainBackend::Electrum => Box::new(electrum::ElectrumBlockchain::new(electrum_url)?),
+    };
+
+    Ok(source)
+}
+
+/// Electrum client of the current network, usable as a BDK blockchain
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn get_electrum() -> Result<electrum::ElectrumBlockchain, BitcoinChainError> {
+    debug!("Getting electrum blockchain");
+    electrum::ElectrumBlockchain::new(&BITCOIN_ELECTRUM_API.read().await)
+}
+
+/// Broadcasts a transaction through the configured chain backend
+pub async fn broadcast_tx(tx: &Transaction) -> Result<(), BitcoinChainError> {
+    use bdk::blockchain::Blockchain;
+
+    let backend = chain_backend().await?;
+    match backend {
+        ChainBackend::Esplora => get_blockchain()
+            .await
+            .broadcast(tx)
+            .await
+            .map_err(esplora_broadcast_err)?,
+        #[cfg(not(target_arch = "wasm32"))]
+        ChainBackend::Electrum => get_electrum().await?.transaction_broadcast(tx).await?,
+        #[cfg(target_arch = "wasm32")]
+        backend => return Err(BitcoinChainError::UnsupportedBackend(backend)),
+    };
+
+    debug!(format!(
+        "Transaction {} broadcast with {backend}",
+        tx.txid()
+    ));
+    Ok(())
+}
+
+/// Esplora answers a transaction the node refused with a client error. Rate limits and
+/// server errors are transient.
+fn esplora_broadcast_err(err: bdk::Error) -> BitcoinChainError {
+    if let bdk::Error::Esplora(esplora_err) = &err {
+        if let bdk::esplora_client::Error::Reqwest(reqwest_err) = esplora_err.as_ref() {
+            if let Some(status) = reqwest_err.status() {
+                if status.is_client_error() && status.as_u16() != 429 {
+                    return BitcoinChainError::Rejected(err.to_string());
+                }
+            }
+        }
+    }
+
+    err.into()
+}
+
+/// Height of the chain tip, from the configured chain backend
+pub async fn get_tip_height() -> Result<u32, BitcoinChainError> {
+    use bdk::blockchain::GetHeight;
+
+    let height = match chain_backend().await? {
+        ChainBackend::Esplora => get_blockchain().await.get_height().await?,
+        #[cfg(not(target_arch = "wasm32"))]
+        ChainBackend::Electrum => get_electrum().await?.get_height().await?,
+        #[cfg(target_arch = "wasm32")]
+        backend => return Err(BitcoinChainError::UnsupportedBackend(backend)),
+    };
+
+    Ok(height)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ChainSource for esplora_block::BlockingClient {
+    fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinChainError> {
+        Ok(esplora_block::BlockingClient::get_tx(self, txid)?)
+    }
+
+    fn get_tx_status(&self, txid: &Txid) -> Result<Option<ChainTxStatus>, BitcoinChainError> {
+        let status = esplora_block::BlockingClient::get_tx_status(self, txid)?;
+        Ok(Some(ChainTxStatus {
+            confirmed: status.confirmed,
+            block_height: status.block_height,
+        }))
+    }
+
+    fn get_output_status(
+        &self,
+        txid: &Txid,
+        index: u64,
+    ) -> Result<Option<ChainOutputStatus>, BitcoinChainError> {
+        let output_status = esplora_block::BlockingClient::get_output_status(self, txid, index)?;
+        Ok(output_status.map(|output_status| ChainOutputStatus {
+            spent: output_status.spent,
+            status: output_status.status.map(|status| ChainTxStatus {
+                confirmed: status.confirmed,
+                block_height: status.block_height,
+            }),
+        }))
+    }
+
+    fn script_txs(&self, script: &Script) -> Result<Vec<ChainScriptTx>, BitcoinChainError> {
+        let mut related_txs = self.scripthash_txs(script, None)?;
+        let n_confirmed = related_txs.iter().filter(|tx| tx.status.confirmed).count();
+        // esplora pages on 25 confirmed transactions. If there are 25 or more we
+        // keep requesting to see if there's more.
+        if n_confirmed >= 25 {
+            loop {
+                let new_related_txs =
+                    self.scripthash_txs(script, Some(related_txs.last().unwrap().txid))?;
+                let n = new_related_txs.len();
+                related_txs.extend(new_related_txs);
+                // we've reached the end
+                if n < 25 {
+                    break;
+                }
+            }
+        }
+
+        Ok(related_txs
+            .into_iter()
+            .map(|tx| ChainScriptTx {
+                txid: tx.txid,
+                output: tx
+                    .vout
+                    .into_iter()
+                    .map(|vout| TxOut {
+                        value: vout.value,
+                        script_pubkey: vout.scriptpubkey,
+                    })
+                    .collect(),
+                status: ChainTxStatus {
+                    confirmed: tx.status.confirmed,
+                    block_height: tx.status.block_height,
+                },
+            })
+            .collect())
+    }
+}
+
+/// Electrum backend. The BDK electrum blockchain cannot be built along the async interface
+/// used by the esplora backend, so the wallet sync is implemented on top of electrum-client.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod electrum {
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
+
+    use async_trait::async_trait;
+    use bdk::{
+        blockchain::{
+            Blockchain, Capability, GetBlockHash, GetHeight, GetTx, Progress, WalletSync,
+        },
+        database::{BatchDatabase, BatchOperations, Database, SyncTime},
+        error::MissingCachedScripts,
+        BlockTime, FeeRate, KeychainKind, LocalUtxo, TransactionDetails,
+    };
+    use bitcoin::{BlockHash, OutPoint, Script, Transaction, Txid};
+    use electrum_client::{Client, ConfigBuilder, ElectrumApi, GetHistoryRes};
+
+    use super::{BitcoinChainError, ChainOutputStatus, ChainScriptTx, ChainSource, ChainTxStatus};
+    use crate::{constants::ELECTRUM_TIMEOUT, debug};
+
+    pub struct ElectrumBlockchain {
+        client: Arc<Client>,
+        stop_gap: Option<usize>,
+    }
+
+    impl ElectrumBlockchain {
+        pub fn new(url: &str) -> Result<Self, BitcoinChainError> {
+            let config = ConfigBuilder::new()
+                .timeout(Some(ELECTRUM_TIMEOUT))?
+                .build();
+            let client = Client::from_config(url, config)?;
+            Ok(Self {
+                client: Arc::new(client),
+                stop_gap: None,
+            })
+        }
+
+        /// Requires `stop_gap` unused scripts after the last used one of each keychain,
+        /// instead of only scanning the scripts BDK cached
+        pub fn with_stop_gap(mut self, stop_gap: usize) -> Self {
+            self.stop_gap = Some(stop_gap);
+            self
+        }
+
+        /// Runs a query of the blocking electrum client on the blocking thread pool, so it
+        /// does not stall the async runtime
+        async fn query<T, F>(&self, query: F) -> Result<T, bdk::Error>
+        where
+            F: FnOnce(&Client) -> Result<T, electrum_client::Error> + Send + 'static,
+            T: Send + 'static,
+        {
+            let client = self.client.clone();
+            tokio::task::spawn_blocking(move || query(&client))
+                .await
+                .map_err(|err| bdk::Error::Generic(format!("Electrum query failed: {err}")))?
+                .map_err(electrum_err)
+        }
+
+        async fn batch_transaction_get(
+            &self,
+            txids: Vec<Txid>,
+        ) -> Result<HashMap<Txid, Transaction>, bdk::Error> {
+            if txids.is_empty() {
+                return Ok(HashMap::new());
+            }
+
+            let txs = self
+                .query(move |client| client.batch_transaction_get(txids.iter()))
+                .await?;
+            Ok(txs.into_iter().map(|tx| (tx.txid(), tx)).collect())
+        }
+
+        /// Broadcasts the transaction. The server relays the refusal of the node as a
+        /// protocol error, other errors are transient.
+        pub async fn transaction_broadcast(
+            &self,
+            tx: &Transaction,
+        ) -> Result<(), BitcoinChainError> {
+            let tx = tx.clone();
+            let client = self.client.clone();
+            let broadcast = tokio::task::spawn_blocking(move || client.transaction_broadcast(&tx))
+                .await
+                .map_err(|err| bdk::Error::Generic(format!("Electrum query failed: {err}")))?;
+            match broadcast {
+                Ok(_) => Ok(()),
+                Err(electrum_client::Error::Protocol(err)) => {
+                    Err(BitcoinChainError::Rejected(err.to_string()))
+                }
+                Err(err) => Err(err.into()),
+            }
+        }
+
+        fn history_status(history: &GetHistoryRes) -> ChainTxStatus {
+            // Electrum reports mempool transactions at height 0, or -1 with unconfirmed parents
+            match history.height {
+                height if height > 0 => ChainTxStatus {
+                    confirmed: true,
+                    block_height: Some(height as u32),
+                },
+                _ => ChainTxStatus::default(),
+            }
+        }
+
+        fn tx_history(
+            &self,
+            tx: &Transaction,
+            index: usize,
+        ) -> Result<Vec<GetHistoryRes>, BitcoinChainError> {
+            match tx.output.get(index) {
+                Some(output) => Ok(self.client.script_get_history(&output.script_pubkey)?),
+                None => Ok(vec![]),
+            }
+        }
+    }
+
+    fn electrum_err(err: electrum_client::Error) -> bdk::Error {
+        bdk::Error::Generic(format!("Electrum error: {err}"))
+    }
+
+    impl ChainSource for ElectrumBlockchain {
+        fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinChainError> {
+            match self.client.transaction_get(txid) {
+                Ok(tx) => Ok(Some(tx)),
+                // Servers answer unknown transactions with a protocol error
+                Err(electrum_client::Error::Protocol(_)) => Ok(None),
+                Err(err) => Err(err.into()),
+            }
+        }
+
+        fn get_tx_status(&self, txid: &Txid) -> Result<Option<ChainTxStatus>, BitcoinChainError> {
+            let tx = match ChainSource::get_tx(self, txid)? {
+                Some(tx) => tx,
+                None => return Ok(None),
+            };
+
+            // Electrum servers do not index OP_RETURN scripts, such as those of opret anchors
+            // and burns
+            let index = tx
+                .output
+                .iter()
+                .position(|output| !output.script_pubkey.is_op_return())
+                .ok_or_else(|| BitcoinChainError::NoIndexedOutput(txid.to_string()))?;
+
+            Ok(self
+                .tx_history(&tx, index)?
+                .iter()
+                .find(|history| history.tx_hash == *txid)
+                .map(Self::history_status))
+        }
+
+        fn get_output_status(
+            &self,
+            txid: &Txid,
+            index: u64,
+        ) -> Result<Option<ChainOutputStatus>, BitcoinChainError> {
+            let tx = match ChainSource::get_tx(self, txid)? {
+                Some(tx) if (index as usize) < tx.output.len() => tx,
+                _ => return Ok(None),
+            };
+
+            let outpoint = OutPoint::new(*txid, index as u32);
+            for history in self.tx_history(&tx, index as usize)? {
+                if history.tx_hash == *txid {
+                    continue;
+                }
+                let spending_tx = self.client.transaction_get(&history.tx_hash)?;
+                if spending_tx
+                    .input
+                    .iter()
+                    .any(|input| input.previous_output == outpoint)
+                {
+                    return Ok(Some(ChainOutputStatus {
+                        spent: true,
+                        status: Some(Self::history_status(&history)),
+                    }));
+                }
+            }
+
+            Ok(Some(ChainOutputStatus::default()))
+        }
+
+        fn script_txs(&self, script: &Script) -> Result<Vec<ChainScriptTx>, BitcoinChainError> {
+            let history = self.client.script_get_history(script)?;
+            let txs = self
+                .client
+                .batch_transaction_get(history.iter().map(|history| &history.tx_hash))?;
+
+            Ok(history
+                .iter()
+                .zip(txs)
+                .map(|(history, tx)| ChainScriptTx {
+                    txid: history.tx_hash,
+                    output: tx.output,
+                    status: Self::history_status(history),
+                })
+                .collect())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl Blockchain for ElectrumBlockchain {
+        fn get_capabilities(&self) -> HashSet<Capability> {
+            vec![
+                Capability::FullHistory,
+                Capability::GetAnyTx,
+                Capability::AccurateFees,
+            ]
+            .into_iter()
+            .collect()
+        }
+
+        async fn broadcast(&self, tx: &Transaction) -> Result<(), bdk::Error> {
+            let tx = tx.clone();
+            self.query(move |client| client.transaction_broadcast(&tx))
+                .await?;
+            Ok(())
+        }
+
+        async fn estimate_fee(&self, target: usize) -> Result<FeeRate, bdk::Error> {
+            // Electrum estimates are in BTC/kvB
+            let btc_per_kvb = self
+                .query(move |client| client.estimate_fee(target))
+                .await?;
+            Ok(FeeRate::from_btc_per_kvb(btc_per_kvb as f32))
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl GetHeight for ElectrumBlockchain {
+        async fn get_height(&self) -> Result<u32, bdk::Error> {
+            let tip = self
+                .query(|client| client.block_headers_subscribe())
+                .await?;
+            Ok(tip.height as u32)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl GetTx for ElectrumBlockchain {
+        async fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, bdk::Error> {
+            let txid = *txid;
+            let result = self
+                .query(move |client| match client.transaction_get(&txid) {
+                    Ok(tx) => Ok(Some(tx)),
+                    // Servers answer unknown transactions with a protocol error
+                    Err(electrum_client::Error::Protocol(_)) => Ok(None),
+                    Err(err) => Err(err),
+                })
+                .await?;
+            Ok(result)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl GetBlockHash for ElectrumBlockchain {
+        async fn get_block_hash(&self, height: u64) -> Result<BlockHash, bdk::Error> {
+            let header = self
+                .query(move |client| client.block_header(height as usize))
+                .await?;
+            Ok(header.block_hash())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl WalletSync for ElectrumBlockchain {
+        /// Updates the wallet transactions and UTXOs from the history of every cached script.
+        /// Transactions, previous transactions and block times already in the database are
+        /// not downloaded again, and only the UTXOs that changed are written.
+        /// BDK caches more scripts and calls again when the last ones are used.
+        async fn wallet_setup<D: BatchDatabase>(
+            &self,
+            database: &RefCell<D>,
+            _progress_update: Box<dyn Progress>,
+        ) -> Result<(), bdk::Error> {
+            let tip = self
+                .query(|client| client.block_headers_subscribe())
+                .await?;
+
+            let mut heights: HashMap<Txid, i32> = HashMap::new();
+            let mut last_indexes = HashMap::new();
+            for keychain in [KeychainKind::External, KeychainKind::Internal] {
+                let scripts = database.borrow().iter_script_pubkeys(Some(keychain))?;
+                let script_count = scripts.len();
+                let histories = self
+                    .query(move |client| client.batch_script_get_history(scripts.iter()))
+                    .await?;
+                for (index, history) in histories.into_iter().enumerate() {
+                    if !history.is_empty() {
+                        last_indexes.insert(keychain, index as u32);
+                    }
+                    for item in history {
+                        heights.insert(item.tx_hash, item.height);
+                    }
+                }
+
+                // BDK caches the missing scripts and calls again
+                if let (Some(stop_gap), Some(last_index)) =
+                    (self.stop_gap, last_indexes.get(&keychain))
+                {
+                    let needed = *last_index as usize + 1 + stop_gap;
+                    if script_count < needed {
+                        return Err(bdk::Error::MissingCachedScripts(MissingCachedScripts {
+                            last_count: script_count,
+                            missing_count: needed - script_count,
+                        }));
+                    }
+                }
+            }
+
+            let (known_txs, known_utxos) = {
+                let database = database.borrow();
+                let known_txs: HashMap<Txid, TransactionDetails> = database
+                    .iter_txs(true)?
+                    .into_iter()
+                    .map(|details| (details.txid, details))
+                    .collect();
+                let known_utxos: HashSet<OutPoint> = database
+                    .iter_utxos()?
+                    .into_iter()
+                    .map(|utxo| utxo.outpoint)
+                    .collect();
+                (known_txs, known_utxos)
+            };
+
+            let mut txs: HashMap<Txid, Transaction> = heights
+                .keys()
+                .filter_map(|txid| {
+                    let tx = known_txs.get(txid)?.transaction.clone()?;
+                    Some((*txid, tx))
+                })
+                .collect();
+            let missing_txids: Vec<Txid> = heights
+                .keys()
+                .filter(|txid| !txs.contains_key(txid))
+                .cloned()
+                .collect();
+            let new_txs = self.batch_transaction_get(missing_txids).await?;
+            txs.extend(new_txs);
+
+            // Previous outputs are needed to compute the amounts sent and the fees
+            let mut prev_txs: HashMap<Txid, Transaction> = HashMap::new();
+            let mut missing_prev_txids = vec![];
+            for txid in txs
+                .values()
+                .flat_map(|tx| tx.input.iter().map(|input| input.previous_output.txid))
+                .filter(|txid| !txs.contains_key(txid))
+                .collect::<HashSet<Txid>>()
+            {
+                match database.borrow().get_raw_tx(&txid)? {
+                    Some(tx) => {
+                        prev_txs.insert(txid, tx);
+                    }
+                    None => missing_prev_txids.push(txid),
+                }
+            }
+            let new_prev_txs = self.batch_transaction_get(missing_prev_txids).await?;
+
+            let mut timestamps: HashMap<u32, u64> = known_txs
+                .values()
+                .filter_map(|details| details.confirmation_time.as_ref())
+                .map(|time| (time.height, time.timestamp))
+                .collect();
+            let mut missing_heights: Vec<u32> = heights
+                .values()
+                .filter(|height| **height > 0)
+                .map(|height| *height as u32)
+                .filter(|height| !timestamps.contains_key(height))
+                .collect();
+            missing_heights.sort_unstable();
+            missing_heights.dedup();
+            if !missing_heights.is_empty() {
+                let query_heights = missing_heights.clone();
+                let headers = self
+                    .query(move |client| client.batch_block_header(query_heights))
+                    .await?;
+                timestamps.extend(
+                    missing_heights
+                        .into_iter()
+                        .zip(headers.into_iter().map(|header| header.time as u64)),
+                );
+            }
+
+            let spent: HashSet<OutPoint> = txs
+                .values()
+                .flat_map(|tx| tx.input.iter().map(|input| input.previous_output))
+                .collect();
+
+            let mut database = database.borrow_mut();
+            let mut batch = database.begin_batch();
+            for txid in known_txs.keys() {
+                if !txs.contains_key(txid) {
+                    batch.del_tx(txid, true)?;
+                }
+            }
+            for tx in new_prev_txs.values() {
+                batch.set_raw_tx(tx)?;
+            }
+            prev_txs.extend(new_prev_txs);
+
+            let mut utxos: HashSet<OutPoint> = HashSet::new();
+            for (txid, tx) in &txs {
+                let mut received = 0;
+                for (vout, output) in tx.output.iter().enumerate() {
+                    let keychain =
+                        match database.get_path_from_script_pubkey(&output.script_pubkey)? {
+                            Some((keychain, _)) => keychain,
+                            None => continue,
+                        };
+                    received += output.value;
+
+                    let outpoint = OutPoint::new(*txid, vout as u32);
+                    if spent.contains(&outpoint) {
+                        continue;
+                    }
+                    utxos.insert(outpoint);
+                    if !known_utxos.contains(&outpoint) {
+                        batch.set_utxo(&LocalUtxo {
+                            outpoint,
+                            txout: output.clone(),
+                            keychain,
+                            is_spent: false,
+                        })?;
+                    }
+                }
+
+                let mut sent = 0;
+                let mut inputs = Some(0);
+                for input in &tx.input {
+                    let prevout = txs
+                        .get(&input.previous_output.txid)
+                        .or_else(|| prev_txs.get(&input.previous_output.txid))
+                        .and_then(|prev_tx| {
+                            prev_tx.output.get(input.previous_output.vout as usize)
+                        });
+                    match prevout {
+                        Some(prevout) => {
+                            inputs = inputs.map(|total| total + prevout.value);
+                            if database
+                                .get_path_from_script_pubkey(&prevout.script_pubkey)?
+                                .is_some()
+                            {
+                                sent += prevout.value;
+                            }
+                        }
+                        None => inputs = None,
+                    }
+                }
+                let outputs: u64 = tx.output.iter().map(|output| output.value).sum();
+                let fee = if tx.is_coin_base() {
+                    Some(0)
+                } else {
+                    inputs.map(|inputs| inputs.saturating_sub(outputs))
+                };
+
+                let height = heights.get(txid).cloned().unwrap_or_default();
+                let confirmation_time = if height > 0 {
+                    timestamps.get(&(height as u32)).map(|timestamp| BlockTime {
+                        height: height as u32,
+                        timestamp: *timestamp,
+                    })
+                } else {
+                    None
+                };
+
+                let details = TransactionDetails {
+                    transaction: Some(tx.clone()),
+                    txid: *txid,
+                    received,
+                    sent,
+                    fee,
+                    confirmation_time,
+                };
+                if known_txs.get(txid) != Some(&details) {
+                    batch.set_tx(&details)?;
+                }
+            }
+
+            // Outputs spent or dropped from the mempool since the last sync
+            for outpoint in known_utxos.difference(&utxos) {
+                batch.del_utxo(outpoint)?;
+            }
+
+            for (keychain, index) in last_indexes {
+                let current = database.get_last_index(keychain)?.unwrap_or_default();
+                batch.set_last_index(keychain, std::cmp::max(current, index))?;
+            }
+
+            batch.set_sync_time(SyncTime {
+                block_time: BlockTime {
+                    height: tip.height as u32,
+                    timestamp: tip.header.time as u64,
+                },
+            })?;
+            database.commit_batch(batch)?;
+
+            debug!(format!(
+                "Electrum sync found {} transactions at height {}",
+                txs.len(),
+                tip.height
+            ));
+            Ok(())
+        }
+    }
+}
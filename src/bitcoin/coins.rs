@@ -0,0 +1,122 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use bdk::{
+    database::BatchDatabase,
+    wallet::{
+        coin_selection::CoinSelectionAlgorithm,
+        tx_builder::{TxBuilder, TxBuilderContext},
+    },
+};
+use bitcoin::OutPoint;
+use postcard::{from_bytes, to_allocvec};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    carbonado::{error::CarbonadoError, retrieve, store},
+    constants::storage_keys::BITCOIN_FROZEN_UTXOS,
+    structs::CoinControl,
+};
+
+#[derive(Error, Debug)]
+pub enum BitcoinCoinsError {
+    /// Outpoint is not formatted as {txid}:{vout}
+    #[error("Invalid outpoint: {0}")]
+    InvalidOutpoint(String),
+    /// Outpoint is frozen and cannot be spent
+    #[error("Outpoint {0} is frozen and cannot be spent")]
+    FrozenOutpoint(String),
+    /// Outpoint is both included and excluded
+    #[error("Outpoint {0} cannot be included and excluded at the same time")]
+    ConflictingOutpoint(String),
+    /// Postcard error
+    #[error(transparent)]
+    PostcardError(#[from] postcard::Error),
+    /// Carbonado error
+    #[error(transparent)]
+    CarbonadoError(#[from] CarbonadoError),
+}
+
+/// Outpoints frozen by the user. Coin selection never spends them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FrozenUtxos {
+    pub utxos: BTreeSet<String>,
+}
+
+pub async fn retrieve_frozen_utxos(sk: &str) -> Result<FrozenUtxos, BitcoinCoinsError> {
+    let (data, _) = retrieve(sk, BITCOIN_FROZEN_UTXOS, vec![]).await?;
+    if data.is_empty() {
+        return Ok(FrozenUtxos::default());
+    }
+
+    Ok(from_bytes(&data)?)
+}
+
+pub async fn store_frozen_utxos(sk: &str, frozen: &FrozenUtxos) -> Result<(), BitcoinCoinsError> {
+    let data = to_allocvec(frozen)?;
+    store(sk, BITCOIN_FROZEN_UTXOS, &data, true, None).await?;
+    Ok(())
+}
+
+pub fn parse_outpoint(outpoint: &str) -> Result<OutPoint, BitcoinCoinsError> {
+    OutPoint::from_str(outpoint).map_err(|_| BitcoinCoinsError::InvalidOutpoint(outpoint.into()))
+}
+
+/// Coin selection constraints, resolved against the frozen set of the wallet
+#[derive(Debug, Clone, Default)]
+pub struct UtxoSelection {
+    pub include: Vec<OutPoint>,
+    pub exclude: Vec<OutPoint>,
+    pub manual: bool,
+}
+
+impl UtxoSelection {
+    pub fn new(
+        coin_control: &CoinControl,
+        frozen: &BTreeSet<String>,
+    ) -> Result<Self, BitcoinCoinsError> {
+        let include = coin_control
+            .include
+            .iter()
+            .map(|outpoint| parse_outpoint(outpoint))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut exclude = BTreeSet::new();
+        for outpoint in coin_control.exclude.iter().chain(frozen.iter()) {
+            exclude.insert(parse_outpoint(outpoint)?);
+        }
+
+        for outpoint in &include {
+            if exclude.contains(outpoint) {
+                let outpoint = outpoint.to_string();
+                return Err(if frozen.contains(&outpoint) {
+                    BitcoinCoinsError::FrozenOutpoint(outpoint)
+                } else {
+                    BitcoinCoinsError::ConflictingOutpoint(outpoint)
+                });
+            }
+        }
+
+        Ok(Self {
+            include,
+            exclude: exclude.into_iter().collect(),
+            manual: coin_control.manual,
+        })
+    }
+
+    pub fn apply<D: BatchDatabase, Cs: CoinSelectionAlgorithm<D>, Ctx: TxBuilderContext>(
+        &self,
+        builder: &mut TxBuilder<'_, D, Cs, Ctx>,
+    ) -> Result<(), bdk::Error> {
+        if !self.include.is_empty() {
+            builder.add_utxos(&self.include)?;
+        }
+        if !self.exclude.is_empty() {
+            builder.unspendable(self.exclude.clone());
+        }
+        if self.manual {
+            builder.manually_selected_only();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use bdk::FeeRate;
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+use crate::{
+    bitcoin::wallet::get_blockchain,
+    constants::BITCOIN_EXPLORER_API,
+    debug,
+    structs::{FeeEstimatesResponse, FeeRateRequest, FeeTarget, PsbtFeeRequest},
+    warn,
+};
+
+/// Default minimum relay fee rate of Bitcoin Core nodes (sats/vB)
+pub const MIN_RELAY_FEE_RATE: f32 = 1.0;
+/// Fee rates above this value are rejected as a probable mistake (sats/vB)
+pub const MAX_FEE_RATE: f32 = 1_000.0;
+/// Seconds the explorer fee estimates are cached
+const FEE_ESTIMATES_TTL: i64 = 60;
+
+#[derive(Error, Debug)]
+pub enum BitcoinFeesError {
+    /// Fee rate is negative or not a number
+    #[error("Invalid fee rate: {0}")]
+    InvalidFeeRate(f32),
+    /// Fee rate is above the sanity ceiling
+    #[error("Fee rate {0} sats/vB is above the maximum of {MAX_FEE_RATE} sats/vB")]
+    FeeRateTooHigh(f32),
+    /// Explorer returned no fee estimates
+    #[error("No fee estimates available from the explorer")]
+    NoEstimates,
+    /// BDK esplora error
+    #[error(transparent)]
+    BdkEsploraError(#[from] bdk::esplora_client::Error),
+}
+
+/// Fee rates (sats/vB) indexed by confirmation target (blocks)
+type FeeEstimates = BTreeMap<u16, f32>;
+
+static FEE_ESTIMATES: Lazy<RwLock<BTreeMap<String, (i64, FeeEstimates)>>> =
+    Lazy::new(Default::default);
+
+/// Fee estimates of the configured explorer, cached for a short period
+async fn fetch_fee_estimates() -> Result<FeeEstimates, BitcoinFeesError> {
+    let explorer_url = BITCOIN_EXPLORER_API.read().await.to_string();
+    let now = chrono::Utc::now().timestamp();
+
+    if let Some((timestamp, estimates)) = FEE_ESTIMATES.read().await.get(&explorer_url) {
+        if now - timestamp < FEE_ESTIMATES_TTL {
+            return Ok(estimates.clone());
+        }
+    }
+
+    let blockchain = get_blockchain().await;
+    let estimates: FeeEstimates = blockchain
+        .get_fee_estimates()
+        .await?
+        .into_iter()
+        .filter_map(|(target, fee_rate)| Some((target.parse().ok()?, fee_rate as f32)))
+        .collect();
+    debug!(format!("Fee estimates: {estimates:?}"));
+
+    if estimates.is_empty() {
+        return Err(BitcoinFeesError::NoEstimates);
+    }
+
+    FEE_ESTIMATES
+        .write()
+        .await
+        .insert(explorer_url, (now, estimates.clone()));
+
+    Ok(estimates)
+}
+
+/// Fee rate to confirm within the target. Uses the estimate of the closest target
+/// not above the requested one.
+pub async fn estimate_fee_rate(target: FeeTarget) -> Result<f32, BitcoinFeesError> {
+    let blocks = target.blocks();
+    let estimates = fetch_fee_estimates().await?;
+    let fee_rate = estimates
+        .range(..=blocks)
+        .next_back()
+        .or_else(|| estimates.iter().next())
+        .map(|(_, fee_rate)| *fee_rate)
+        .ok_or(BitcoinFeesError::NoEstimates)?;
+
+    check_fee_rate(fee_rate)
+}
+
+/// Raises the fee rate to the min-relay floor and rejects it above the sanity ceiling
+pub fn check_fee_rate(fee_rate: f32) -> Result<f32, BitcoinFeesError> {
+    if !fee_rate.is_finite() || fee_rate < 0.0 {
+        return Err(BitcoinFeesError::InvalidFeeRate(fee_rate));
+    }
+    if fee_rate > MAX_FEE_RATE {
+        return Err(BitcoinFeesError::FeeRateTooHigh(fee_rate));
+    }
+
+    Ok(fee_rate.max(MIN_RELAY_FEE_RATE))
+}
+
+/// Resolves a fee request into a fee rate. Without a request, the medium target is used,
+/// falling back to the min-relay fee rate when the explorer has no estimates.
+pub async fn resolve_fee_rate(
+    fee_rate: Option<FeeRateRequest>,
+) -> Result<FeeRate, BitcoinFeesError> {
+    let fee_rate = match fee_rate {
+        Some(FeeRateRequest::Rate(fee_rate)) => check_fee_rate(fee_rate)?,
+        Some(FeeRateRequest::Target(target)) => estimate_fee_rate(target).await?,
+        None => match estimate_fee_rate(FeeTarget::Medium).await {
+            Ok(fee_rate) => fee_rate,
+            Err(err) => {
+                warn!(format!(
+                    "Using min-relay fee rate, fee estimation failed: {err}"
+                ));
+                MIN_RELAY_FEE_RATE
+            }
+        },
+    };
+
+    Ok(FeeRate::from_sat_per_vb(fee_rate))
+}
+
+/// Resolves the confirmation target of an RGB PSBT fee into a fee rate
+pub async fn resolve_psbt_fee(fee: PsbtFeeRequest) -> Result<PsbtFeeRequest, BitcoinFeesError> {
+    let fee = match fee {
+        PsbtFeeRequest::Value(fee_value) => PsbtFeeRequest::Value(fee_value),
+        PsbtFeeRequest::FeeRate(fee_rate) => PsbtFeeRequest::FeeRate(check_fee_rate(fee_rate)?),
+        PsbtFeeRequest::Target(target) => PsbtFeeRequest::FeeRate(estimate_fee_rate(target).await?),
+    };
+
+    Ok(fee)
+}
+
+pub async fn get_fee_estimates() -> Result<FeeEstimatesResponse, BitcoinFeesError> {
+    let estimates = fetch_fee_estimates().await?;
+
+    Ok(FeeEstimatesResponse {
+        fast: estimate_fee_rate(FeeTarget::Fast).await?,
+        medium: estimate_fee_rate(FeeTarget::Medium).await?,
+        slow: estimate_fee_rate(FeeTarget::Slow).await?,
+        min_relay: MIN_RELAY_FEE_RATE,
+        estimates,
+    })
+}
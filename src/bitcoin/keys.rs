@@ -3,14 +3,16 @@ use std::str::FromStr;
 use bdk::{
     bitcoin::{
         secp256k1::Secp256k1,
-        util::bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey, KeySource},
+        util::bip32::{
+            ChainCode, ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey, KeySource,
+        },
     },
     keys::{DerivableKey, DescriptorKey, DescriptorKey::Secret as SecretDesc, DescriptorSecretKey},
     miniscript::{descriptor::DescriptorKeyParseError, Tap},
 };
 use bip39::{Language, Mnemonic};
-use bitcoin::{KeyPair, Network};
-use bitcoin_hashes::{sha256, Hash};
+use bitcoin::{secp256k1::SecretKey, KeyPair, Network, PrivateKey};
+use bitcoin_hashes::{hmac, sha256, sha512, Hash, HashEngine};
 use miniscript_crate::{
     descriptor::{DescriptorXKey, Wildcard},
     DescriptorPublicKey,
@@ -21,7 +23,10 @@ use zeroize::Zeroize;
 
 use crate::{
     constants::{get_marketplace_fee_xpub, get_network, BTC_PATH, NETWORK},
-    structs::{DecryptedWalletData, PrivateWalletData, PublicWalletData, SecretString},
+    structs::{
+        Bip85Application, Bip85Response, DecryptedWalletData, PrivateWalletData, PublicWalletData,
+        SecretString, WalletAccount,
+    },
 };
 
 #[derive(Error, Debug)]
@@ -56,6 +61,12 @@ pub enum BitcoinKeysError {
     /// Nostr SDK key error
     #[error(transparent)]
     NostrKeyError(#[from] nostr_sdk::key::Error),
+    /// BIP-85 mnemonic length not supported
+    #[error("BIP-85 mnemonics have 12, 18 or 24 words, not {0}")]
+    InvalidBip85Words(u8),
+    /// BIP-85 hex entropy length out of range
+    #[error("BIP-85 hex entropy has 16 to 64 bytes, not {0}")]
+    InvalidBip85Length(u8),
     /// Nostr SDK key error
     #[error(transparent)]
     NostrNip19Error(#[from] nostr_sdk::nips::nip19::Error),
@@ -112,10 +123,10 @@ fn watcher_xpub(
     }
 }
 
-// For NIP-06 Nostr signing and Carbonado encryption key derivation
-fn nostr_keypair(xprv: &ExtendedPrivKey) -> Result<(String, String), BitcoinKeysError> {
-    pub const NOSTR_PATH: &str = "m/44'/1237'/0'/0/0";
-    let deriv_descriptor = DerivationPath::from_str(NOSTR_PATH)?;
+// For NIP-06 Nostr signing and Carbonado encryption key derivation, at the account index
+fn nostr_keypair(xprv: &ExtendedPrivKey, index: u32) -> Result<(String, String), BitcoinKeysError> {
+    let nostr_path = format!("m/44'/1237'/{index}'/0/0");
+    let deriv_descriptor = DerivationPath::from_str(&nostr_path)?;
     let secp = Secp256k1::new();
     let nostr_sk = xprv.derive_priv(&secp, &deriv_descriptor)?;
     let keypair =
@@ -151,30 +162,88 @@ pub async fn get_mnemonic(
     mnemonic_phrase: Mnemonic,
     seed_password: &SecretString,
 ) -> Result<DecryptedWalletData, BitcoinKeysError> {
-    let seed = mnemonic_phrase.to_seed_normalized(&seed_password.0);
+    let xprv = master_xprv(&mnemonic_phrase, seed_password).await?;
+    let (private, public) = account_data(&xprv, 0).await?;
+
+    Ok(DecryptedWalletData {
+        mnemonic: mnemonic_phrase.to_string(),
+        private,
+        public,
+        accounts: vec![],
+    })
+}
+
+/// Derives a named account of the mnemonic at the account index
+pub async fn get_account(
+    mnemonic_phrase: &SecretString,
+    seed_password: &SecretString,
+    name: &str,
+    index: u32,
+) -> Result<WalletAccount, BitcoinKeysError> {
+    let mnemonic = Mnemonic::from_str(&mnemonic_phrase.0)?;
+    let xprv = master_xprv(&mnemonic, seed_password).await?;
+    let (private, public) = account_data(&xprv, index).await?;
+
+    Ok(WalletAccount {
+        name: name.to_owned(),
+        index,
+        private,
+        public,
+    })
+}
+
+async fn master_xprv(
+    mnemonic_phrase: &Mnemonic,
+    seed_password: &SecretString,
+) -> Result<ExtendedPrivKey, BitcoinKeysError> {
+    let mut seed = mnemonic_phrase.to_seed_normalized(&seed_password.0);
 
     let network = NETWORK.read().await;
-    let xprv = ExtendedPrivKey::new_master(*network, &seed)?;
-    let xprvkh = sha256::Hash::hash(&xprv.to_priv().to_bytes()).to_string();
+    let xprv = ExtendedPrivKey::new_master(*network, &seed);
+    seed.zeroize();
 
+    Ok(xprv?)
+}
+
+/// Account level derivation path, replacing the account index of `BTC_PATH`
+async fn account_path(index: u32) -> Result<String, BitcoinKeysError> {
+    let btc_path = DerivationPath::from_str(&BTC_PATH.read().await)?;
+    let mut path: Vec<ChildNumber> = btc_path.into();
+    path.pop();
+    path.push(ChildNumber::from_hardened_idx(index)?);
+
+    Ok(DerivationPath::from(path).to_string())
+}
+
+async fn account_data(
+    xprv: &ExtendedPrivKey,
+    index: u32,
+) -> Result<(PrivateWalletData, PublicWalletData), BitcoinKeysError> {
     let secp = Secp256k1::new();
-    let xpub = ExtendedPubKey::from_priv(&secp, &xprv);
-    let xpubkh = xpub.to_pub().pubkey_hash().to_string();
+    let btc_path = account_path(index).await?;
 
-    let btc_path = BTC_PATH.read().await;
+    // The first account keeps the keys of the master key, so existing wallets don't change
+    let account_xprv = if index == 0 {
+        *xprv
+    } else {
+        xprv.derive_priv(&secp, &DerivationPath::from_str(&btc_path)?)?
+    };
+    let xpub = ExtendedPubKey::from_priv(&secp, &account_xprv);
+    let xpubkh = xpub.to_pub().pubkey_hash().to_string();
+    let xprvkh = sha256::Hash::hash(&account_xprv.to_priv().to_bytes()).to_string();
 
-    let btc_descriptor_xprv = xprv_desc(&xprv, &btc_path, 0)?;
-    let btc_change_descriptor_xprv = xprv_desc(&xprv, &btc_path, 1)?;
+    let btc_descriptor_xprv = xprv_desc(xprv, &btc_path, 0)?;
+    let btc_change_descriptor_xprv = xprv_desc(xprv, &btc_path, 1)?;
 
-    let btc_descriptor_xpub = xpub_desc(&xprv, &btc_path, 0)?;
-    let btc_change_descriptor_xpub = xpub_desc(&xprv, &btc_path, 1)?;
-    let rgb_assets_descriptor_xprv = xprv_desc(&xprv, &btc_path, 20)?;
-    let rgb_udas_descriptor_xprv = xprv_desc(&xprv, &btc_path, 21)?;
-    let rgb_assets_descriptor_xpub = xpub_desc(&xprv, &btc_path, 20)?;
-    let rgb_udas_descriptor_xpub = xpub_desc(&xprv, &btc_path, 21)?;
-    let watcher_xpub = watcher_xpub(&xprv, &btc_path, 0)?;
+    let btc_descriptor_xpub = xpub_desc(xprv, &btc_path, 0)?;
+    let btc_change_descriptor_xpub = xpub_desc(xprv, &btc_path, 1)?;
+    let rgb_assets_descriptor_xprv = xprv_desc(xprv, &btc_path, 20)?;
+    let rgb_udas_descriptor_xprv = xprv_desc(xprv, &btc_path, 21)?;
+    let rgb_assets_descriptor_xpub = xpub_desc(xprv, &btc_path, 20)?;
+    let rgb_udas_descriptor_xpub = xpub_desc(xprv, &btc_path, 21)?;
+    let watcher_xpub = watcher_xpub(xprv, &btc_path, 0)?;
 
-    let (nostr_prv, nostr_pub) = nostr_keypair(&xprv)?;
+    let (nostr_prv, nostr_pub) = nostr_keypair(xprv, index)?;
     let nostr_keys = nostr_sdk::Keys::from_sk_str(&nostr_prv)?;
     let nostr_nsec = nostr_keys.secret_key()?.to_bech32()?;
     let nostr_npub = nostr_keys.public_key().to_bech32()?;
@@ -201,13 +270,86 @@ pub async fn get_mnemonic(
         nostr_npub,
     };
 
-    Ok(DecryptedWalletData {
-        mnemonic: mnemonic_phrase.to_string(),
-        private,
-        public,
+    Ok((private, public))
+}
+
+/// BIP-85 root of the derivation paths of the applications
+const BIP85_PATH: &str = "m/83696968'";
+const BIP85_HMAC_KEY: &[u8] = b"bip-entropy-from-k";
+
+/// Derives a BIP-85 child of the master key at the index
+pub fn derive_bip85(
+    xprv: &ExtendedPrivKey,
+    application: &Bip85Application,
+    index: u32,
+) -> Result<Bip85Response, BitcoinKeysError> {
+    let path = match application {
+        Bip85Application::Mnemonic { words } => {
+            if ![12, 18, 24].contains(words) {
+                return Err(BitcoinKeysError::InvalidBip85Words(*words));
+            }
+            // English is language 0
+            format!("{BIP85_PATH}/39'/0'/{words}'/{index}'")
+        }
+        Bip85Application::Wif => format!("{BIP85_PATH}/2'/{index}'"),
+        Bip85Application::Xprv => format!("{BIP85_PATH}/32'/{index}'"),
+        Bip85Application::Hex { num_bytes } => {
+            if !(16..=64).contains(num_bytes) {
+                return Err(BitcoinKeysError::InvalidBip85Length(*num_bytes));
+            }
+            format!("{BIP85_PATH}/128169'/{num_bytes}'/{index}'")
+        }
+    };
+
+    let secp = Secp256k1::new();
+    let derived = xprv.derive_priv(&secp, &DerivationPath::from_str(&path)?)?;
+    let mut engine = hmac::HmacEngine::<sha512::Hash>::new(BIP85_HMAC_KEY);
+    engine.input(&derived.private_key.secret_bytes());
+    let mut entropy = hmac::Hmac::<sha512::Hash>::from_engine(engine)[..].to_vec();
+
+    let child = match application {
+        Bip85Application::Mnemonic { words } => {
+            let len = usize::from(*words) * 4 / 3;
+            Mnemonic::from_entropy_in(Language::English, &entropy[..len])?.to_string()
+        }
+        Bip85Application::Wif => PrivateKey {
+            compressed: true,
+            network: xprv.network,
+            inner: SecretKey::from_slice(&entropy[..32])?,
+        }
+        .to_wif(),
+        Bip85Application::Xprv => ExtendedPrivKey {
+            network: xprv.network,
+            depth: 0,
+            parent_fingerprint: Default::default(),
+            child_number: ChildNumber::from_normal_idx(0)?,
+            private_key: SecretKey::from_slice(&entropy[32..])?,
+            chain_code: ChainCode::from(&entropy[..32]),
+        }
+        .to_string(),
+        Bip85Application::Hex { num_bytes } => hex::encode(&entropy[..usize::from(*num_bytes)]),
+    };
+    entropy.zeroize();
+
+    Ok(Bip85Response {
+        path,
+        child: SecretString(child),
     })
 }
 
+/// Derives a BIP-85 child of the master key of the mnemonic at the index
+pub async fn get_bip85(
+    mnemonic_phrase: &SecretString,
+    seed_password: &SecretString,
+    application: &Bip85Application,
+    index: u32,
+) -> Result<Bip85Response, BitcoinKeysError> {
+    let mnemonic = Mnemonic::from_str(&mnemonic_phrase.0)?;
+    let xprv = master_xprv(&mnemonic, seed_password).await?;
+
+    derive_bip85(&xprv, application, index)
+}
+
 pub async fn get_marketplace_descriptor() -> Result<Option<SecretString>, BitcoinKeysError> {
     let btc_path = BTC_PATH.read().await;
     let marketplace_xpub = get_marketplace_fee_xpub().await;
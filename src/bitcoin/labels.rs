@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use bdk::KeychainKind;
+use bitcoin_hashes::{sha256, Hash};
+use thiserror::Error;
+
+use crate::{
+    bitcoin::wallet::WalletHandle,
+    carbonado::{error::CarbonadoError, retrieve, store},
+    constants::storage_keys::BITCOIN_LABELS,
+    structs::{LabelType, WalletLabel},
+};
+
+#[derive(Error, Debug)]
+pub enum BitcoinLabelsError {
+    /// Wallet has no public descriptor
+    #[error("Wallet has no public descriptor")]
+    NoDescriptor,
+    /// Label record cannot be parsed
+    #[error("Invalid BIP-329 label on line {0}: {1}")]
+    InvalidLabel(usize, String),
+    /// BDK error
+    #[error(transparent)]
+    BdkError(#[from] bdk::Error),
+    /// Serde JSON error
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+    /// UTF-8 error
+    #[error(transparent)]
+    Utf8Error(#[from] std::string::FromUtf8Error),
+    /// Carbonado error
+    #[error(transparent)]
+    CarbonadoError(#[from] CarbonadoError),
+}
+
+/// Labels of a wallet, indexed by type and reference
+pub type WalletLabels = BTreeMap<(LabelType, String), WalletLabel>;
+
+/// Identifies a wallet by its public descriptor, so the xprv and xpub
+/// descriptors of the same wallet share their labels.
+pub async fn wallet_id(wallet: &WalletHandle) -> Result<String, BitcoinLabelsError> {
+    let descriptor = wallet
+        .lock()
+        .await
+        .public_descriptor(KeychainKind::External)?
+        .ok_or(BitcoinLabelsError::NoDescriptor)?;
+
+    Ok(sha256::Hash::hash(descriptor.to_string().as_bytes()).to_string())
+}
+
+/// Parses labels in the BIP-329 JSON Lines format
+pub fn parse_labels(jsonl: &str) -> Result<Vec<WalletLabel>, BitcoinLabelsError> {
+    jsonl
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| BitcoinLabelsError::InvalidLabel(index + 1, err.to_string()))
+        })
+        .collect()
+}
+
+/// Serializes labels in the BIP-329 JSON Lines format
+pub fn format_labels<'a>(
+    labels: impl IntoIterator<Item = &'a WalletLabel>,
+) -> Result<String, BitcoinLabelsError> {
+    let mut jsonl = String::new();
+    for label in labels {
+        jsonl.push_str(&serde_json::to_string(label)?);
+        jsonl.push('\n');
+    }
+    Ok(jsonl)
+}
+
+pub async fn retrieve_labels(
+    sk: &str,
+    wallet_id: &str,
+) -> Result<WalletLabels, BitcoinLabelsError> {
+    let (data, _) = retrieve(sk, &format!("{wallet_id}-{BITCOIN_LABELS}"), vec![]).await?;
+    let labels = parse_labels(&String::from_utf8(data)?)?
+        .into_iter()
+        .map(|label| ((label.label_type, label.reference.clone()), label))
+        .collect();
+
+    Ok(labels)
+}
+
+pub async fn store_labels(
+    sk: &str,
+    wallet_id: &str,
+    labels: &WalletLabels,
+) -> Result<(), BitcoinLabelsError> {
+    let jsonl = format_labels(labels.values())?;
+    store(
+        sk,
+        &format!("{wallet_id}-{BITCOIN_LABELS}"),
+        jsonl.as_bytes(),
+        true,
+        None,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Inserts or replaces labels. A record without label nor spendable flag removes the label.
+pub fn merge_labels(labels: &mut WalletLabels, updates: Vec<WalletLabel>) {
+    for label in updates {
+        let key = (label.label_type, label.reference.clone());
+        if label.label.is_none() && label.spendable.is_none() {
+            labels.remove(&key);
+        } else {
+            labels.insert(key, label);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::str::FromStr;
+
+use bdk::SignOptions;
+use bitcoin::{
+    blockdata::{opcodes::all, script::Builder},
+    consensus::encode,
+    hashes::{sha256, Hash, HashEngine},
+    psbt::PartiallySignedTransaction,
+    secp256k1::{Message, Secp256k1, XOnlyPublicKey},
+    util::{
+        address::{Payload, WitnessVersion},
+        schnorr::SchnorrSig,
+        sighash::{Prevouts, SighashCache},
+    },
+    Address, OutPoint, PackedLockTime, SchnorrSighashType, Script, Sequence, Transaction, TxIn,
+    TxOut, Witness,
+};
+use thiserror::Error;
+
+use crate::{
+    bitcoin::wallet::{get_wallet, BitcoinWalletError},
+    debug,
+    structs::{Bip322Format, SecretString},
+};
+
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+/// Addresses of the wallet looked up for the signing key
+const BIP322_ADDRESS_LOOKAHEAD: u32 = 1_000;
+
+#[derive(Error, Debug)]
+pub enum BitcoinMessageError {
+    /// Only P2TR key path spends are supported
+    #[error("Address {0} is not a P2TR address")]
+    UnsupportedAddress(String),
+    /// Address was not derived by the descriptor
+    #[error("Address {0} does not belong to the wallet")]
+    AddressNotInWallet(String),
+    /// Wallet could not sign the message
+    #[error("Wallet could not sign the message")]
+    CouldNotSign,
+    /// Signature is not Base64
+    #[error("Invalid BIP-322 signature encoding: {0}")]
+    InvalidSignature(String),
+    /// BDK error
+    #[error(transparent)]
+    BdkError(#[from] bdk::Error),
+    /// Bitcoin address error
+    #[error(transparent)]
+    BitcoinAddressError(#[from] bitcoin::util::address::Error),
+    /// Bitcoin consensus encoding error
+    #[error(transparent)]
+    BitcoinEncodeError(#[from] bitcoin::consensus::encode::Error),
+    /// PSBT error
+    #[error(transparent)]
+    PsbtError(#[from] bitcoin::psbt::Error),
+    /// Sighash error
+    #[error(transparent)]
+    SighashError(#[from] bitcoin::util::sighash::Error),
+    /// secp256k1 error
+    #[error(transparent)]
+    Secp256k1Error(#[from] bitcoin::secp256k1::Error),
+    /// BitMask Core Bitcoin Wallet error
+    #[error(transparent)]
+    BitcoinWalletError(#[from] BitcoinWalletError),
+}
+
+fn message_hash(message: &str) -> [u8; 32] {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag[..]);
+    engine.input(&tag[..]);
+    engine.input(message.as_bytes());
+
+    sha256::Hash::from_engine(engine).into_inner()
+}
+
+/// Virtual transaction committing to the message and paying to the address
+fn to_spend(script_pubkey: &Script, message: &str) -> Transaction {
+    let script_sig = Builder::new()
+        .push_opcode(all::OP_PUSHBYTES_0)
+        .push_slice(&message_hash(message))
+        .into_script();
+
+    Transaction {
+        version: 0,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: script_pubkey.clone(),
+        }],
+    }
+}
+
+/// Virtual transaction spending `to_spend`, whose witness is the signature
+fn to_sign(to_spend: &Transaction) -> Transaction {
+    Transaction {
+        version: 0,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend.txid(), 0),
+            script_sig: Script::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: Builder::new().push_opcode(all::OP_RETURN).into_script(),
+        }],
+    }
+}
+
+fn output_key(address: &Address) -> Result<XOnlyPublicKey, BitcoinMessageError> {
+    match &address.payload {
+        Payload::WitnessProgram {
+            version: WitnessVersion::V1,
+            program,
+        } if program.len() == 32 => Ok(XOnlyPublicKey::from_slice(program)?),
+        _ => Err(BitcoinMessageError::UnsupportedAddress(address.to_string())),
+    }
+}
+
+/// Signs the message with the key of a P2TR address of the descriptor, following BIP-322.
+/// Returns the Base64 signature in the simple (witness) or full (transaction) format.
+pub async fn sign_message(
+    descriptor: &SecretString,
+    address: &str,
+    message: &str,
+    format: Bip322Format,
+) -> Result<String, BitcoinMessageError> {
+    let address = Address::from_str(address)?;
+    output_key(&address)?;
+
+    let to_spend = to_spend(&address.script_pubkey(), message);
+    let mut psbt = PartiallySignedTransaction::from_unsigned_tx(to_sign(&to_spend))?;
+    psbt.inputs[0].witness_utxo = Some(to_spend.output[0].clone());
+
+    let wallet = get_wallet(descriptor, None).await?;
+    let wallet = wallet.lock().await;
+    wallet.ensure_addresses_cached(BIP322_ADDRESS_LOOKAHEAD)?;
+    if !wallet.is_mine(&address.script_pubkey())? {
+        return Err(BitcoinMessageError::AddressNotInWallet(address.to_string()));
+    }
+
+    debug!(format!("Signing BIP-322 message for {address}"));
+    let opts = SignOptions {
+        trust_witness_utxo: true,
+        ..Default::default()
+    };
+    if !wallet.sign(&mut psbt, opts)? {
+        return Err(BitcoinMessageError::CouldNotSign);
+    }
+
+    let to_sign = psbt.extract_tx();
+    let signature = match format {
+        Bip322Format::Simple => encode::serialize(&to_sign.input[0].witness),
+        Bip322Format::Full => encode::serialize(&to_sign),
+    };
+
+    Ok(base64::encode(&signature))
+}
+
+/// Verifies a BIP-322 signature of the message by a P2TR address, in the simple or full format
+pub fn verify_message(
+    address: &str,
+    message: &str,
+    signature: &str,
+) -> Result<bool, BitcoinMessageError> {
+    let address = Address::from_str(address)?;
+    let output_key = output_key(&address)?;
+
+    let signature = base64::decode(signature)
+        .map_err(|err| BitcoinMessageError::InvalidSignature(err.to_string()))?;
+    let to_spend = to_spend(&address.script_pubkey(), message);
+    let to_sign = match encode::deserialize::<Witness>(&signature) {
+        Ok(witness) => {
+            let mut to_sign = to_sign(&to_spend);
+            to_sign.input[0].witness = witness;
+            to_sign
+        }
+        Err(_) => {
+            let to_sign: Transaction = encode::deserialize(&signature)?;
+            let spends_message = to_sign.input.len() == 1
+                && to_sign.input[0].previous_output == OutPoint::new(to_spend.txid(), 0)
+                && to_sign.output.len() == 1
+                && to_sign.output[0].script_pubkey.is_op_return();
+            if !spends_message {
+                return Ok(false);
+            }
+            to_sign
+        }
+    };
+
+    // Key path spends have the signature as their only witness element
+    let witness = &to_sign.input[0].witness;
+    let sig = match witness.iter().next() {
+        Some(sig) if witness.len() == 1 => sig,
+        _ => return Ok(false),
+    };
+    let sig = match SchnorrSig::from_slice(sig) {
+        Ok(sig) => sig,
+        Err(_) => return Ok(false),
+    };
+    if !matches!(
+        sig.hash_ty,
+        SchnorrSighashType::Default | SchnorrSighashType::All
+    ) {
+        return Ok(false);
+    }
+
+    let sighash = SighashCache::new(&to_sign).taproot_key_spend_signature_hash(
+        0,
+        &Prevouts::All(&to_spend.output),
+        sig.hash_ty,
+    )?;
+    let msg = Message::from_slice(&sighash[..])?;
+
+    Ok(Secp256k1::verification_only()
+        .verify_schnorr(&sig.sig, &msg, &output_key)
+        .is_ok())
+}
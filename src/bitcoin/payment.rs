@@ -1,20 +1,35 @@
-use bdk::{wallet::tx_builder::TxOrdering, FeeRate, TransactionDetails};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Mutex, MutexGuard},
+};
+
+use bdk::{
+    wallet::{tx_builder::TxOrdering, AddressIndex},
+    FeeRate, SignOptions, TransactionDetails,
+};
 
 use bitcoin::{
     consensus::serialize,
     psbt::{Input, Psbt},
-    Amount, TxIn,
+    Amount, OutPoint, TxIn, Txid,
+};
+use once_cell::sync::Lazy;
+use payjoin::{
+    receive::{Headers, UncheckedProposal},
+    send::Configuration,
+    PjUri, PjUriExt,
 };
-use payjoin::{send::Configuration, PjUri, PjUriExt};
 use thiserror::Error;
 
 use crate::{
     bitcoin::{
+        coins::UtxoSelection,
+        fees::MIN_RELAY_FEE_RATE,
         psbt::{sign_and_publish_psbt, sign_psbt, BitcoinPsbtError},
-        wallet::MemoryWallet,
+        wallet::{get_blockchain, WalletHandle},
     },
     debug, info,
-    structs::SatsInvoice,
+    structs::{CpfpResponse, SatsInvoice},
 };
 
 #[derive(Error, Debug)]
@@ -22,12 +37,33 @@ pub enum BitcoinPaymentError {
     /// Payjoin error response
     #[error("Error performing payjoin: {0}")]
     PayjoinError(String),
+    /// Parent transaction not found
+    #[error("Transaction {0} was not found")]
+    TransactionNotFound(String),
+    /// Parent transaction already confirmed
+    #[error("Transaction {0} is already confirmed")]
+    TransactionAlreadyConfirmed(String),
+    /// Parent transaction has no output the wallet can spend
+    #[error("Transaction {0} has no unspent output owned by the wallet")]
+    NoSpendableOutput(String),
+    /// Original PSBT of a payjoin was rejected
+    #[error("Payjoin original PSBT rejected: {0}")]
+    PayjoinReceiveError(String),
+    /// Original PSBT of a payjoin cannot be broadcast
+    #[error("Payjoin original transaction cannot be broadcast: {0}")]
+    PayjoinUnbroadcastable(String),
+    /// Receiver has no UTXO allowed to be contributed to the payjoin
+    #[error("No UTXO available to contribute to the payjoin")]
+    NoPayjoinInputs,
     /// BitMask Core Bitcoin Psbt error
     #[error(transparent)]
     BitcoinPsbtError(#[from] BitcoinPsbtError),
     /// BDK error
     #[error(transparent)]
     BdkError(#[from] bdk::Error),
+    /// BDK esplora error
+    #[error(transparent)]
+    BdkEsploraError(#[from] bdk::esplora_client::Error),
     /// Payjoin Request error
     #[error(transparent)]
     PayjoinGetRequestError(#[from] payjoin::send::CreateRequestError),
@@ -39,17 +75,22 @@ pub enum BitcoinPaymentError {
     ReqwestError(#[from] reqwest::Error),
 }
 
-pub async fn create_transaction(
+/// Builds the unsigned PSBT paying the invoices
+pub async fn build_transaction(
     invoices: Vec<SatsInvoice>,
-    wallet: &MemoryWallet,
+    wallet: &WalletHandle,
     fee_rate: Option<FeeRate>,
-) -> Result<TransactionDetails, BitcoinPaymentError> {
+    selection: Option<&UtxoSelection>,
+) -> Result<(Psbt, TransactionDetails), BitcoinPaymentError> {
     let (psbt, details) = {
         let locked_wallet = wallet.lock().await;
         let mut builder = locked_wallet.build_tx();
         for invoice in invoices {
             builder.add_recipient(invoice.address.script_pubkey(), invoice.amount);
         }
+        if let Some(selection) = selection {
+            selection.apply(&mut builder)?;
+        }
 
         builder.ordering(TxOrdering::Untouched); // TODO: Remove after implementing wallet persistence
         builder.enable_rbf().fee_rate(fee_rate.unwrap_or_default());
@@ -58,17 +99,31 @@ pub async fn create_transaction(
 
     debug!(format!("Create transaction: {details:#?}"));
     debug!("Unsigned PSBT:", base64::encode(&serialize(&psbt)));
-    let details = sign_and_publish_psbt(wallet, psbt).await?;
+
+    Ok((psbt, details))
+}
+
+pub async fn create_transaction(
+    sk: &str,
+    invoices: Vec<SatsInvoice>,
+    wallet: &WalletHandle,
+    fee_rate: Option<FeeRate>,
+    selection: Option<&UtxoSelection>,
+) -> Result<TransactionDetails, BitcoinPaymentError> {
+    let (psbt, _) = build_transaction(invoices, wallet, fee_rate, selection).await?;
+    let details = sign_and_publish_psbt(sk, wallet, psbt).await?;
     info!("PSBT successfully signed");
 
     Ok(details)
 }
 
 pub async fn create_payjoin(
+    sk: &str,
     invoices: Vec<SatsInvoice>,
-    wallet: &MemoryWallet,
+    wallet: &WalletHandle,
     fee_rate: Option<FeeRate>,
     pj_uri: PjUri<'_>, // TODO specify Uri<PayJoinParams>
+    selection: Option<&UtxoSelection>,
 ) -> Result<TransactionDetails, BitcoinPaymentError> {
     let enacted_fee_rate = fee_rate.unwrap_or_default();
     let (psbt, details) = {
@@ -77,6 +132,9 @@ pub async fn create_payjoin(
         for invoice in &invoices {
             builder.add_recipient(invoice.address.script_pubkey(), invoice.amount);
         }
+        if let Some(selection) = selection {
+            selection.apply(&mut builder)?;
+        }
         builder.enable_rbf().fee_rate(enacted_fee_rate);
         builder.finish()?
     };
@@ -146,11 +204,263 @@ pub async fn create_payjoin(
         base64::encode(&serialize(&payjoin_psbt))
     );
     // sign_psbt also broadcasts;
-    let tx = sign_and_publish_psbt(wallet, payjoin_psbt).await?;
+    let tx = sign_and_publish_psbt(sk, wallet, payjoin_psbt).await?;
 
     Ok(tx)
 }
 
+/// Child-pays-for-parent: spends one of our outputs of an unconfirmed parent transaction,
+/// paying a child fee high enough for the package (parent + child) to reach the target fee rate.
+pub async fn create_cpfp(
+    sk: &str,
+    wallet: &WalletHandle,
+    parent_txid: Txid,
+    target_fee_rate: FeeRate,
+    selection: Option<&UtxoSelection>,
+) -> Result<CpfpResponse, BitcoinPaymentError> {
+    let blockchain = get_blockchain().await;
+    let parent = blockchain
+        .get_tx(&parent_txid)
+        .await?
+        .ok_or_else(|| BitcoinPaymentError::TransactionNotFound(parent_txid.to_string()))?;
+
+    if let Some(status) = blockchain.get_tx_status(&parent_txid).await? {
+        if status.confirmed {
+            return Err(BitcoinPaymentError::TransactionAlreadyConfirmed(
+                parent_txid.to_string(),
+            ));
+        }
+    }
+
+    // The parent may be paid by a counterparty, so its fee is computed from the prevouts
+    let mut parent_inputs = 0;
+    for input in &parent.input {
+        let prev_tx = blockchain
+            .get_tx(&input.previous_output.txid)
+            .await?
+            .ok_or_else(|| {
+                BitcoinPaymentError::TransactionNotFound(input.previous_output.txid.to_string())
+            })?;
+        parent_inputs += prev_tx.output[input.previous_output.vout as usize].value;
+    }
+    let parent_outputs: u64 = parent.output.iter().map(|output| output.value).sum();
+    let parent_fee = parent_inputs.saturating_sub(parent_outputs);
+    let parent_vsize = parent.vsize() as u64;
+
+    let (psbt, estimated_vsize) = {
+        let locked_wallet = wallet.lock().await;
+        let unspent = locked_wallet.list_unspent()?;
+        let excluded = selection.map(|s| s.exclude.clone()).unwrap_or_default();
+        let outpoint = unspent
+            .iter()
+            .filter(|utxo| utxo.outpoint.txid == parent_txid)
+            .filter(|utxo| !excluded.contains(&utxo.outpoint))
+            .max_by_key(|utxo| utxo.txout.value)
+            .map(|utxo| utxo.outpoint)
+            .ok_or_else(|| BitcoinPaymentError::NoSpendableOutput(parent_txid.to_string()))?;
+        let change = locked_wallet.get_internal_address(AddressIndex::New)?;
+
+        let build_child = |fee: Option<u64>| {
+            let mut builder = locked_wallet.build_tx();
+            if let Some(selection) = selection {
+                selection.apply(&mut builder)?;
+            }
+            builder
+                .add_utxo(outpoint)?
+                .drain_to(change.script_pubkey())
+                .enable_rbf();
+            match fee {
+                Some(fee) => builder.fee_absolute(fee),
+                None => builder.fee_rate(target_fee_rate),
+            };
+            builder.finish()
+        };
+
+        // First pass at the target fee rate gives the child size, then the
+        // child fee is raised to cover the parent deficit.
+        let (_, details) = build_child(None)?;
+        let child_fee = details.fee.unwrap_or_default();
+        let child_vsize = (child_fee as f32 / target_fee_rate.as_sat_per_vb()).ceil() as u64;
+        let package_fee = target_fee_rate.fee_vb((parent_vsize + child_vsize) as usize);
+        let child_fee = std::cmp::max(child_fee, package_fee.saturating_sub(parent_fee));
+
+        let (psbt, details) = build_child(Some(child_fee))?;
+        debug!(format!("Create CPFP transaction: {details:#?}"));
+        (psbt, child_vsize)
+    };
+
+    debug!("Unsigned CPFP PSBT:", base64::encode(&serialize(&psbt)));
+    let details = sign_and_publish_psbt(sk, wallet, psbt).await?;
+    info!(format!("CPFP transaction {} published", details.txid));
+
+    let fee = details.fee.unwrap_or_default();
+    let vsize = details
+        .transaction
+        .as_ref()
+        .map(|tx| tx.vsize() as u64)
+        .unwrap_or(estimated_vsize);
+    let package_fee_rate = (parent_fee + fee) as f32 / (parent_vsize + vsize) as f32;
+
+    Ok(CpfpResponse {
+        parent_txid: parent_txid.to_string(),
+        txid: details.txid.to_string(),
+        parent_fee,
+        fee,
+        parent_vsize,
+        vsize,
+        package_fee_rate,
+    })
+}
+
+/// Seconds the inputs of an answered original PSBT are remembered
+const PAYJOIN_SEEN_INPUTS_TTL: i64 = 24 * 60 * 60;
+
+/// Inputs of the original PSBTs answered with a proposal, and when, so the same inputs cannot
+/// be used to probe the receiver UTXOs (BIP-78). Once forgotten, the inputs are usually spent
+/// and the original PSBT fails the broadcast check instead.
+static PAYJOIN_SEEN_INPUTS: Lazy<Mutex<BTreeMap<OutPoint, i64>>> = Lazy::new(Default::default);
+
+fn seen_payjoin_inputs() -> Result<MutexGuard<'static, BTreeMap<OutPoint, i64>>, BitcoinPaymentError>
+{
+    let mut seen = PAYJOIN_SEEN_INPUTS
+        .lock()
+        .map_err(|err| BitcoinPaymentError::PayjoinReceiveError(err.to_string()))?;
+    let now = chrono::Utc::now().timestamp();
+    seen.retain(|_, seen_at| now - *seen_at < PAYJOIN_SEEN_INPUTS_TTL);
+    Ok(seen)
+}
+
+struct PayjoinHeaders {
+    content_length: String,
+}
+
+impl Headers for PayjoinHeaders {
+    fn get_header(&self, key: &str) -> Option<&str> {
+        match key.to_lowercase().as_str() {
+            "content-length" => Some(&self.content_length),
+            "content-type" => Some("text/plain"),
+            _ => None,
+        }
+    }
+}
+
+/// Payjoin receiver (BIP-78): validates the original PSBT of the sender, contributes one of
+/// the wallet UTXOs allowed by the selection and returns the signed proposal PSBT.
+pub async fn receive_payjoin(
+    wallet: &WalletHandle,
+    body: &[u8],
+    query: &str,
+    selection: &UtxoSelection,
+) -> Result<Psbt, BitcoinPaymentError> {
+    let headers = PayjoinHeaders {
+        content_length: body.len().to_string(),
+    };
+    let proposal = UncheckedProposal::from_request(body, query, headers)
+        .map_err(|err| BitcoinPaymentError::PayjoinReceiveError(err.to_string()))?;
+
+    // Esplora has no testmempoolaccept, so the original transaction is checked
+    // to spend existing and unspent outputs only.
+    let original_tx = proposal.get_transaction_to_schedule_broadcast();
+    let blockchain = get_blockchain().await;
+    for input in &original_tx.input {
+        let OutPoint { txid, vout } = input.previous_output;
+        if blockchain.get_tx(&txid).await?.is_none() {
+            return Err(BitcoinPaymentError::PayjoinUnbroadcastable(format!(
+                "input {txid}:{vout} does not exist"
+            )));
+        }
+        if let Some(status) = blockchain.get_output_status(&txid, vout as u64).await? {
+            if status.spent {
+                return Err(BitcoinPaymentError::PayjoinUnbroadcastable(format!(
+                    "input {txid}:{vout} is already spent"
+                )));
+            }
+        }
+    }
+    debug!(format!("Payjoin original transaction: {original_tx:#?}"));
+
+    let psbt = {
+        let locked_wallet = wallet.lock().await;
+        let is_mine = |script: &bitcoin::Script| {
+            locked_wallet
+                .is_mine(script)
+                .map_err(|err| payjoin::receive::Error::Server(err.into()))
+        };
+        let reject = |err: payjoin::receive::Error| {
+            BitcoinPaymentError::PayjoinReceiveError(err.to_string())
+        };
+
+        let mut payjoin = proposal
+            .check_can_broadcast(|_| Ok(true))
+            .map_err(reject)?
+            .check_inputs_not_owned(is_mine)
+            .map_err(reject)?
+            .check_no_mixed_input_scripts()
+            .map_err(|err| BitcoinPaymentError::PayjoinReceiveError(err.to_string()))?
+            .check_no_inputs_seen_before(|outpoint| {
+                let seen = seen_payjoin_inputs()
+                    .map_err(|err| payjoin::receive::Error::Server(err.into()))?;
+                Ok(seen.contains_key(outpoint))
+            })
+            .map_err(reject)?
+            .identify_receiver_outputs(is_mine)
+            .map_err(reject)?;
+
+        let candidates: Vec<_> = locked_wallet
+            .list_unspent()?
+            .into_iter()
+            .filter(|utxo| !selection.exclude.contains(&utxo.outpoint))
+            .filter(|utxo| {
+                selection.include.is_empty() || selection.include.contains(&utxo.outpoint)
+            })
+            .collect();
+        let candidate_inputs: HashMap<Amount, OutPoint> = candidates
+            .iter()
+            .map(|utxo| (Amount::from_sat(utxo.txout.value), utxo.outpoint))
+            .collect();
+
+        // Falls back to any allowed UTXO when none avoids the unnecessary input heuristic
+        let outpoint = payjoin
+            .try_preserving_privacy(candidate_inputs)
+            .ok()
+            .or_else(|| candidates.first().map(|utxo| utxo.outpoint))
+            .ok_or(BitcoinPaymentError::NoPayjoinInputs)?;
+        let utxo = candidates
+            .iter()
+            .find(|utxo| utxo.outpoint == outpoint)
+            .ok_or(BitcoinPaymentError::NoPayjoinInputs)?;
+        info!(format!("Contributing {outpoint} to payjoin"));
+        payjoin.contribute_witness_input(utxo.txout.clone(), outpoint);
+
+        let mut psbt = payjoin
+            .apply_fee(Some(MIN_RELAY_FEE_RATE as u64))
+            .map_err(|err| BitcoinPaymentError::PayjoinReceiveError(err.to_string()))?
+            .clone();
+
+        let opts = SignOptions {
+            trust_witness_utxo: true,
+            ..Default::default()
+        };
+        locked_wallet.sign(&mut psbt, opts)?;
+
+        payjoin
+            .prepare_psbt(psbt)
+            .map_err(|err| BitcoinPaymentError::PayjoinReceiveError(err.to_string()))?
+    };
+
+    // Inputs are only seen once answered, a rejected original PSBT can be sent again
+    let now = chrono::Utc::now().timestamp();
+    let mut seen = seen_payjoin_inputs()?;
+    for input in &original_tx.input {
+        seen.insert(input.previous_output, now);
+    }
+    drop(seen);
+
+    debug!("Payjoin proposal PSBT:", base64::encode(&serialize(&psbt)));
+
+    Ok(psbt)
+}
+
 /// Unlike Bitcoin Core's walletprocesspsbt RPC, BDK's finalize_psbt only checks
 /// if the script in the PSBT input map matches the descriptor and does not
 /// check whether it has control of the OutPoint specified in the unsigned_tx's
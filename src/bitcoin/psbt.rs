@@ -1,10 +1,15 @@
-use bdk::{blockchain::Blockchain, psbt::PsbtUtils, SignOptions, TransactionDetails};
+use bdk::{psbt::PsbtUtils, SignOptions, TransactionDetails};
 use bitcoin::{consensus::serialize, hashes::hex::ToHex, util::psbt::PartiallySignedTransaction};
 use thiserror::Error;
 
 use crate::{
-    bitcoin::{get_blockchain, MemoryWallet},
+    bitcoin::{
+        broadcast::{queue_transaction, BitcoinBroadcastError},
+        chain::BitcoinChainError,
+        WalletHandle,
+    },
     debug,
+    structs::BroadcastStatus,
 };
 
 #[derive(Error, Debug)]
@@ -18,11 +23,23 @@ pub enum BitcoinPsbtError {
     /// BDK esplora error
     #[error(transparent)]
     BdkEsploraError(#[from] bdk::esplora_client::Error),
+    /// BitMask Core Bitcoin Broadcast error
+    #[error(transparent)]
+    BitcoinBroadcastError(#[from] BitcoinBroadcastError),
+    /// BitMask Core Bitcoin Chain error
+    #[error(transparent)]
+    BitcoinChainError(#[from] BitcoinChainError),
+    /// Transaction refused by the node, or replaced, it is not broadcast again
+    #[error("Transaction {0} rejected: {1}")]
+    Rejected(String, String),
+    /// Transaction not broadcast yet, the broadcast queue retries it
+    #[error("Transaction {0} could not be broadcast and is queued for a retry: {1}")]
+    Queued(String, String),
 }
 
 // Only signs an original psbt.
 pub async fn sign_psbt(
-    wallet: &MemoryWallet,
+    wallet: &WalletHandle,
     mut psbt: PartiallySignedTransaction,
 ) -> Result<PartiallySignedTransaction, BitcoinPsbtError> {
     debug!("Funding PSBT...");
@@ -36,7 +53,7 @@ pub async fn sign_psbt(
 }
 
 pub async fn multi_sign_psbt(
-    wallets: Vec<MemoryWallet>,
+    wallets: Vec<WalletHandle>,
     mut psbt: PartiallySignedTransaction,
 ) -> Result<PartiallySignedTransaction, BitcoinPsbtError> {
     let total_wallets = wallets.len();
@@ -62,9 +79,11 @@ pub async fn multi_sign_psbt(
     Ok(psbt)
 }
 
-/// Signs and broadcasts a transaction given a Psbt
+/// Signs and broadcasts a transaction given a Psbt, through the broadcast queue of the wallet.
+/// Fails with `Queued` when the transaction is waiting for a retry of its broadcast.
 pub async fn sign_and_publish_psbt(
-    wallet: &MemoryWallet,
+    sk: &str,
+    wallet: &WalletHandle,
     mut psbt: PartiallySignedTransaction,
 ) -> Result<TransactionDetails, BitcoinPsbtError> {
     debug!("Signing PSBT...");
@@ -74,44 +93,59 @@ pub async fn sign_and_publish_psbt(
         .sign(&mut psbt, SignOptions::default())?;
     debug!(format!("Finalized: {finalized}"));
     if finalized {
-        debug!("Signed PSBT:", base64::encode(&serialize(&psbt)));
-        let fee_amount = psbt.fee_amount().expect("fee amount on PSBT is known");
-        let tx = psbt.extract_tx();
-        debug!("tx:", &serialize(&tx.clone()).to_hex());
-        let blockchain = get_blockchain().await;
-        blockchain.broadcast(&tx).await?;
-
-        let txid = tx.txid();
-        let tx = blockchain.get_tx(&txid).await?;
-
-        let mut sent = 0;
-        let mut received = 0;
-
-        if let Some(tx) = tx.clone() {
-            sent = tx.output.iter().fold(0, |sum, output| output.value + sum);
-            received = sent - fee_amount;
-        }
+        publish_psbt(sk, wallet, psbt).await
+    } else {
+        Err(BitcoinPsbtError::CouldNotFinalizePsbt)
+    }
+}
 
-        let details = TransactionDetails {
-            transaction: tx,
-            txid,
-            received,
-            sent,
-            fee: Some(fee_amount),
-            confirmation_time: None,
-        };
+/// Finalizes and broadcasts a Psbt signed by an external signer (watch-only wallets)
+pub async fn finalize_and_publish_psbt(
+    sk: &str,
+    wallet: &WalletHandle,
+    psbt: PartiallySignedTransaction,
+) -> Result<TransactionDetails, BitcoinPsbtError> {
+    let psbt = finalize_psbt(wallet, psbt).await?;
+    publish_psbt(sk, wallet, psbt).await
+}
 
-        Ok(details)
+/// Finalizes a Psbt signed by an external signer (watch-only wallets)
+pub async fn finalize_psbt(
+    wallet: &WalletHandle,
+    mut psbt: PartiallySignedTransaction,
+) -> Result<PartiallySignedTransaction, BitcoinPsbtError> {
+    debug!("Finalizing PSBT...");
+    let finalized = wallet
+        .lock()
+        .await
+        .finalize_psbt(&mut psbt, SignOptions::default())?;
+    debug!(format!("Finalized: {finalized}"));
+    if finalized {
+        Ok(psbt)
     } else {
         Err(BitcoinPsbtError::CouldNotFinalizePsbt)
     }
 }
 
-/// Signs and broadcasts a transaction given a Psbt
+/// Signs and broadcasts a transaction given a Psbt, through the broadcast queue of the first wallet
 pub async fn multi_sign_and_publish_psbt(
-    wallets: Vec<MemoryWallet>,
-    mut psbt: PartiallySignedTransaction,
+    sk: &str,
+    wallets: Vec<WalletHandle>,
+    psbt: PartiallySignedTransaction,
 ) -> Result<TransactionDetails, BitcoinPsbtError> {
+    let wallet = wallets
+        .first()
+        .cloned()
+        .ok_or(BitcoinPsbtError::CouldNotFinalizePsbt)?;
+    let psbt = multi_sign_and_finalize_psbt(wallets, psbt).await?;
+    publish_psbt(sk, &wallet, psbt).await
+}
+
+/// Signs a Psbt with every wallet, until it is finalized
+pub async fn multi_sign_and_finalize_psbt(
+    wallets: Vec<WalletHandle>,
+    mut psbt: PartiallySignedTransaction,
+) -> Result<PartiallySignedTransaction, BitcoinPsbtError> {
     let total_wallets = wallets.len();
     debug!(format!(
         "Signing PSBT ({total_wallets}/{total_wallets}) ..."
@@ -135,35 +169,53 @@ pub async fn multi_sign_and_publish_psbt(
 
     debug!(format!("Finalized: {finalized}"));
     if finalized {
-        debug!("Signed PSBT:", base64::encode(&serialize(&psbt)));
-        let fee_amount = psbt.fee_amount().expect("fee amount on PSBT is known");
-        let tx = psbt.extract_tx();
-        debug!("tx:", &serialize(&tx.clone()).to_hex());
-        let blockchain = get_blockchain().await;
-        blockchain.broadcast(&tx).await?;
-
-        let txid = tx.txid();
-        let tx = blockchain.get_tx(&txid).await?;
-
-        let mut sent = 0;
-        let mut received = 0;
-
-        if let Some(tx) = tx.clone() {
-            sent = tx.output.iter().fold(0, |sum, output| output.value + sum);
-            received = sent - fee_amount;
-        }
-
-        let details = TransactionDetails {
-            transaction: tx,
-            txid,
-            received,
-            sent,
-            fee: Some(fee_amount),
-            confirmation_time: None,
-        };
-
-        Ok(details)
+        Ok(psbt)
     } else {
         Err(BitcoinPsbtError::CouldNotFinalizePsbt)
     }
 }
+
+/// Broadcasts a finalized Psbt. The transaction is kept in the broadcast queue of the wallet,
+/// so a failed broadcast is retried by `process_broadcast_queue` instead of being lost.
+/// Only a transaction that reached the mempool is returned, otherwise the error tells whether
+/// it is queued for a retry or was rejected.
+async fn publish_psbt(
+    sk: &str,
+    wallet: &WalletHandle,
+    psbt: PartiallySignedTransaction,
+) -> Result<TransactionDetails, BitcoinPsbtError> {
+    debug!("Signed PSBT:", base64::encode(&serialize(&psbt)));
+    let fee_amount = psbt.fee_amount().expect("fee amount on PSBT is known");
+    let tx = psbt.extract_tx();
+    debug!("tx:", &serialize(&tx.clone()).to_hex());
+    let queued = queue_transaction(sk, wallet, tx.clone()).await?;
+    let reason = queued.last_error.unwrap_or_default();
+    match queued.status {
+        BroadcastStatus::Mempool | BroadcastStatus::Confirmed(_) => {}
+        BroadcastStatus::Pending => return Err(BitcoinPsbtError::Queued(queued.txid, reason)),
+        BroadcastStatus::Failed | BroadcastStatus::Rejected => {
+            return Err(BitcoinPsbtError::Rejected(queued.txid, reason))
+        }
+        BroadcastStatus::Replaced(replacement) => {
+            return Err(BitcoinPsbtError::Rejected(
+                queued.txid,
+                format!("replaced by {replacement}"),
+            ))
+        }
+    }
+
+    let txid = tx.txid();
+    let sent = tx.output.iter().fold(0, |sum, output| output.value + sum);
+    let received = sent - fee_amount;
+
+    let details = TransactionDetails {
+        transaction: Some(tx),
+        txid,
+        received,
+        sent,
+        fee: Some(fee_amount),
+        confirmation_time: None,
+    };
+
+    Ok(details)
+}
@@ -0,0 +1,109 @@
+use bip39::{Language, Mnemonic};
+use thiserror::Error;
+use zeroize::Zeroize;
+
+use crate::{
+    bitcoin::keys::{get_mnemonic, BitcoinKeysError},
+    structs::{DecryptedWalletData, SecretString, Slip39Shares, Slip39SplitRequest},
+};
+
+/// PBKDF2 iterations of the share encryption are 10000 * 2^e
+const SLIP39_ITERATION_EXPONENT: u8 = 1;
+
+#[derive(Error, Debug)]
+pub enum BitcoinSlip39Error {
+    /// Group or member thresholds are not satisfiable
+    #[error("Invalid SLIP-39 thresholds: {0}")]
+    InvalidThresholds(String),
+    /// Shares are invalid or not enough to reach the thresholds
+    #[error("Invalid SLIP-39 shares: {0}")]
+    InvalidShares(String),
+    /// BIP-39 error
+    #[error(transparent)]
+    Bip39Error(#[from] bip39::Error),
+    /// BitMask Core Bitcoin Keys error
+    #[error(transparent)]
+    BitcoinKeysError(#[from] BitcoinKeysError),
+}
+
+/// Splits the entropy of the mnemonic into SLIP-39 shares, one list per group
+pub fn split_mnemonic(
+    mnemonic_phrase: &SecretString,
+    request: &Slip39SplitRequest,
+) -> Result<Slip39Shares, BitcoinSlip39Error> {
+    let Slip39SplitRequest {
+        group_threshold,
+        groups,
+        passphrase,
+    } = request;
+
+    if groups.is_empty() || usize::from(*group_threshold) > groups.len() {
+        return Err(BitcoinSlip39Error::InvalidThresholds(format!(
+            "group threshold {group_threshold} of {} groups",
+            groups.len()
+        )));
+    }
+    if let Some(group) = groups
+        .iter()
+        .find(|group| group.member_threshold == 0 || group.member_threshold > group.member_count)
+    {
+        return Err(BitcoinSlip39Error::InvalidThresholds(format!(
+            "member threshold {} of {} members",
+            group.member_threshold, group.member_count
+        )));
+    }
+
+    let mnemonic = Mnemonic::parse_in(Language::English, &mnemonic_phrase.0)?;
+    let mut entropy = mnemonic.to_entropy();
+    let groups: Vec<(u8, u8)> = groups
+        .iter()
+        .map(|group| (group.member_threshold, group.member_count))
+        .collect();
+    let passphrase = passphrase
+        .as_ref()
+        .map(|p| p.0.as_str())
+        .unwrap_or_default();
+
+    let group_shares = sssmc39::generate_mnemonics(
+        *group_threshold,
+        &groups,
+        &entropy,
+        passphrase,
+        SLIP39_ITERATION_EXPONENT,
+    );
+    entropy.zeroize();
+
+    let mut shares = vec![];
+    for group in group_shares.map_err(|err| BitcoinSlip39Error::InvalidShares(err.to_string()))? {
+        let members = group
+            .mnemonic_list()
+            .map_err(|err| BitcoinSlip39Error::InvalidShares(err.to_string()))?
+            .into_iter()
+            .map(|words| SecretString(words.join(" ")))
+            .collect();
+        shares.push(members);
+    }
+
+    Ok(Slip39Shares { groups: shares })
+}
+
+/// Recovers the wallet from a quorum of SLIP-39 shares. The passphrase is the one the
+/// shares were split with, the seed password the BIP-39 one of the wallet.
+pub async fn recover_mnemonic(
+    shares: &[SecretString],
+    passphrase: Option<&SecretString>,
+    seed_password: &SecretString,
+) -> Result<DecryptedWalletData, BitcoinSlip39Error> {
+    let shares: Vec<Vec<String>> = shares
+        .iter()
+        .map(|share| share.0.split_whitespace().map(str::to_owned).collect())
+        .collect();
+    let passphrase = passphrase.map(|p| p.0.as_str()).unwrap_or_default();
+
+    let mut entropy = sssmc39::combine_mnemonics(&shares, passphrase)
+        .map_err(|err| BitcoinSlip39Error::InvalidShares(err.to_string()))?;
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy);
+    entropy.zeroize();
+
+    Ok(get_mnemonic(mnemonic?, seed_password).await?)
+}
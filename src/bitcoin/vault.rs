@@ -0,0 +1,195 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use bdk::{
+    descriptor::policy::{Policy, SatisfiableItem},
+    KeychainKind, TransactionDetails,
+};
+use bitcoin::Address;
+use miniscript_crate::policy::Concrete;
+use thiserror::Error;
+
+use crate::{
+    bitcoin::{
+        chain::{get_tip_height, BitcoinChainError},
+        fees::{resolve_fee_rate, BitcoinFeesError},
+        psbt::{sign_and_publish_psbt, BitcoinPsbtError},
+        wallet::{get_wallet, sync_wallet, BitcoinWalletError},
+    },
+    debug, info,
+    structs::{SecretString, VaultRecoveryRequest},
+};
+
+// Placeholders of the keys while compiling the policy
+const PRIMARY_KEY: &str = "PRIMARY";
+const RECOVERY_KEY: &str = "RECOVERY";
+
+#[derive(Error, Debug)]
+pub enum BitcoinVaultError {
+    /// Relative timelocks are between 1 and 65535 blocks
+    #[error("Invalid vault timelock: {0} blocks")]
+    InvalidTimelock(u32),
+    /// Key is not a descriptor key
+    #[error("Invalid vault key: {0}")]
+    InvalidKey(String),
+    /// Policy could not be compiled
+    #[error("Vault policy could not be compiled: {0}")]
+    PolicyError(String),
+    /// Wallet has no recovery path
+    #[error("Vault wallet has no recovery spending policy")]
+    NoRecoveryPolicy,
+    /// No coins of the vault have matured
+    #[error("Vault timelock matures in {0} blocks")]
+    TimelockNotMatured(u32),
+    /// BDK error
+    #[error(transparent)]
+    BdkError(#[from] bdk::Error),
+    /// Bitcoin address error
+    #[error(transparent)]
+    BitcoinAddressError(#[from] bitcoin::util::address::Error),
+    /// BitMask Core Bitcoin Chain error
+    #[error(transparent)]
+    BitcoinChainError(#[from] BitcoinChainError),
+    /// BitMask Core Bitcoin Fees error
+    #[error(transparent)]
+    BitcoinFeesError(#[from] BitcoinFeesError),
+    /// BitMask Core Bitcoin Psbt error
+    #[error(transparent)]
+    BitcoinPsbtError(#[from] BitcoinPsbtError),
+    /// BitMask Core Bitcoin Wallet error
+    #[error(transparent)]
+    BitcoinWalletError(#[from] BitcoinWalletError),
+}
+
+/// Key of a single-key `tr()` descriptor, or the descriptor key itself
+fn descriptor_key(key: &str) -> Result<&str, BitcoinVaultError> {
+    let key = key.split('#').next().unwrap_or_default().trim();
+    let key = match key.strip_prefix("tr(") {
+        Some(key) => key.strip_suffix(')').unwrap_or(key),
+        None => key,
+    };
+
+    if key.is_empty() || key.contains(['(', ')', ',']) {
+        return Err(BitcoinVaultError::InvalidKey(key.to_owned()));
+    }
+
+    Ok(key)
+}
+
+/// Compiles "primary key OR (recovery key AND older(timelock))" into a taproot descriptor.
+/// The primary key spends through the key path, the recovery key through a script path once
+/// the coins are `timelock` blocks old. Keys are descriptor keys, or single-key `tr()`
+/// descriptors, either public or private.
+pub fn compile_vault_descriptor(
+    primary: &SecretString,
+    recovery: &SecretString,
+    timelock: u32,
+) -> Result<SecretString, BitcoinVaultError> {
+    if timelock == 0 || timelock > u16::MAX as u32 {
+        return Err(BitcoinVaultError::InvalidTimelock(timelock));
+    }
+
+    let policy = format!("or(pk({PRIMARY_KEY}),and(pk({RECOVERY_KEY}),older({timelock})))");
+    let descriptor = Concrete::<String>::from_str(&policy)
+        .and_then(|policy| policy.compile_tr(None))
+        .map_err(|err| BitcoinVaultError::PolicyError(err.to_string()))?
+        .to_string();
+    let descriptor = descriptor.split('#').next().unwrap_or_default();
+    debug!(format!("Vault policy compiled to {descriptor}"));
+
+    let descriptor = descriptor
+        .replace(PRIMARY_KEY, descriptor_key(&primary.0)?)
+        .replace(RECOVERY_KEY, descriptor_key(&recovery.0)?);
+
+    Ok(SecretString(descriptor))
+}
+
+/// Whether the policy, or one of its items, is locked by a relative timelock
+fn is_timelocked(policy: &Policy) -> bool {
+    match &policy.item {
+        SatisfiableItem::RelativeTimelock { .. } => true,
+        SatisfiableItem::Thresh { items, .. } => items.iter().any(is_timelocked),
+        _ => false,
+    }
+}
+
+/// Index of the timelocked recovery branch among the items of the vault policy
+fn recovery_path(policy: &Policy) -> Result<Vec<usize>, BitcoinVaultError> {
+    match &policy.item {
+        SatisfiableItem::Thresh { items, .. } => items
+            .iter()
+            .position(is_timelocked)
+            .map(|index| vec![index])
+            .ok_or(BitcoinVaultError::NoRecoveryPolicy),
+        _ => Err(BitcoinVaultError::NoRecoveryPolicy),
+    }
+}
+
+/// Sweeps the matured coins of the vault to the destination, through the recovery path
+pub async fn recover_vault(
+    sk: &str,
+    request: VaultRecoveryRequest,
+) -> Result<TransactionDetails, BitcoinVaultError> {
+    let VaultRecoveryRequest {
+        primary,
+        recovery,
+        timelock,
+        destination,
+        fee_rate,
+    } = request;
+
+    let descriptor = compile_vault_descriptor(&primary, &recovery, timelock)?;
+    let wallet = get_wallet(&descriptor, None).await?;
+    sync_wallet(&wallet).await?;
+
+    let address = Address::from_str(&destination)?;
+    let fee_rate = resolve_fee_rate(fee_rate).await?;
+    let tip = get_tip_height().await?;
+
+    let psbt = {
+        let locked_wallet = wallet.lock().await;
+
+        // Coins can be spent in the next block once they are `timelock` blocks old
+        let mut matured = vec![];
+        let mut immature = vec![];
+        for utxo in locked_wallet.list_unspent()? {
+            let height = locked_wallet
+                .get_tx(&utxo.outpoint.txid, false)?
+                .and_then(|tx| tx.confirmation_time)
+                .map(|time| time.height);
+            match height {
+                Some(height) if tip + 1 >= height + timelock => matured.push(utxo.outpoint),
+                Some(height) => immature.push((utxo.outpoint, height + timelock - tip - 1)),
+                None => immature.push((utxo.outpoint, timelock)),
+            }
+        }
+
+        if matured.is_empty() {
+            let remaining = immature
+                .iter()
+                .map(|(_, remaining)| *remaining)
+                .min()
+                .unwrap_or(timelock);
+            return Err(BitcoinVaultError::TimelockNotMatured(remaining));
+        }
+
+        let policy = locked_wallet
+            .policies(KeychainKind::External)?
+            .ok_or(BitcoinVaultError::NoRecoveryPolicy)?;
+        let path = BTreeMap::from([(policy.id.clone(), recovery_path(&policy)?)]);
+
+        let mut builder = locked_wallet.build_tx();
+        builder
+            .policy_path(path, KeychainKind::External)
+            .unspendable(immature.into_iter().map(|(outpoint, _)| outpoint).collect())
+            .drain_wallet()
+            .drain_to(address.script_pubkey())
+            .fee_rate(fee_rate);
+        let (psbt, _) = builder.finish()?;
+        psbt
+    };
+
+    let details = sign_and_publish_psbt(sk, &wallet, psbt).await?;
+    info!(format!("Vault recovered in {}", details.txid));
+
+    Ok(details)
+}
@@ -1,17 +1,35 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{env, path::Path};
 
-use bdk::{blockchain::esplora::EsploraBlockchain, database::MemoryDatabase, SyncOptions, Wallet};
+#[cfg(not(target_arch = "wasm32"))]
+use bdk::sled;
+use bdk::{
+    blockchain::esplora::EsploraBlockchain,
+    database::{AnyDatabase, Database, MemoryDatabase},
+    KeychainKind, SyncOptions, Wallet,
+};
 use bitcoin::Network;
 use bitcoin_hashes::{sha256, Hash};
-use futures::Future;
+use futures::{stream, StreamExt};
 use once_cell::sync::Lazy;
 use thiserror::Error;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::bitcoin::chain::get_electrum;
 use crate::{
+    bitcoin::chain::{chain_backend, BitcoinChainError, ChainBackend},
     constants::{BITCOIN_EXPLORER_API, NETWORK},
     debug,
-    structs::SecretString,
+    structs::{
+        AssetType, DiscoveredKeychain, SecretString, WalletSyncDescriptors, WalletSyncEvent,
+        WalletSyncRequest, WalletSyncStatus,
+    },
 };
 
 #[derive(Error, Debug)]
@@ -22,10 +40,19 @@ pub enum BitcoinWalletError {
     /// BDK error
     #[error(transparent)]
     BdkError(#[from] bdk::Error),
+    /// BitMask Core Bitcoin Chain error
+    #[error(transparent)]
+    BitcoinChainError(#[from] BitcoinChainError),
+    /// Sled wallet database error
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    SledError(#[from] sled::Error),
 }
 
-pub type MemoryWallet = Arc<Mutex<Wallet<MemoryDatabase>>>;
-type Wallets = BTreeMap<(String, Option<String>), MemoryWallet>;
+/// Cached wallet handle. On native targets the wallet is backed by a persistent database,
+/// the in-memory map only avoids reopening it.
+pub type WalletHandle = Arc<Mutex<Wallet<AnyDatabase>>>;
+type Wallets = BTreeMap<(String, Option<String>), WalletHandle>;
 type NetworkWallet = Arc<RwLock<Wallets>>;
 
 #[derive(Default)]
@@ -38,52 +65,115 @@ struct Networks {
 
 static BDK: Lazy<Networks> = Lazy::new(Networks::default);
 
-async fn access_network_wallets<U, F, Fut>(
+/// Wallets synced at the same time by `sync_wallets`
+const SYNC_PARALLELISM: usize = 8;
+const SYNC_EVENTS_CAPACITY: usize = 256;
+
+static SYNC_EVENTS: Lazy<broadcast::Sender<WalletSyncEvent>> =
+    Lazy::new(|| broadcast::channel(SYNC_EVENTS_CAPACITY).0);
+
+#[cfg(not(target_arch = "wasm32"))]
+static WALLET_DB: Lazy<std::sync::Mutex<BTreeMap<String, sled::Db>>> = Lazy::new(Default::default);
+
+/// Opens the wallet database for a descriptor key.
+///
+/// Native targets persist every wallet as a sled tree under `BITMASK_WALLET_DIR` (one database
+/// per network), so history survives restarts and `sync_wallet` does not download the stored
+/// transactions again. Sled allows a single process per database: when another process (a
+/// second bitmaskd, tests running in parallel) holds it, the wallet is kept in memory instead
+/// and fully rescanned, so each process needs its own `BITMASK_WALLET_DIR` to persist wallets.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_database(network: Network, key: &str) -> Result<AnyDatabase, BitcoinWalletError> {
+    match open_tree(network, key)? {
+        Some(tree) => Ok(AnyDatabase::Sled(tree)),
+        None => Ok(AnyDatabase::Memory(MemoryDatabase::default())),
+    }
+}
+
+/// Opens a tree of the wallet database of the network, none when another process holds it
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn open_tree(
     network: Network,
-    mut f: F,
-) -> Result<(), BitcoinWalletError>
-where
-    U: 'static + Send,
-    F: 'static + FnMut(NetworkWallet) -> Fut + Send,
-    Fut: 'static + Future<Output = Result<U, BitcoinWalletError>> + Send,
-{
-    match network {
-        Network::Bitcoin => {
-            f(BDK.bitcoin.clone()).await?;
-        }
-        Network::Testnet => {
-            f(BDK.testnet.clone()).await?;
-        }
-        Network::Signet => {
-            f(BDK.signet.clone()).await?;
-        }
-        Network::Regtest => {
-            f(BDK.regtest.clone()).await?;
+    name: &str,
+) -> Result<Option<sled::Tree>, BitcoinWalletError> {
+    let mut databases = WALLET_DB
+        .lock()
+        .expect("wallet database lock is not poisoned");
+    let network = network.to_string();
+
+    let db = match databases.get(&network) {
+        Some(db) => db.clone(),
+        None => {
+            let directory = Path::new(
+                &env::var("BITMASK_WALLET_DIR").unwrap_or("/tmp/bitmaskd/wallets".to_owned()),
+            )
+            .join(&network);
+            debug!(format!(
+                "Opening wallet database at {}",
+                directory.display()
+            ));
+
+            let db = match sled::open(&directory) {
+                Ok(db) => db,
+                Err(sled::Error::Io(err)) if err.to_string().contains("could not acquire lock") => {
+                    debug!(format!(
+                        "Wallet database at {} is locked by another process, keeping the wallet in memory",
+                        directory.display()
+                    ));
+                    return Ok(None);
+                }
+                Err(err) => return Err(err.into()),
+            };
+            databases.insert(network, db.clone());
+            db
         }
     };
 
-    Ok(())
+    Ok(Some(db.open_tree(name)?))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_database(_network: Network, _key: &str) -> Result<AnyDatabase, BitcoinWalletError> {
+    Ok(AnyDatabase::Memory(MemoryDatabase::default()))
+}
+
+fn network_wallets(network: Network) -> NetworkWallet {
+    match network {
+        Network::Bitcoin => BDK.bitcoin.clone(),
+        Network::Testnet => BDK.testnet.clone(),
+        Network::Signet => BDK.signet.clone(),
+        Network::Regtest => BDK.regtest.clone(),
+    }
+}
+
+/// Subscribes to the progress of `sync_wallets`, one event when a wallet starts syncing and
+/// one when it is done. Slow receivers miss the oldest events.
+pub fn subscribe_sync_events() -> broadcast::Receiver<WalletSyncEvent> {
+    SYNC_EVENTS.subscribe()
+}
+
+fn emit_sync_event(event: WalletSyncEvent) {
+    // Sending only fails when nobody is subscribed
+    let _ = SYNC_EVENTS.send(event);
+}
+
+/// Key of the wallet of the descriptors in the wallet cache, as found in its sync events
+pub fn wallet_key(descriptor: &SecretString, change_descriptor: Option<&SecretString>) -> String {
+    let descriptor_key = format!("{descriptor:?}{change_descriptor:?}");
+    sha256::Hash::hash(descriptor_key.as_bytes()).to_string()
 }
 
 pub async fn get_wallet(
     descriptor: &SecretString,
     change_descriptor: Option<&SecretString>,
-) -> Result<Arc<Mutex<Wallet<MemoryDatabase>>>, BitcoinWalletError> {
-    let descriptor_key = format!("{descriptor:?}{change_descriptor:?}");
-    let key = sha256::Hash::hash(descriptor_key.as_bytes()).to_string();
+) -> Result<WalletHandle, BitcoinWalletError> {
+    let key = wallet_key(descriptor, change_descriptor);
 
     let network_lock = NETWORK.read().await;
     let network = network_lock.to_owned();
     drop(network_lock);
 
-    let wallets = match network {
-        Network::Bitcoin => BDK.bitcoin.clone(),
-        Network::Testnet => BDK.testnet.clone(),
-        Network::Signet => BDK.signet.clone(),
-        Network::Regtest => BDK.regtest.clone(),
-    };
-
-    let wallets = wallets.clone();
+    let wallets = network_wallets(network);
     let wallets_lock = wallets.read().await;
     let wallets_ref = wallets_lock.get(&(key.clone(), None));
     if let Some(wallets) = wallets_ref {
@@ -95,27 +185,17 @@ pub async fn get_wallet(
         &descriptor.0,
         change_descriptor.map(|desc| &desc.0),
         network,
-        MemoryDatabase::default(),
+        open_database(network, &key)?,
     )?));
 
-    let key_outer = key;
-    let new_wallet_outer = new_wallet.clone();
-
-    access_network_wallets(network, move |wallets| {
-        let key_inner = key_outer.clone();
-        let new_wallet_inner = new_wallet_outer.clone();
-
-        async move {
-            wallets
-                .write()
-                .await
-                .insert((key_inner, None), new_wallet_inner);
-            Ok(())
-        }
-    })
-    .await?;
+    let mut wallets_lock = wallets.write().await;
+    // Another task may have opened the wallet while the lock was released
+    let wallet = wallets_lock
+        .entry((key, None))
+        .or_insert(new_wallet)
+        .clone();
 
-    Ok(new_wallet)
+    Ok(wallet)
 }
 
 pub async fn get_blockchain() -> EsploraBlockchain {
@@ -123,75 +203,199 @@ pub async fn get_blockchain() -> EsploraBlockchain {
     EsploraBlockchain::new(&BITCOIN_EXPLORER_API.read().await, 1)
 }
 
-pub async fn sync_wallet(wallet: &MemoryWallet) -> Result<(), BitcoinWalletError> {
-    let blockchain = get_blockchain().await;
-    wallet
-        .lock()
-        .await
-        .sync(&blockchain, SyncOptions::default())
-        .await?;
-
-    debug!("Wallet synced");
-    Ok(())
+pub async fn sync_wallet(wallet: &WalletHandle) -> Result<(), BitcoinWalletError> {
+    sync_wallet_until_gap(wallet, chain_backend().await?, None).await
 }
 
-pub async fn sync_wallets() -> Result<(), BitcoinWalletError> {
-    let network_lock = NETWORK.read().await;
-    let network = network_lock.to_owned();
-    drop(network_lock);
+/// Syncs the wallet with the given chain backend instead of the configured one
+pub async fn sync_wallet_with_backend(
+    wallet: &WalletHandle,
+    backend: ChainBackend,
+) -> Result<(), BitcoinWalletError> {
+    sync_wallet_until_gap(wallet, backend, None).await
+}
 
-    /* // BDK RefCell prevents this from working:
-       access_network_wallets(network, move |wallets| async move {
-           for (key, &mut wallet) in wallets.write().await.iter_mut() {
-               let blockchain = get_blockchain().await;
-               let wallet = wallet.lock().await;
-               let wallet_sync_fut = wallet.sync(&blockchain, SyncOptions::default());
-               wallet_sync_fut.await?;
-           }
+/// Syncs the wallet, scanning each keychain until `stop_gap` consecutive addresses past the
+/// last used one have no history. Restored wallets need a wider gap than the regular sync.
+pub async fn sync_wallet_with_gap(
+    wallet: &WalletHandle,
+    stop_gap: u32,
+) -> Result<(), BitcoinWalletError> {
+    sync_wallet_until_gap(wallet, chain_backend().await?, Some(stop_gap as usize)).await
+}
 
-           Ok(())
-       });
-    */
+async fn sync_wallet_until_gap(
+    wallet: &WalletHandle,
+    backend: ChainBackend,
+    stop_gap: Option<usize>,
+) -> Result<(), BitcoinWalletError> {
+    let wallet = wallet.lock().await;
+    if let Some(sync_time) = wallet.database().get_sync_time()? {
+        debug!(format!(
+            "Syncing wallet stored at height {}, known transactions are not downloaded again",
+            sync_time.block_time.height
+        ));
+    }
 
-    match network {
-        Network::Bitcoin => {
-            let wallets = BDK.bitcoin.clone();
-            for (_key, wallet) in wallets.write().await.iter_mut() {
-                let blockchain = get_blockchain().await;
-                let wallet = wallet.lock().await;
-                let wallet_sync_fut = wallet.sync(&blockchain, SyncOptions::default());
-                wallet_sync_fut.await?;
-            }
-        }
-        Network::Testnet => {
-            let wallets = BDK.testnet.clone();
-            for (_key, wallet) in wallets.write().await.iter_mut() {
-                let blockchain = get_blockchain().await;
-                let wallet = wallet.lock().await;
-                let wallet_sync_fut = wallet.sync(&blockchain, SyncOptions::default());
-                wallet_sync_fut.await?;
-            }
+    match backend {
+        ChainBackend::Esplora => {
+            let blockchain = match stop_gap {
+                Some(stop_gap) => {
+                    EsploraBlockchain::new(&BITCOIN_EXPLORER_API.read().await, stop_gap)
+                }
+                None => get_blockchain().await,
+            };
+            wallet.sync(&blockchain, SyncOptions::default()).await?;
         }
-        Network::Signet => {
-            let wallets = BDK.signet.clone();
-            for (_key, wallet) in wallets.write().await.iter_mut() {
-                let blockchain = get_blockchain().await;
-                let wallet = wallet.lock().await;
-                let wallet_sync_fut = wallet.sync(&blockchain, SyncOptions::default());
-                wallet_sync_fut.await?;
+        #[cfg(not(target_arch = "wasm32"))]
+        ChainBackend::Electrum => {
+            let mut blockchain = get_electrum().await?;
+            if let Some(stop_gap) = stop_gap {
+                blockchain = blockchain.with_stop_gap(stop_gap);
             }
+            wallet.sync(&blockchain, SyncOptions::default()).await?;
         }
-        Network::Regtest => {
-            let wallets = BDK.regtest.clone();
-            for (_key, wallet) in wallets.write().await.iter_mut() {
-                let blockchain = get_blockchain().await;
-                let wallet = wallet.lock().await;
-                let wallet_sync_fut = wallet.sync(&blockchain, SyncOptions::default());
-                wallet_sync_fut.await?;
+        #[cfg(target_arch = "wasm32")]
+        backend => return Err(BitcoinChainError::UnsupportedBackend(backend).into()),
+    };
+
+    debug!(format!("Wallet synced with {backend}"));
+    Ok(())
+}
+
+/// Addresses of the external and change keychains that received outputs, for a wallet synced
+/// with `gap_limit`
+pub async fn discovered_keychains(
+    wallet: &WalletHandle,
+    gap_limit: u32,
+) -> Result<Vec<DiscoveredKeychain>, BitcoinWalletError> {
+    let wallet = wallet.lock().await;
+    let database = wallet.database();
+    let txs = database.iter_txs(true)?;
+
+    let mut discovered = vec![];
+    for (keychain, asset_type) in [
+        (KeychainKind::External, AssetType::Bitcoin),
+        (KeychainKind::Internal, AssetType::Change),
+    ] {
+        let mut used = BTreeSet::new();
+        let mut received_outputs = 0;
+        for output in txs
+            .iter()
+            .filter_map(|tx| tx.transaction.as_ref())
+            .flat_map(|tx| &tx.output)
+        {
+            match database.get_path_from_script_pubkey(&output.script_pubkey)? {
+                Some((kind, index)) if kind == keychain => {
+                    used.insert(index);
+                    received_outputs += 1;
+                }
+                _ => {}
             }
         }
+
+        let last_used_index = used.iter().max().copied();
+        discovered.push(DiscoveredKeychain {
+            asset_type,
+            scanned_addresses: last_used_index.map_or(gap_limit, |index| index + 1 + gap_limit),
+            used_addresses: used.len() as u32,
+            last_used_index,
+            received_outputs,
+        });
+    }
+
+    Ok(discovered)
+}
+
+async fn sync_wallet_with_events(
+    key: String,
+    wallet: WalletHandle,
+    job: Option<String>,
+) -> Result<(), BitcoinWalletError> {
+    emit_sync_event(WalletSyncEvent {
+        wallet: key.clone(),
+        status: WalletSyncStatus::Started,
+        scanned_scripts: 0,
+        tip_height: None,
+        job: job.clone(),
+    });
+
+    let synced = sync_wallet(&wallet).await;
+    let (scanned_scripts, tip_height) = {
+        let wallet = wallet.lock().await;
+        let database = wallet.database();
+        let scanned_scripts = database.iter_script_pubkeys(None)?.len();
+        let tip_height = database
+            .get_sync_time()?
+            .map(|sync_time| sync_time.block_time.height);
+        (scanned_scripts, tip_height)
     };
 
-    debug!("All wallets synced");
-    Ok(())
+    let status = match &synced {
+        Ok(()) => WalletSyncStatus::Synced,
+        Err(err) => WalletSyncStatus::Failed(err.to_string()),
+    };
+    emit_sync_event(WalletSyncEvent {
+        wallet: key,
+        status,
+        scanned_scripts,
+        tip_height,
+        job,
+    });
+
+    synced
+}
+
+/// Syncs every cached wallet of the current network, `SYNC_PARALLELISM` at a time.
+/// The wallet map is only locked while listing the wallets, and a failing wallet does not
+/// stop the others; the first error is returned once all of them were synced.
+pub async fn sync_wallets() -> Result<(), BitcoinWalletError> {
+    let network = *NETWORK.read().await;
+
+    let wallets: Vec<_> = network_wallets(network)
+        .read()
+        .await
+        .iter()
+        .map(|((key, _), wallet)| (key.clone(), wallet.clone()))
+        .collect();
+
+    sync_wallets_with_events(wallets, None).await
+}
+
+/// Syncs the wallets of the descriptors, opening the ones that are not cached yet.
+/// Progress is reported to the `subscribe_sync_events` receivers, like `sync_wallets`, with
+/// the events tagged with the job so its caller can tell them apart.
+pub async fn sync_wallet_descriptors(
+    request: WalletSyncRequest,
+    job: Option<String>,
+) -> Result<(), BitcoinWalletError> {
+    let mut wallets = vec![];
+    for WalletSyncDescriptors {
+        descriptor,
+        change_descriptor,
+    } in request.wallets
+    {
+        let key = wallet_key(&descriptor, change_descriptor.as_ref());
+        let wallet = get_wallet(&descriptor, change_descriptor.as_ref()).await?;
+        wallets.push((key, wallet));
+    }
+
+    sync_wallets_with_events(wallets, job).await
+}
+
+async fn sync_wallets_with_events(
+    wallets: Vec<(String, WalletHandle)>,
+    job: Option<String>,
+) -> Result<(), BitcoinWalletError> {
+    let count = wallets.len();
+
+    let results: Vec<_> = stream::iter(wallets)
+        .map(|(key, wallet)| sync_wallet_with_events(key, wallet, job.clone()))
+        .buffer_unordered(SYNC_PARALLELISM)
+        .collect()
+        .await;
+
+    let failed = results.iter().filter(|result| result.is_err()).count();
+    debug!(format!("{count} wallets synced, {failed} failed"));
+    results.into_iter().collect()
 }
@@ -37,6 +37,10 @@ static BITCOIN_EXPLORER_API_REGTEST: Lazy<RwLock<String>> =
 pub static BITCOIN_EXPLORER_API: Lazy<RwLock<String>> =
     Lazy::new(|| RwLock::new(dot_env("BITCOIN_EXPLORER_API_REGTEST")));
 
+/// Chain backend used to sync wallets, broadcast and resolve RGB data (esplora or electrum)
+pub static BITCOIN_CHAIN_BACKEND: Lazy<RwLock<String>> =
+    Lazy::new(|| RwLock::new(dot_env("BITCOIN_CHAIN_BACKEND")));
+
 static BITCOIN_ELECTRUM_API_MAINNET: Lazy<RwLock<String>> =
     Lazy::new(|| RwLock::new(dot_env("BITCOIN_ELECTRUM_API_MAINNET")));
 static BITCOIN_ELECTRUM_API_TESTNET: Lazy<RwLock<String>> =
@@ -93,6 +97,9 @@ pub static BTC_PATH: Lazy<RwLock<String>> = Lazy::new(|| {
     })
 });
 
+/// Consecutive unused addresses ending the scan of a keychain when restoring a wallet
+pub const DISCOVERY_GAP_LIMIT: u32 = 20;
+
 // Magic number for versioning descriptors
 pub const DIBA_DESCRIPTOR_VERSION: u8 = 0;
 pub const DIBA_MAGIC_NO: [u8; 4] = *b"DIBA";
@@ -103,6 +110,17 @@ pub const DIBA_DESCRIPTOR: [u8; 5] = [
     DIBA_MAGIC_NO[3],
     DIBA_DESCRIPTOR_VERSION,
 ];
+// Descriptors encrypted with a per-wallet salt and the Argon2 parameters in their header
+pub const DIBA_DESCRIPTOR_VERSION_ARGON2: u8 = 1;
+pub const DIBA_SALT_LEN: usize = 16;
+// Argon2id costs of new encrypted descriptors: memory (KiB), iterations and parallelism
+pub const DIBA_ARGON2_M_COST: u32 = 65_536;
+pub const DIBA_ARGON2_T_COST: u32 = 3;
+pub const DIBA_ARGON2_P_COST: u32 = 1;
+// Upper bounds of the Argon2id costs accepted from the header of encrypted descriptors
+pub const DIBA_ARGON2_MAX_M_COST: u32 = 1_048_576;
+pub const DIBA_ARGON2_MAX_T_COST: u32 = 16;
+pub const DIBA_ARGON2_MAX_P_COST: u32 = 8;
 
 pub static NETWORK: Lazy<RwLock<Network>> = Lazy::new(|| {
     RwLock::new(Network::from_str(&dot_env("BITCOIN_NETWORK")).expect("Parse Bitcoin network"))
@@ -163,6 +181,7 @@ pub async fn get_env(key: &str) -> String {
         "BITCOIN_EXPLORER_API_TESTNET" => BITCOIN_EXPLORER_API_TESTNET.read().await.to_string(),
         "BITCOIN_EXPLORER_API_SIGNET" => BITCOIN_EXPLORER_API_SIGNET.read().await.to_string(),
         "BITCOIN_EXPLORER_API_REGTEST" => BITCOIN_EXPLORER_API_REGTEST.read().await.to_string(),
+        "BITCOIN_CHAIN_BACKEND" => BITCOIN_CHAIN_BACKEND.read().await.to_string(),
         "BITCOIN_ELECTRUM_API_MAINNET" => BITCOIN_ELECTRUM_API_MAINNET.read().await.to_string(),
         "BITCOIN_ELECTRUM_API_TESTNET" => BITCOIN_ELECTRUM_API_TESTNET.read().await.to_string(),
         "BITCOIN_ELECTRUM_API_SIGNET" => BITCOIN_ELECTRUM_API_SIGNET.read().await.to_string(),
@@ -191,6 +210,7 @@ pub async fn set_env(key: &str, value: &str) {
         "BITCOIN_EXPLORER_API_REGTEST" => {
             *BITCOIN_EXPLORER_API_REGTEST.write().await = value.to_owned()
         }
+        "BITCOIN_CHAIN_BACKEND" => *BITCOIN_CHAIN_BACKEND.write().await = value.to_owned(),
         "BITCOIN_ELECTRUM_API_MAINNET" => {
             *BITCOIN_ELECTRUM_API_MAINNET.write().await = value.to_owned()
         }
@@ -215,6 +235,11 @@ pub mod storage_keys {
     pub const ASSETS_TRANSFERS: &str = "bitmask_assets_transfers.c15";
     pub const ASSETS_OFFERS: &str = "bitmask-asset_offers.c15";
     pub const ASSETS_BIDS: &str = "bitmask-asset_bids.c15";
+    pub const ASSETS_AIRDROPS: &str = "bitmask-asset_airdrops.c15";
+    pub const ASSETS_BURNS: &str = "bitmask-asset_burns.c15";
     pub const MARKETPLACE_OFFERS: &str = "bitmask-marketplace_public_offers.c15";
     pub const MARKETPLACE_BIDS: &str = "bitmask-marketplace_public_bids.c15";
+    pub const BITCOIN_FROZEN_UTXOS: &str = "bitmask-bitcoin_frozen_utxos.c15";
+    pub const BITCOIN_LABELS: &str = "bitmask-bitcoin_labels.c15";
+    pub const BITCOIN_BROADCAST_QUEUE: &str = "bitmask-bitcoin_broadcast_queue.c15";
 }
@@ -5,11 +5,12 @@ use amplify::{
 };
 use anyhow::Result;
 use autosurgeon::reconcile;
-use bitcoin::{psbt::PartiallySignedTransaction, EcdsaSighashType, Network, Txid};
+use bitcoin::{psbt::PartiallySignedTransaction, EcdsaSighashType, Network, Transaction, Txid};
 use bitcoin_30::bip32::ExtendedPubKey;
 use bitcoin_scripts::address::AddressNetwork;
 use garde::Validate;
 
+use ::wallet::onchain::ResolveTx;
 use miniscript_crate::DescriptorPublicKey;
 use rgb::RgbDescr;
 use rgbstd::{
@@ -21,7 +22,7 @@ use rgbstd::{
 };
 use rgbwallet::{psbt::DbcPsbtError, RgbInvoice};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ops::Sub,
     str::FromStr,
 };
@@ -46,25 +47,31 @@ pub mod transfer;
 pub mod wallet;
 
 use crate::{
-    constants::{get_network, BITCOIN_EXPLORER_API, NETWORK},
+    bitcoin::{get_blockchain, get_wallet, multi_sign_and_finalize_psbt, queue_transaction},
+    constants::{get_network, NETWORK},
     rgb::{
         issue::{issue_contract as create_contract, IssueContractError},
         psbt::{create_psbt as create_rgb_psbt, extract_commit},
         resolvers::ExplorerResolver,
         transfer::{
-            accept_transfer as accept_rgb_transfer, create_invoice as create_rgb_invoice,
-            pay_invoice,
+            accept_transfer as accept_rgb_transfer, beneficiary_invoice,
+            burn_contract as burn_rgb_contract, create_invoice as create_rgb_invoice,
+            inflate_contract as create_inflation, pay_invoice, pay_invoices,
+            verify_burn as verify_rgb_burn,
         },
         wallet::list_allocations,
     },
     structs::{
         AcceptRequest, AcceptResponse, AssetType, BatchRgbTransferItem, BatchRgbTransferResponse,
-        ContractMetadata, ContractResponse, ContractsResponse, FullRgbTransferRequest,
-        ImportRequest, InterfaceDetail, InterfacesResponse, InvoiceRequest, InvoiceResponse,
+        BroadcastStatus, ContractMetadata, ContractResponse, ContractsResponse, DiscoveredKeychain,
+        FullRgbBatchTransferRequest, FullRgbTransferRequest, ImportRequest, InflateRequest,
+        InflateResponse, InterfaceDetail, InterfacesResponse, InvoiceRequest, InvoiceResponse,
         IssueMetaRequest, IssueMetadata, IssueRequest, IssueResponse, NewCollectible,
         NextAddressResponse, NextUtxoResponse, NextUtxosResponse, PsbtFeeRequest, PsbtRequest,
         PsbtResponse, PublicRgbBidResponse, PublicRgbOfferResponse, PublicRgbOffersResponse,
-        ReIssueRequest, ReIssueResponse, RgbBidDetail, RgbBidRequest, RgbBidResponse,
+        ReIssueRequest, ReIssueResponse, RgbAirdropChunk, RgbAirdropChunkStatus,
+        RgbAirdropRecipient, RgbAirdropRequest, RgbAirdropResponse, RgbBatchConsignment,
+        RgbBatchInvoice, RgbBatchTransferResponse, RgbBidDetail, RgbBidRequest, RgbBidResponse,
         RgbBidsResponse, RgbInvoiceResponse, RgbOfferBidsResponse, RgbOfferDetail, RgbOfferRequest,
         RgbOfferResponse, RgbOffersResponse, RgbRemoveTransferRequest, RgbSaveTransferRequest,
         RgbSwapRequest, RgbSwapResponse, RgbTransferDetail, RgbTransferInternalParams,
@@ -76,30 +83,33 @@ use crate::{
 };
 
 use self::{
-    constants::{RGB_DEFAULT_FETCH_LIMIT, RGB_DEFAULT_NAME},
+    constants::{RGB_AIRDROP_CHUNK_SIZE, RGB_DEFAULT_FETCH_LIMIT, RGB_DEFAULT_NAME},
     contract::{export_contract, ExportContractError},
     crdt::{LocalRgbAccount, RawRgbAccount, RgbMerge},
     fs::{
-        retrieve_account, retrieve_bids, retrieve_local_account, retrieve_offers,
-        retrieve_public_offers, retrieve_stock as retrieve_rgb_stock, retrieve_stock_account,
-        retrieve_stock_account_transfers, retrieve_stock_transfers, retrieve_transfers,
-        store_account, store_bids, store_local_account, store_offers,
-        store_stock as store_rgb_stock, store_stock_account, store_stock_account_transfers,
-        store_stock_transfers, store_transfers, RgbPersistenceError,
+        retrieve_account, retrieve_airdrops, retrieve_bids, retrieve_burns, retrieve_local_account,
+        retrieve_offers, retrieve_public_offers, retrieve_stock as retrieve_rgb_stock,
+        retrieve_stock_account, retrieve_stock_account_transfers, retrieve_stock_transfers,
+        retrieve_transfers, store_account, store_airdrops, store_bids, store_burns,
+        store_local_account, store_offers, store_stock as store_rgb_stock, store_stock_account,
+        store_stock_account_transfers, store_stock_transfers, store_transfers, RgbPersistenceError,
     },
     import::{import_contract, ImportContractError},
     prebuild::{
-        prebuild_buyer_swap, prebuild_extract_transfer, prebuild_seller_swap,
-        prebuild_transfer_asset,
+        invoice_amount, prebuild_batch_transfer_asset, prebuild_burn_asset, prebuild_buyer_swap,
+        prebuild_extract_transfer, prebuild_inflation, prebuild_seller_swap,
+        prebuild_spendable_amount, prebuild_transfer_asset,
     },
     prefetch::{
         prefetch_resolver_allocations, prefetch_resolver_images, prefetch_resolver_import_rgb,
-        prefetch_resolver_psbt, prefetch_resolver_rgb, prefetch_resolver_txs_status,
-        prefetch_resolver_user_utxo_status, prefetch_resolver_utxos, prefetch_resolver_waddress,
-        prefetch_resolver_wutxo,
+        prefetch_resolver_psbt, prefetch_resolver_rgb, prefetch_resolver_txs,
+        prefetch_resolver_txs_status, prefetch_resolver_user_utxo_status, prefetch_resolver_utxos,
+        prefetch_resolver_waddress, prefetch_resolver_wutxo,
     },
     psbt::{save_commit, set_tapret_position, CreatePsbtError, EstimateFeeError},
-    structs::{RgbAccount, RgbExtractTransfer, RgbTransfer, RgbTransfers},
+    structs::{
+        RgbAccount, RgbAirdrop, RgbBurn, RgbBurns, RgbExtractTransfer, RgbTransfer, RgbTransfers,
+    },
     swap::{
         get_public_offer, get_swap_bid, mark_bid_fill, mark_offer_fill, mark_transfer_bid,
         mark_transfer_offer, publish_public_bid, publish_public_offer, publish_swap_bid,
@@ -108,8 +118,8 @@ use self::{
     },
     transfer::{AcceptTransferError, NewInvoiceError, NewPaymentError},
     wallet::{
-        create_wallet, next_address, next_utxo, next_utxos, register_address, register_utxo,
-        sync_wallet,
+        create_wallet, discover_utxos, next_address, next_utxo, next_utxos, register_address,
+        register_utxo, sync_wallet,
     },
 };
 
@@ -148,12 +158,10 @@ pub async fn issue_contract(sk: &str, request: IssueRequest) -> Result<IssueResp
         iface,
         seal,
         meta,
+        inflation,
     } = request;
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) = retrieve_stock_account(sk).await.map_err(IssueError::IO)?;
     let network = get_network().await;
@@ -195,6 +203,7 @@ pub async fn issue_contract(sk: &str, request: IssueRequest) -> Result<IssueResp
         &seal,
         &network,
         meta,
+        inflation,
         udas_data,
         &mut resolver,
         &mut stock,
@@ -216,6 +225,9 @@ pub async fn issue_contract(sk: &str, request: IssueRequest) -> Result<IssueResp
         genesis,
         meta,
         created,
+        issued_supply: _,
+        max_supply: _,
+        burned: _,
     } = export_contract(
         contract.contract_id(),
         &mut stock,
@@ -265,10 +277,7 @@ pub async fn reissue_contract(
         return Err(IssueError::Validation(errors));
     }
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) = retrieve_stock_account(sk).await.map_err(IssueError::IO)?;
 
@@ -354,6 +363,7 @@ pub async fn reissue_contract(
             &seal,
             &network,
             meta,
+            None,
             udas_data,
             &mut resolver,
             &mut stock,
@@ -499,10 +509,7 @@ pub enum PsbtError {
 }
 
 pub async fn create_psbt(sk: &str, request: PsbtRequest) -> Result<PsbtResponse, PsbtError> {
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let mut rgb_account = retrieve_account(sk).await.map_err(PsbtError::IO)?;
     let psbt = internal_create_psbt(request, true, None, &mut rgb_account, &mut resolver).await?;
@@ -547,7 +554,7 @@ async fn internal_create_psbt(
     // Retrieve transaction fee
     let fee = match fee {
         PsbtFeeRequest::Value(fee) => fee,
-        PsbtFeeRequest::FeeRate(_) => return Err(PsbtError::NoFeeRate),
+        PsbtFeeRequest::FeeRate(_) | PsbtFeeRequest::Target(_) => return Err(PsbtError::NoFeeRate),
     };
 
     let wallet = rgb_account.wallets.get("default");
@@ -590,6 +597,8 @@ pub enum TransferError {
     NoIface,
     /// FeeRate is supported in this operation. Please, use the absolute fee value.
     NoFeeRate,
+    /// Bitcoin fee cannot be resolved. {0}
+    WrongFee(String),
     /// Insufficient funds (expected: {input} sats / current: {output} sats)
     Inflation {
         /// Amount spent: input amounts
@@ -620,6 +629,12 @@ pub enum TransferError {
     WrongSwap(RgbOfferErrors),
     /// Occurs an error in export step. {0}
     Export(ExportContractError),
+    /// Occurs an error in invoice step. {0}
+    Invoice(NewInvoiceError),
+    /// Occurs an error in sign or broadcast step. {0}
+    Broadcast(String),
+    /// Burn {0} is not found
+    NoBurn(String),
 }
 
 pub async fn full_transfer_asset(
@@ -651,10 +666,7 @@ pub async fn full_transfer_asset(
         .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
     let mut rgb_account_changes = RawRgbAccount::from(rgb_account.clone());
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let mut rgb_wallet = match rgb_account.wallets.get(RGB_DEFAULT_NAME) {
         Some(rgb_wallet) => rgb_wallet.to_owned(),
@@ -715,6 +727,894 @@ pub async fn full_transfer_asset(
     Ok(resp)
 }
 
+/// Pays several RGB invoices, possibly of different contracts, with one PSBT and a single
+/// tapret commitment. Each beneficiary gets its own consignment.
+pub async fn full_batch_transfer_asset(
+    sk: &str,
+    request: FullRgbBatchTransferRequest,
+) -> Result<RgbBatchTransferResponse, TransferError> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    let (mut stock, mut rgb_transfers) = retrieve_stock_transfers(sk)
+        .await
+        .map_err(TransferError::IO)?;
+
+    let local_rgb_account = retrieve_local_account(sk)
+        .await
+        .map_err(TransferError::IO)?;
+
+    let LocalRgbAccount {
+        doc,
+        mut rgb_account,
+    } = local_rgb_account;
+    let mut fork_wallet = automerge::AutoCommit::load(&doc)
+        .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+    let mut rgb_account_changes = RawRgbAccount::from(rgb_account.clone());
+
+    let mut resolver = ExplorerResolver::from_config().await;
+
+    let resp = internal_batch_transfer_asset(
+        request,
+        &mut stock,
+        &mut rgb_account,
+        &mut rgb_transfers,
+        &mut resolver,
+    )
+    .await?;
+
+    rgb_account.clone().update(&mut rgb_account_changes);
+    reconcile(&mut fork_wallet, rgb_account_changes.clone())
+        .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+
+    store_local_account(sk, fork_wallet.save())
+        .await
+        .map_err(TransferError::IO)?;
+
+    store_stock_transfers(sk, stock, rgb_transfers)
+        .await
+        .map_err(TransferError::IO)?;
+
+    Ok(resp)
+}
+
+async fn internal_batch_transfer_asset(
+    request: FullRgbBatchTransferRequest,
+    stock: &mut Stock,
+    rgb_account: &mut RgbAccount,
+    rgb_transfers: &mut RgbTransfers,
+    resolver: &mut ExplorerResolver,
+) -> Result<RgbBatchTransferResponse, TransferError> {
+    let mut rgb_wallet = match rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+        Some(rgb_wallet) => rgb_wallet.to_owned(),
+        _ => return Err(TransferError::NoWatcher),
+    };
+
+    let (asset_inputs, bitcoin_inputs, bitcoin_changes, fee_value) =
+        prebuild_batch_transfer_asset(request.clone(), stock, &mut rgb_wallet, resolver).await?;
+
+    let FullRgbBatchTransferRequest {
+        invoices,
+        change_terminal,
+        ..
+    } = request;
+
+    let psbt_req = PsbtRequest {
+        fee: PsbtFeeRequest::Value(fee_value),
+        asset_inputs,
+        bitcoin_inputs,
+        bitcoin_changes,
+        asset_descriptor_change: None,
+        asset_terminal_change: Some(change_terminal),
+    };
+
+    let psbt_response = internal_create_psbt(psbt_req, true, None, rgb_account, resolver)
+        .await
+        .map_err(TransferError::Create)?;
+
+    let rgb_invoices = invoices
+        .iter()
+        .map(|item| item.rgb_invoice.clone())
+        .collect();
+    let (psbt, transfers) =
+        pay_invoices(rgb_invoices, psbt_response.psbt, stock).map_err(TransferError::Pay)?;
+
+    let (outpoint, commit) = extract_commit(psbt.clone()).map_err(TransferError::Commitment)?;
+    if let Some(wallet) = rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+        let mut wallet = wallet.to_owned();
+        save_commit(
+            outpoint,
+            commit.clone(),
+            &psbt_response.terminal,
+            &mut wallet,
+        );
+
+        rgb_account
+            .wallets
+            .insert(RGB_DEFAULT_NAME.to_string(), wallet.clone());
+    };
+
+    let bp_txid = bp::Txid::from_hex(&psbt.to_txid().to_hex())
+        .map_err(|err| TransferError::WrongConsig(err.to_string()))?;
+
+    let mut consignments = vec![];
+    for (item, transfer) in invoices.into_iter().zip(transfers) {
+        let consig_id = transfer.bindle_id().to_string();
+        let consig = transfer
+            .to_strict_serialized::<{ U32 }>()
+            .map_err(|err| TransferError::WrongConsig(err.to_string()))?
+            .to_hex();
+
+        let rgb_invoice = RgbInvoice::from_str(&item.rgb_invoice)
+            .map_err(|err| TransferError::WrongInvoice(err.to_string()))?;
+        let contract_id = rgb_invoice.contract.unwrap().to_string();
+        let rgb_transfer = RgbTransfer {
+            iface: rgb_invoice.iface.unwrap().to_string(),
+            consig_id: consig_id.clone(),
+            consig: consig.clone(),
+            tx: bp_txid,
+            is_send: true,
+        };
+        rgb_transfers
+            .transfers
+            .entry(contract_id.clone())
+            .or_default()
+            .push(rgb_transfer);
+
+        consignments.push(RgbBatchConsignment {
+            contract_id,
+            rgb_invoice: item.rgb_invoice,
+            consig_id,
+            consig,
+        });
+    }
+
+    Ok(RgbBatchTransferResponse {
+        psbt: psbt.to_string(),
+        commit: commit.to_hex(),
+        consignments,
+    })
+}
+
+/// Mints additional supply of an inflatable RGB20 asset, spending the inflation allowance held
+/// by the wallet. The new supply is assigned to the beneficiary seals. The allowance is only
+/// checked here, RGB validation does not enforce it (see `ifa_schema`).
+pub async fn inflate_contract(
+    sk: &str,
+    request: InflateRequest,
+) -> Result<InflateResponse, TransferError> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+    for beneficiary in &request.beneficiaries {
+        if let Err(err) = beneficiary.validate(&RGBContext::default()) {
+            let errors = err
+                .flatten()
+                .into_iter()
+                .map(|(f, e)| (f, e.to_string()))
+                .collect();
+            return Err(TransferError::Validation(errors));
+        }
+    }
+
+    let mut stock = retrieve_rgb_stock(sk).await.map_err(TransferError::IO)?;
+
+    let local_rgb_account = retrieve_local_account(sk)
+        .await
+        .map_err(TransferError::IO)?;
+
+    let LocalRgbAccount {
+        doc,
+        mut rgb_account,
+    } = local_rgb_account;
+    let mut fork_wallet = automerge::AutoCommit::load(&doc)
+        .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+    let mut rgb_account_changes = RawRgbAccount::from(rgb_account.clone());
+
+    let mut resolver = ExplorerResolver::from_config().await;
+
+    let mut rgb_wallet = match rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+        Some(rgb_wallet) => rgb_wallet.to_owned(),
+        _ => return Err(TransferError::NoWatcher),
+    };
+
+    let (asset_inputs, bitcoin_inputs, bitcoin_changes, fee_value) =
+        prebuild_inflation(request.clone(), &mut stock, &mut rgb_wallet, &mut resolver).await?;
+
+    let InflateRequest {
+        contract_id,
+        beneficiaries,
+        change_terminal,
+        ..
+    } = request;
+
+    let psbt_req = PsbtRequest {
+        fee: PsbtFeeRequest::Value(fee_value),
+        asset_inputs,
+        bitcoin_inputs,
+        bitcoin_changes,
+        asset_descriptor_change: None,
+        asset_terminal_change: Some(change_terminal),
+    };
+
+    let psbt_response = internal_create_psbt(psbt_req, true, None, &mut rgb_account, &mut resolver)
+        .await
+        .map_err(TransferError::Create)?;
+
+    let (psbt, minted) =
+        create_inflation(&contract_id, beneficiaries, psbt_response.psbt, &mut stock)
+            .map_err(TransferError::Pay)?;
+
+    let (outpoint, commit) = extract_commit(psbt.clone()).map_err(TransferError::Commitment)?;
+    if let Some(wallet) = rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+        let mut wallet = wallet.to_owned();
+        save_commit(
+            outpoint,
+            commit.clone(),
+            &psbt_response.terminal,
+            &mut wallet,
+        );
+
+        rgb_account
+            .wallets
+            .insert(RGB_DEFAULT_NAME.to_string(), wallet.clone());
+    };
+
+    rgb_account.clone().update(&mut rgb_account_changes);
+    reconcile(&mut fork_wallet, rgb_account_changes.clone())
+        .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+
+    store_local_account(sk, fork_wallet.save())
+        .await
+        .map_err(TransferError::IO)?;
+
+    store_rgb_stock(sk, stock)
+        .await
+        .map_err(TransferError::IO)?;
+
+    Ok(InflateResponse {
+        psbt: psbt.to_string(),
+        commit: commit.to_hex(),
+        minted,
+    })
+}
+
+/// Burns an amount of the asset (or the whole UDA token), assigning it to an unspendable output
+/// of the anchor transaction. The consignment of the burn is stored, so a burn proof can be
+/// requested with `burn_proof` once the transaction is published. The burn is only counted by
+/// `get_contract`, `list_contracts` and `list_transfers` once the explorer sees its anchor.
+pub async fn burn_asset(
+    sk: &str,
+    request: RgbBurnRequest,
+) -> Result<RgbBurnResponse, TransferError> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    let mut stock = retrieve_rgb_stock(sk).await.map_err(TransferError::IO)?;
+    let mut rgb_burns = retrieve_burns(sk).await.map_err(TransferError::IO)?;
+
+    let local_rgb_account = retrieve_local_account(sk)
+        .await
+        .map_err(TransferError::IO)?;
+
+    let LocalRgbAccount {
+        doc,
+        mut rgb_account,
+    } = local_rgb_account;
+    let mut fork_wallet = automerge::AutoCommit::load(&doc)
+        .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+    let mut rgb_account_changes = RawRgbAccount::from(rgb_account.clone());
+
+    let mut resolver = ExplorerResolver::from_config().await;
+
+    let mut rgb_wallet = match rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+        Some(rgb_wallet) => rgb_wallet.to_owned(),
+        _ => return Err(TransferError::NoWatcher),
+    };
+
+    let (asset_inputs, bitcoin_inputs, bitcoin_changes, fee_value) =
+        prebuild_burn_asset(request.clone(), &mut stock, &mut rgb_wallet, &mut resolver).await?;
+
+    let RgbBurnRequest {
+        contract_id,
+        iface,
+        amount,
+        change_terminal,
+        ..
+    } = request;
+
+    let psbt_req = PsbtRequest {
+        fee: PsbtFeeRequest::Value(fee_value),
+        asset_inputs,
+        bitcoin_inputs,
+        bitcoin_changes,
+        asset_descriptor_change: None,
+        asset_terminal_change: Some(change_terminal),
+    };
+
+    let psbt_response = internal_create_psbt(psbt_req, true, None, &mut rgb_account, &mut resolver)
+        .await
+        .map_err(TransferError::Create)?;
+
+    let (psbt, transfer, vout, blinding, burned) =
+        burn_rgb_contract(&contract_id, &iface, amount, psbt_response.psbt, &mut stock)
+            .map_err(TransferError::Pay)?;
+
+    let (outpoint, commit) = extract_commit(psbt.clone()).map_err(TransferError::Commitment)?;
+    if let Some(wallet) = rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+        let mut wallet = wallet.to_owned();
+        save_commit(
+            outpoint,
+            commit.clone(),
+            &psbt_response.terminal,
+            &mut wallet,
+        );
+
+        rgb_account
+            .wallets
+            .insert(RGB_DEFAULT_NAME.to_string(), wallet.clone());
+    };
+
+    let bp_txid = bp::Txid::from_hex(&psbt.to_txid().to_hex())
+        .map_err(|err| TransferError::WrongConsig(err.to_string()))?;
+    let consig_id = transfer.bindle_id().to_string();
+    let consig = transfer
+        .to_strict_serialized::<{ U32 }>()
+        .map_err(|err| TransferError::WrongConsig(err.to_string()))?
+        .to_hex();
+
+    let rgb_burn = RgbBurn {
+        consig_id: consig_id.clone(),
+        iface,
+        consig: consig.clone(),
+        tx: bp_txid,
+        vout,
+        blinding,
+        amount: burned,
+    };
+    rgb_burns
+        .burns
+        .entry(contract_id)
+        .or_default()
+        .push(rgb_burn);
+
+    rgb_account.clone().update(&mut rgb_account_changes);
+    reconcile(&mut fork_wallet, rgb_account_changes.clone())
+        .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+
+    store_local_account(sk, fork_wallet.save())
+        .await
+        .map_err(TransferError::IO)?;
+
+    store_rgb_stock(sk, stock)
+        .await
+        .map_err(TransferError::IO)?;
+
+    store_burns(sk, rgb_burns)
+        .await
+        .map_err(TransferError::IO)?;
+
+    Ok(RgbBurnResponse {
+        psbt: psbt.to_string(),
+        commit: commit.to_hex(),
+        consig_id,
+        consig,
+        vout,
+        burned,
+    })
+}
+
+/// Returns the proof of a published burn: its consignment and anchor transaction
+pub async fn burn_proof(
+    sk: &str,
+    request: RgbBurnProofRequest,
+) -> Result<RgbBurnProof, TransferError> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    let RgbBurnProofRequest {
+        contract_id,
+        consig_id,
+    } = request;
+
+    let rgb_burns = retrieve_burns(sk).await.map_err(TransferError::IO)?;
+    let rgb_burn = rgb_burns
+        .burns
+        .get(&contract_id)
+        .and_then(|burns| burns.iter().find(|burn| burn.consig_id == consig_id))
+        .ok_or_else(|| TransferError::NoBurn(consig_id.clone()))?
+        .to_owned();
+
+    let mut resolver = ExplorerResolver::from_config().await;
+    let txid = Txid::from_str(&rgb_burn.tx.to_hex())
+        .map_err(|err| TransferError::WrongConsig(err.to_string()))?;
+    prefetch_resolver_txs(vec![txid], &mut resolver).await;
+    let tx = resolver
+        .resolve_tx(txid)
+        .map_err(|_| TransferError::Broadcast(format!("anchor transaction {txid} not found")))?;
+
+    Ok(RgbBurnProof {
+        contract_id,
+        iface: rgb_burn.iface,
+        consig: rgb_burn.consig,
+        tx: bitcoin::consensus::serialize(&tx).to_hex(),
+        vout: rgb_burn.vout,
+        blinding: rgb_burn.blinding.to_string(),
+        burned: rgb_burn.amount,
+    })
+}
+
+/// Verifies a burn proof against the blockchain. The stock of the user is not changed.
+pub async fn verify_burn_proof(
+    sk: &str,
+    proof: RgbBurnProof,
+) -> Result<RgbBurnVerifyResponse, TransferError> {
+    if let Err(err) = proof.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    let mut stock = retrieve_rgb_stock(sk).await.map_err(TransferError::IO)?;
+    let mut resolver = ExplorerResolver::from_config().await;
+
+    let RgbBurnProof {
+        contract_id,
+        iface,
+        consig,
+        tx,
+        vout,
+        blinding,
+        burned,
+    } = proof;
+
+    let tx: Transaction = Vec::<u8>::from_hex(&tx)
+        .ok()
+        .and_then(|tx| bitcoin::consensus::deserialize(&tx).ok())
+        .ok_or_else(|| TransferError::WrongConsig("invalid anchor transaction".to_string()))?;
+    let blinding = u64::from_str(&blinding)
+        .map_err(|_| TransferError::WrongConsig("invalid burn seal blinding".to_string()))?;
+
+    prefetch_resolver_rgb(&consig, &mut resolver, None).await;
+    let (burn_contract_id, proven) = verify_rgb_burn(
+        consig,
+        &iface,
+        &tx,
+        vout,
+        blinding,
+        &mut resolver,
+        &mut stock,
+    )
+    .map_err(TransferError::Accept)?;
+
+    let burn_contract_id = burn_contract_id.to_string();
+    Ok(RgbBurnVerifyResponse {
+        valid: burn_contract_id == contract_id && proven == burned,
+        contract_id: burn_contract_id,
+        txid: tx.txid().to_string(),
+        burned: proven,
+    })
+}
+
+/// Burns of the contract whose anchor transaction the explorer sees, in the mempool or in a
+/// block. Burns are stored before their PSBT is signed and published, so the others may never
+/// reach the chain.
+async fn seen_burns(
+    rgb_burns: &RgbBurns,
+    contract_id: &str,
+    resolver: &mut ExplorerResolver,
+) -> Vec<(RgbBurn, TxStatus)> {
+    let burns = rgb_burns
+        .burns
+        .get(contract_id)
+        .cloned()
+        .unwrap_or_default();
+    let txids: Vec<bitcoin::Txid> = burns
+        .iter()
+        .map(|burn| Txid::from_str(&burn.tx.to_hex()).expect("invalid tx id"))
+        .collect();
+    prefetch_resolver_txs_status(txids, resolver).await;
+
+    burns
+        .into_iter()
+        .filter_map(|burn| {
+            let txid = Txid::from_str(&burn.tx.to_hex()).expect("invalid tx id");
+            match resolver.txs_status.get(&txid) {
+                Some(status @ (TxStatus::Mempool | TxStatus::Block(_))) => {
+                    Some((burn, status.to_owned()))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+async fn burned_amount(
+    rgb_burns: &RgbBurns,
+    contract_id: &str,
+    resolver: &mut ExplorerResolver,
+) -> u64 {
+    seen_burns(rgb_burns, contract_id, resolver)
+        .await
+        .iter()
+        .map(|(burn, _)| burn.amount)
+        .sum()
+}
+
+fn airdrop_id(contract_id: &str, iface: &str, recipients: &[RgbAirdropRecipient]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(format!("{contract_id}:{iface}").as_bytes());
+    for recipient in recipients {
+        hasher.update(format!(";{}:{}", recipient.beneficiary, recipient.amount).as_bytes());
+    }
+    hasher.finalize().to_hex().to_lowercase()
+}
+
+/// Updates a chunk in the broadcast queue or in the mempool from the explorer
+async fn refresh_airdrop_chunk(chunk: &mut RgbAirdropChunk) -> Result<(), TransferError> {
+    if !matches!(
+        chunk.status,
+        RgbAirdropChunkStatus::Queued | RgbAirdropChunkStatus::Mempool
+    ) {
+        return Ok(());
+    }
+    let Some(txid) = chunk
+        .txid
+        .as_ref()
+        .and_then(|txid| Txid::from_str(txid).ok())
+    else {
+        return Ok(());
+    };
+
+    let status = get_blockchain()
+        .await
+        .get_tx_status(&txid)
+        .await
+        .map_err(|err| TransferError::Broadcast(err.to_string()))?;
+    if let Some(status) = status {
+        chunk.status = match status.block_height {
+            Some(height) if status.confirmed => RgbAirdropChunkStatus::Confirmed(height),
+            _ => RgbAirdropChunkStatus::Mempool,
+        };
+    }
+
+    Ok(())
+}
+
+/// Pays the recipients of an airdrop with as few anchor transactions as the unspent
+/// allocations of the wallet allow, at most `chunk_size` recipients each. Recipients the
+/// allocations cannot pay yet, e.g. while the change of the previous chunk is unconfirmed, are
+/// left pending, and a chunk is only paid once the explorer sees the anchor of the previous
+/// one. The progress of every chunk is stored, so calling it again with the same request
+/// resumes the airdrop: broadcast chunks are refreshed, signed chunks are queued again and
+/// pending or rejected ones are paid, no recipient is paid twice.
+pub async fn airdrop_asset(
+    sk: &str,
+    request: RgbAirdropRequest,
+) -> Result<RgbAirdropResponse, TransferError> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+    for recipient in &request.recipients {
+        if let Err(err) = recipient.validate(&RGBContext::default()) {
+            let errors = err
+                .flatten()
+                .into_iter()
+                .map(|(f, e)| (f, e.to_string()))
+                .collect();
+            return Err(TransferError::Validation(errors));
+        }
+    }
+
+    let RgbAirdropRequest {
+        contract_id,
+        iface,
+        recipients,
+        chunk_size,
+        descriptor,
+        change_terminal,
+        fee,
+        bitcoin_changes,
+        signers,
+    } = request;
+
+    let airdrop_id = airdrop_id(&contract_id, &iface, &recipients);
+    let mut rgb_airdrops = retrieve_airdrops(sk).await.map_err(TransferError::IO)?;
+    let mut airdrop = match rgb_airdrops.airdrops.get(&airdrop_id) {
+        Some(airdrop) => airdrop.to_owned(),
+        None => {
+            let network = NETWORK.read().await.to_string();
+            let mut invoices = vec![];
+            for recipient in recipients {
+                let invoice = beneficiary_invoice(
+                    &contract_id,
+                    &iface,
+                    recipient.amount,
+                    &recipient.beneficiary,
+                    &network,
+                )
+                .map_err(TransferError::Invoice)?;
+                invoices.push(invoice.to_string());
+            }
+
+            let chunk_size = chunk_size
+                .map(usize::from)
+                .unwrap_or(RGB_AIRDROP_CHUNK_SIZE)
+                .clamp(1, RGB_AIRDROP_CHUNK_SIZE);
+            let chunks = invoices
+                .chunks(chunk_size)
+                .map(|invoices| RgbAirdropChunk {
+                    invoices: invoices.to_vec(),
+                    status: RgbAirdropChunkStatus::Pending,
+                    txid: None,
+                    tx: None,
+                    consignments: vec![],
+                })
+                .collect();
+
+            RgbAirdrop {
+                contract_id: contract_id.clone(),
+                iface: iface.clone(),
+                chunks,
+            }
+        }
+    };
+
+    let mut wallets = vec![];
+    for signer in &signers {
+        let wallet = get_wallet(signer, None)
+            .await
+            .map_err(|err| TransferError::Broadcast(err.to_string()))?;
+        wallets.push(wallet);
+    }
+    let queue_wallet = wallets[0].clone();
+
+    let contract = ContractId::from_str(&contract_id).map_err(|_| {
+        let mut errors = BTreeMap::new();
+        errors.insert("contract_id".to_string(), "invalid contract id".to_string());
+        TransferError::Validation(errors)
+    })?;
+
+    let mut index = 0;
+    while index < airdrop.chunks.len() {
+        refresh_airdrop_chunk(&mut airdrop.chunks[index]).await?;
+
+        let unpaid = matches!(
+            airdrop.chunks[index].status,
+            RgbAirdropChunkStatus::Pending | RgbAirdropChunkStatus::Rejected
+        );
+        if unpaid
+            && index > 0
+            && !matches!(
+                airdrop.chunks[index - 1].status,
+                RgbAirdropChunkStatus::Mempool | RgbAirdropChunkStatus::Confirmed(_)
+            )
+        {
+            // The previous anchor is not seen by the explorer yet, resume later
+            break;
+        }
+
+        // The chunk is only stored as signed once the stock holding its change is stored.
+        // A crash before leaves the chunk unpaid, and it is paid again from the current
+        // allocations with a new anchor, the first one was never broadcast. A crash after
+        // leaves a signed chunk which is queued again.
+        if unpaid {
+            let (mut stock, mut rgb_transfers) = retrieve_stock_transfers(sk)
+                .await
+                .map_err(TransferError::IO)?;
+            let LocalRgbAccount {
+                doc,
+                mut rgb_account,
+            } = retrieve_local_account(sk)
+                .await
+                .map_err(TransferError::IO)?;
+            let mut fork_wallet = automerge::AutoCommit::load(&doc)
+                .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+            let mut rgb_account_changes = RawRgbAccount::from(rgb_account.clone());
+            let mut resolver = ExplorerResolver::from_config().await;
+
+            let mut rgb_wallet = match rgb_account.wallets.get(RGB_DEFAULT_NAME) {
+                Some(rgb_wallet) => rgb_wallet.to_owned(),
+                _ => return Err(TransferError::NoWatcher),
+            };
+
+            // The chunk pays as many invoices as the unspent allocations of the wallet allow,
+            // the others are left to the next chunk
+            let mut spendable = prebuild_spendable_amount(
+                contract,
+                &iface,
+                &mut stock,
+                &mut rgb_wallet,
+                &mut resolver,
+            )
+            .await?;
+            let chunk = &mut airdrop.chunks[index];
+            let mut payable = 0;
+            for rgb_invoice in &chunk.invoices {
+                let amount = invoice_amount(rgb_invoice)?;
+                if amount > spendable {
+                    break;
+                }
+                spendable -= amount;
+                payable += 1;
+            }
+
+            if payable == 0 {
+                if index == 0 {
+                    let mut errors = BTreeMap::new();
+                    errors.insert("recipients".to_string(), "insufficient state".to_string());
+                    return Err(TransferError::Validation(errors));
+                }
+                // The change of the previous chunks is not spendable yet, resume later
+                break;
+            }
+            if payable < chunk.invoices.len() {
+                let invoices = chunk.invoices.split_off(payable);
+                airdrop.chunks.insert(
+                    index + 1,
+                    RgbAirdropChunk {
+                        invoices,
+                        status: RgbAirdropChunkStatus::Pending,
+                        txid: None,
+                        tx: None,
+                        consignments: vec![],
+                    },
+                );
+            }
+
+            let chunk = &mut airdrop.chunks[index];
+            let batch = FullRgbBatchTransferRequest {
+                invoices: chunk
+                    .invoices
+                    .iter()
+                    .map(|rgb_invoice| RgbBatchInvoice {
+                        contract_id: contract_id.clone(),
+                        iface: iface.clone(),
+                        rgb_invoice: rgb_invoice.clone(),
+                    })
+                    .collect(),
+                descriptor: descriptor.clone(),
+                change_terminal: change_terminal.clone(),
+                fee: fee.clone(),
+                bitcoin_changes: bitcoin_changes.clone(),
+            };
+            let response = internal_batch_transfer_asset(
+                batch,
+                &mut stock,
+                &mut rgb_account,
+                &mut rgb_transfers,
+                &mut resolver,
+            )
+            .await?;
+
+            let psbt = Psbt::from_str(&response.psbt)
+                .map_err(|err| TransferError::Broadcast(err.to_string()))?;
+            let psbt = multi_sign_and_finalize_psbt(
+                wallets.clone(),
+                PartiallySignedTransaction::from(psbt),
+            )
+            .await
+            .map_err(|err| TransferError::Broadcast(err.to_string()))?;
+            let tx = psbt.extract_tx();
+
+            chunk.txid = Some(tx.txid().to_string());
+            chunk.tx = Some(bitcoin::consensus::serialize(&tx).to_hex());
+            chunk.consignments = response.consignments;
+            chunk.status = RgbAirdropChunkStatus::Signed;
+
+            rgb_account.clone().update(&mut rgb_account_changes);
+            reconcile(&mut fork_wallet, rgb_account_changes.clone())
+                .map_err(|op| TransferError::WrongAutoMerge(op.to_string()))?;
+            store_local_account(sk, fork_wallet.save())
+                .await
+                .map_err(TransferError::IO)?;
+            store_stock_transfers(sk, stock, rgb_transfers)
+                .await
+                .map_err(TransferError::IO)?;
+
+            rgb_airdrops
+                .airdrops
+                .insert(airdrop_id.clone(), airdrop.clone());
+            store_airdrops(sk, rgb_airdrops.clone())
+                .await
+                .map_err(TransferError::IO)?;
+        }
+
+        let chunk = &mut airdrop.chunks[index];
+        if chunk.status == RgbAirdropChunkStatus::Signed {
+            let tx: Transaction = chunk
+                .tx
+                .as_ref()
+                .and_then(|tx| Vec::<u8>::from_hex(tx).ok())
+                .and_then(|tx| bitcoin::consensus::deserialize(&tx).ok())
+                .ok_or_else(|| TransferError::Broadcast(format!("invalid chunk {index}")))?;
+            let queued = queue_transaction(sk, &queue_wallet, tx)
+                .await
+                .map_err(|err| TransferError::Broadcast(err.to_string()))?;
+
+            let rejected = match queued.status {
+                BroadcastStatus::Pending => {
+                    chunk.status = RgbAirdropChunkStatus::Queued;
+                    None
+                }
+                BroadcastStatus::Mempool => {
+                    chunk.status = RgbAirdropChunkStatus::Mempool;
+                    None
+                }
+                BroadcastStatus::Confirmed(height) => {
+                    chunk.status = RgbAirdropChunkStatus::Confirmed(height);
+                    None
+                }
+                BroadcastStatus::Failed
+                | BroadcastStatus::Rejected
+                | BroadcastStatus::Replaced(_) => {
+                    chunk.status = RgbAirdropChunkStatus::Rejected;
+                    Some(queued.last_error.unwrap_or_default())
+                }
+            };
+            rgb_airdrops
+                .airdrops
+                .insert(airdrop_id.clone(), airdrop.clone());
+            store_airdrops(sk, rgb_airdrops.clone())
+                .await
+                .map_err(TransferError::IO)?;
+
+            if let Some(reason) = rejected {
+                return Err(TransferError::Broadcast(format!(
+                    "anchor {} of airdrop chunk {index} rejected: {reason}",
+                    queued.txid
+                )));
+            }
+        }
+
+        index += 1;
+    }
+
+    // Keeps the statuses refreshed from the explorer
+    rgb_airdrops
+        .airdrops
+        .insert(airdrop_id.clone(), airdrop.clone());
+    store_airdrops(sk, rgb_airdrops)
+        .await
+        .map_err(TransferError::IO)?;
+
+    Ok(RgbAirdropResponse {
+        airdrop_id,
+        chunks: airdrop.chunks,
+    })
+}
+
 pub async fn transfer_asset(
     sk: &str,
     request: RgbTransferRequest,
@@ -873,6 +1773,8 @@ pub enum RgbSwapError {
     Transfer(TransferError),
     /// Swap fee cannot be decoded. {0}
     WrongSwapFee(String),
+    /// Bitcoin fee cannot be resolved. {0}
+    WrongFee(String),
     /// Bitcoin network cannot be decoded. {0}
     WrongNetwork(String),
     /// Bitcoin address cannot be decoded. {0}
@@ -902,10 +1804,7 @@ pub async fn create_seller_offer(
     let network =
         Network::from_str(&network).map_err(|op| RgbSwapError::WrongNetwork(op.to_string()))?;
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) =
         retrieve_stock_account(sk).await.map_err(RgbSwapError::IO)?;
@@ -952,7 +1851,7 @@ pub async fn create_seller_offer(
     .map_err(RgbSwapError::Create)?;
 
     let iface_index = match iface.to_uppercase().as_str() {
-        "RGB20" => AssetType::RGB20,
+        "RGB20" | "RGB25" => AssetType::RGB20,
         "RGB21" => AssetType::RGB21,
         _ => AssetType::Contract,
     } as u32;
@@ -1019,10 +1918,7 @@ pub async fn create_buyer_bid(
         return Err(RgbSwapError::Validation(errors));
     }
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) =
         retrieve_stock_account(sk).await.map_err(RgbSwapError::IO)?;
@@ -1201,7 +2097,7 @@ pub async fn create_swap_transfer(
             .map_err(RgbSwapError::Swap)?;
 
     let change_terminal = match iface.to_uppercase().as_str() {
-        "RGB20" => "/20/1",
+        "RGB20" | "RGB25" => "/20/1",
         "RGB21" => "/21/1",
         _ => "/10/1",
     };
@@ -1265,10 +2161,7 @@ pub async fn accept_transfer(
         return Err(TransferError::Validation(errors));
     }
     let mut stock = retrieve_rgb_stock(sk).await.map_err(TransferError::IO)?;
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let AcceptRequest { consignment, force } = request;
     prefetch_resolver_rgb(&consignment, &mut resolver, None).await;
@@ -1497,10 +2390,7 @@ pub async fn internal_verify_transfers(
     stock: &mut Stock,
     rgb_transfers: RgbTransfers,
 ) -> Result<(RgbTransfers, Vec<BatchRgbTransferItem>), TransferError> {
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let mut transfers = vec![];
     let mut rgb_pending = RgbTransfers::default();
@@ -1576,10 +2466,7 @@ pub async fn internal_verify_transfers(
 }
 
 pub async fn get_contract(sk: &str, contract_id: &str) -> Result<ContractResponse> {
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) = retrieve_stock_account(sk).await?;
 
@@ -1588,13 +2475,18 @@ pub async fn get_contract(sk: &str, contract_id: &str) -> Result<ContractRespons
     let mut wallet = match wallet {
         Some(wallet) => {
             let mut fetch_wallet = wallet.to_owned();
-            for contract_type in [AssetType::RGB20, AssetType::RGB21] {
-                let contract_index = contract_type.clone() as u32;
+            for contract_type in [AssetType::RGB20, AssetType::RGB21, AssetType::RGB25] {
                 let iface_name = contract_type.to_string().to_uppercase().clone();
-
-                let iface = stock
-                    .iface_by_name(&tn!(iface_name.clone()))
-                    .map_err(|_| TransferError::NoIface)?;
+                // RGB25 assets share the fungible keychain
+                let contract_index = match contract_type {
+                    AssetType::RGB25 => AssetType::RGB20,
+                    asset_type => asset_type,
+                } as u32;
+
+                // Stocks created before RGB25 was supported do not have its interface
+                let Ok(iface) = stock.iface_by_name(&tn!(iface_name.clone())) else {
+                    continue;
+                };
 
                 if let Ok(contract_iface) = stock.contract_iface(contract_id, iface.iface_id()) {
                     sync_wallet(contract_index, &mut fetch_wallet, &mut resolver);
@@ -1614,7 +2506,9 @@ pub async fn get_contract(sk: &str, contract_id: &str) -> Result<ContractRespons
         _ => None,
     };
 
-    let contract = export_contract(contract_id, &mut stock, &mut resolver, &mut wallet)?;
+    let mut contract = export_contract(contract_id, &mut stock, &mut resolver, &mut wallet)?;
+    let rgb_burns = retrieve_burns(sk).await?;
+    contract.burned = burned_amount(&rgb_burns, &contract.contract_id, &mut resolver).await;
     if let Some(wallet) = wallet {
         rgb_account
             .wallets
@@ -1626,10 +2520,7 @@ pub async fn get_contract(sk: &str, contract_id: &str) -> Result<ContractRespons
 }
 
 pub async fn list_contracts(sk: &str) -> Result<ContractsResponse> {
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) = retrieve_stock_account(sk).await?;
 
@@ -1653,14 +2544,16 @@ pub async fn list_contracts(sk: &str) -> Result<ContractsResponse> {
         _ => None,
     };
 
+    let rgb_burns = retrieve_burns(sk).await?;
     let mut contracts = vec![];
-    for contract_type in [AssetType::RGB20, AssetType::RGB21] {
+    for contract_type in [AssetType::RGB20, AssetType::RGB21, AssetType::RGB25] {
         let iface_name = contract_type.to_string().to_uppercase().clone();
         let iface_name = tn!(iface_name);
-        let iface = stock
-            .iface_by_name(&iface_name)
-            .expect("Iface name not found")
-            .clone();
+        // Stocks created before RGB25 was supported do not have its interface
+        let Ok(iface) = stock.iface_by_name(&iface_name) else {
+            continue;
+        };
+        let iface = iface.clone();
 
         let contract_ids = stock
             .contract_ids_by_iface(&iface_name)
@@ -1673,7 +2566,8 @@ pub async fn list_contracts(sk: &str) -> Result<ContractsResponse> {
                 .expect("Iface not found");
 
             prefetch_resolver_allocations(contract_iface, &mut resolver).await;
-            let resp = export_contract(contract_id, &mut stock, &mut resolver, &mut wallet)?;
+            let mut resp = export_contract(contract_id, &mut stock, &mut resolver, &mut wallet)?;
+            resp.burned = burned_amount(&rgb_burns, &resp.contract_id, &mut resolver).await;
             contracts.push(resp);
         }
     }
@@ -1731,11 +2625,11 @@ pub async fn list_schemas(sk: &str) -> Result<SchemasResponse> {
 
 pub async fn list_transfers(sk: &str, contract_id: String) -> Result<RgbTransfersResponse> {
     let rgb_transfers = retrieve_transfers(sk).await?;
+    let rgb_burns = retrieve_burns(sk).await?;
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
+
+    let burn_activities = seen_burns(&rgb_burns, &contract_id, &mut resolver).await;
 
     let mut transfers = vec![];
     if let Some(transfer_activities) = rgb_transfers.transfers.get(&contract_id) {
@@ -1770,6 +2664,14 @@ pub async fn list_transfers(sk: &str, contract_id: String) -> Result<RgbTransfer
         }
     }
 
+    for (burn, status) in burn_activities {
+        transfers.push(RgbTransferDetail {
+            consig_id: burn.consig_id,
+            status,
+            ty: TransferType::Burned,
+        });
+    }
+
     Ok(RgbTransfersResponse { transfers })
 }
 
@@ -1857,10 +2759,7 @@ pub enum ImportError {
 }
 
 pub async fn import(sk: &str, request: ImportRequest) -> Result<ContractResponse, ImportError> {
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let (mut stock, mut rgb_account) = retrieve_stock_account(sk).await.map_err(ImportError::IO)?;
 
@@ -1871,8 +2770,13 @@ pub async fn import(sk: &str, request: ImportRequest) -> Result<ContractResponse
     let mut wallet = match wallet {
         Some(wallet) => {
             let mut fetch_wallet = wallet.to_owned();
+            // RGB25 assets share the fungible keychain
+            let contract_index = match import {
+                AssetType::RGB25 => AssetType::RGB20,
+                ref asset_type => asset_type.clone(),
+            } as u32;
             prefetch_resolver_utxos(
-                import.clone() as u32,
+                contract_index,
                 &mut fetch_wallet,
                 &mut resolver,
                 Some(RGB_DEFAULT_FETCH_LIMIT),
@@ -1997,10 +2901,7 @@ pub async fn watcher_details(sk: &str, name: &str) -> Result<WatcherDetailRespon
         _ => return Err(WatcherError::NoWatcher),
     };
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     let mut allocations = vec![];
     for contract_type in [AssetType::RGB20, AssetType::RGB21] {
@@ -2031,6 +2932,103 @@ pub async fn watcher_details(sk: &str, name: &str) -> Result<WatcherDetailRespon
     Ok(resp)
 }
 
+/// Outpoints of the wallet that hold unspent RGB allocations
+pub async fn list_rgb_utxos(sk: &str) -> Result<BTreeSet<String>, WatcherError> {
+    let details = match watcher_details(sk, RGB_DEFAULT_NAME).await {
+        Ok(details) => details,
+        Err(WatcherError::NoWatcher) => return Ok(BTreeSet::new()),
+        Err(err) => return Err(err),
+    };
+
+    let utxos = details
+        .contracts
+        .into_iter()
+        .flat_map(|contract| contract.allocations)
+        .filter(|allocation| allocation.is_mine && !allocation.is_spent)
+        .map(|allocation| allocation.utxo)
+        .collect();
+
+    Ok(utxos)
+}
+
+/// Scans the terminals of the watcher, registering the outputs received until `gap_limit`
+/// consecutive addresses of each terminal are unused
+pub async fn watcher_discovery(
+    sk: &str,
+    name: &str,
+    gap_limit: u32,
+) -> Result<Vec<DiscoveredKeychain>, WatcherError> {
+    let mut rgb_account = retrieve_account(sk).await.map_err(WatcherError::IO)?;
+    let mut wallet = match rgb_account.wallets.get(name) {
+        Some(wallet) => wallet.to_owned(),
+        _ => return Err(WatcherError::NoWatcher),
+    };
+
+    let mut resolver = ExplorerResolver::from_config().await;
+
+    let mut discovered = vec![];
+    for asset_type in [AssetType::Contract, AssetType::RGB20, AssetType::RGB21] {
+        let iface_index = asset_type.clone() as u32;
+        let mut used = BTreeSet::new();
+        let mut received_outputs = 0;
+        let mut scanned = 0;
+        let mut limit = gap_limit;
+        while scanned < limit {
+            prefetch_resolver_utxos(iface_index, &mut wallet, &mut resolver, Some(limit)).await;
+            let utxos = discover_utxos(iface_index, scanned..limit, &mut wallet, &mut resolver)
+                .map_err(|op| WatcherError::Validation(op.to_string()))?;
+            for utxo in utxos {
+                used.insert(utxo.derivation.terminal.index);
+                received_outputs += 1;
+            }
+
+            scanned = limit;
+            if let Some(last_used) = used.iter().max() {
+                limit = last_used + 1 + gap_limit;
+            }
+        }
+
+        discovered.push(DiscoveredKeychain {
+            asset_type,
+            scanned_addresses: scanned,
+            used_addresses: used.len() as u32,
+            last_used_index: used.iter().max().copied(),
+            received_outputs,
+        });
+    }
+
+    rgb_account.wallets.insert(name.to_string(), wallet);
+    store_account(sk, rgb_account)
+        .await
+        .map_err(WatcherError::IO)?;
+
+    Ok(discovered)
+}
+
+/// Index following the last address of the terminal holding a UTXO known by the watcher,
+/// 0 when the terminal has none. Unlike the BDK address index, it is stored with the account.
+pub async fn watcher_next_index(
+    sk: &str,
+    name: &str,
+    iface_index: u32,
+) -> Result<u32, WatcherError> {
+    let rgb_account = retrieve_account(sk).await.map_err(WatcherError::IO)?;
+    let wallet = match rgb_account.wallets.get(name) {
+        Some(wallet) => wallet,
+        _ => return Err(WatcherError::NoWatcher),
+    };
+
+    let next_index = wallet
+        .utxos
+        .iter()
+        .filter(|utxo| utxo.derivation.terminal.app == iface_index)
+        .map(|utxo| utxo.derivation.terminal.index + 1)
+        .max()
+        .unwrap_or_default();
+
+    Ok(next_index)
+}
+
 pub async fn watcher_address(
     sk: &str,
     name: &str,
@@ -2041,10 +3039,7 @@ pub async fn watcher_address(
     let mut resp = WatcherUtxoResponse::default();
     if let Some(wallet) = rgb_account.wallets.get(name) {
         // Prefetch
-        let mut resolver = ExplorerResolver {
-            explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-            ..Default::default()
-        };
+        let mut resolver = ExplorerResolver::from_config().await;
 
         let asset_indexes: Vec<u32> = [0, 1, 9, 10, 20, 21].to_vec();
         let mut wallet = wallet.to_owned();
@@ -2081,10 +3076,7 @@ pub async fn watcher_utxo(
         let network =
             Network::from_str(&network).map_err(|op| WatcherError::Validation(op.to_string()))?;
 
-        let mut resolver = ExplorerResolver {
-            explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-            ..Default::default()
-        };
+        let mut resolver = ExplorerResolver::from_config().await;
 
         let network = AddressNetwork::from(network);
         let asset_indexes: Vec<u32> = [0, 1, 9, 10, 20, 21].to_vec();
@@ -2126,7 +3118,7 @@ pub async fn watcher_next_address(
     };
 
     let iface_index = match iface {
-        "RGB20" => 20,
+        "RGB20" | "RGB25" => 20,
         "RGB21" => 21,
         _ => 10,
     };
@@ -2148,7 +3140,7 @@ pub async fn watcher_next_utxo(
 ) -> Result<NextUtxoResponse, WatcherError> {
     let mut rgb_account = retrieve_account(sk).await.map_err(WatcherError::IO)?;
     let iface_index = match iface {
-        "RGB20" => 20,
+        "RGB20" | "RGB25" => 20,
         "RGB21" => 21,
         _ => 10,
     };
@@ -2158,10 +3150,7 @@ pub async fn watcher_next_utxo(
         _ => return Err(WatcherError::NoWatcher),
     };
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     prefetch_resolver_utxos(
         iface_index,
@@ -2207,15 +3196,12 @@ pub async fn watcher_unspent_utxos(
     };
 
     let iface_index = match iface {
-        "RGB20" => 20,
+        "RGB20" | "RGB25" => 20,
         "RGB21" => 21,
         _ => 10,
     };
 
-    let mut resolver = ExplorerResolver {
-        explorer_url: BITCOIN_EXPLORER_API.read().await.to_string(),
-        ..Default::default()
-    };
+    let mut resolver = ExplorerResolver::from_config().await;
 
     sync_wallet(iface_index, &mut wallet, &mut resolver);
     prefetch_resolver_utxos(
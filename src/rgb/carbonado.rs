@@ -9,7 +9,7 @@ use crate::carbonado::server_store;
 use crate::rgb::crdt::{LocalRgbAccount, LocalRgbOffers, RawRgbAccount};
 
 use crate::rgb::{
-    structs::RgbTransfers,
+    structs::{RgbAirdrops, RgbBurns, RgbTransfers},
     swap::{RgbBids, RgbOffers},
 };
 use crate::{
@@ -120,6 +120,48 @@ pub async fn store_transfers(
     .map_err(|op| StorageError::CarbonadoWrite(name.to_string(), op.to_string()))
 }
 
+pub async fn store_airdrops(
+    sk: &str,
+    name: &str,
+    rgb_airdrops: &RgbAirdrops,
+) -> Result<(), StorageError> {
+    let data = to_allocvec(rgb_airdrops)
+        .map_err(|op| StorageError::StrictWrite(name.to_string(), op.to_string()))?;
+
+    let hashed_name = blake3::hash(format!("{LIB_ID_RGB}-{name}").as_bytes())
+        .to_hex()
+        .to_lowercase();
+
+    store(
+        sk,
+        &format!("{hashed_name}.c15"),
+        &data,
+        true,
+        Some(RGB_STRICT_TYPE_VERSION.to_vec()),
+    )
+    .await
+    .map_err(|op| StorageError::CarbonadoWrite(name.to_string(), op.to_string()))
+}
+
+pub async fn store_burns(sk: &str, name: &str, rgb_burns: &RgbBurns) -> Result<(), StorageError> {
+    let data = to_allocvec(rgb_burns)
+        .map_err(|op| StorageError::StrictWrite(name.to_string(), op.to_string()))?;
+
+    let hashed_name = blake3::hash(format!("{LIB_ID_RGB}-{name}").as_bytes())
+        .to_hex()
+        .to_lowercase();
+
+    store(
+        sk,
+        &format!("{hashed_name}.c15"),
+        &data,
+        true,
+        Some(RGB_STRICT_TYPE_VERSION.to_vec()),
+    )
+    .await
+    .map_err(|op| StorageError::CarbonadoWrite(name.to_string(), op.to_string()))
+}
+
 pub async fn store_offers(
     sk: &str,
     name: &str,
@@ -219,6 +261,42 @@ pub async fn retrieve_transfers(sk: &str, name: &str) -> Result<RgbTransfers, St
     }
 }
 
+pub async fn retrieve_airdrops(sk: &str, name: &str) -> Result<RgbAirdrops, StorageError> {
+    let hashed_name = blake3::hash(format!("{LIB_ID_RGB}-{name}").as_bytes())
+        .to_hex()
+        .to_lowercase();
+
+    let (data, _) = retrieve(sk, &format!("{hashed_name}.c15"), vec![])
+        .await
+        .map_err(|op| StorageError::CarbonadoRetrieve(name.to_string(), op.to_string()))?;
+
+    if data.is_empty() {
+        Ok(RgbAirdrops::default())
+    } else {
+        let rgb_airdrops = from_bytes(&data)
+            .map_err(|op| StorageError::StrictRetrieve(name.to_string(), op.to_string()))?;
+        Ok(rgb_airdrops)
+    }
+}
+
+pub async fn retrieve_burns(sk: &str, name: &str) -> Result<RgbBurns, StorageError> {
+    let hashed_name = blake3::hash(format!("{LIB_ID_RGB}-{name}").as_bytes())
+        .to_hex()
+        .to_lowercase();
+
+    let (data, _) = retrieve(sk, &format!("{hashed_name}.c15"), vec![])
+        .await
+        .map_err(|op| StorageError::CarbonadoRetrieve(name.to_string(), op.to_string()))?;
+
+    if data.is_empty() {
+        Ok(RgbBurns::default())
+    } else {
+        let rgb_burns = from_bytes(&data)
+            .map_err(|op| StorageError::StrictRetrieve(name.to_string(), op.to_string()))?;
+        Ok(rgb_burns)
+    }
+}
+
 pub async fn retrieve_offers(sk: &str, name: &str) -> Result<RgbOffers, StorageError> {
     let hashed_name = blake3::hash(format!("{LIB_ID_RGB}-{name}").as_bytes())
         .to_hex()
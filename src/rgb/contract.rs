@@ -1,11 +1,14 @@
-use amplify::{confinement::U32, hex::ToHex};
+use amplify::{confinement::U32, hex::ToHex, Wrapper};
 use bech32::{encode, ToBase32};
 use rgb::{Resolver, RgbWallet};
 use rgbstd::{
     contract::ContractId,
     interface::{rgb21::TokenData, IfaceId, IfacePair},
     persistence::{Inventory, InventoryInconsistency, StashInconsistency, Stock},
-    stl::{ContractData, DivisibleAssetSpec, RicardianContract, Timestamp},
+    stl::{
+        Amount, ContractData, Details, DivisibleAssetSpec, Name, Precision, RicardianContract,
+        Timestamp,
+    },
 };
 use strict_encoding::{FieldName, StrictDeserialize, StrictSerialize};
 
@@ -14,7 +17,7 @@ use crate::structs::{
     GenesisFormats, MediaInfo, UDADetail,
 };
 use crate::{
-    rgb::{resolvers::ResolveSpent, wallet::contract_allocations},
+    rgb::{issue::OS_INFLATION, resolvers::ResolveSpent, wallet::contract_allocations},
     structs::AttachInfo,
 };
 
@@ -123,15 +126,55 @@ where
         .contract_iface(contract_id, iface_id.to_owned())
         .expect("invalid contracts state");
 
-    let ty: FieldName = FieldName::from("spec");
-    let specs = match contract_iface.global(ty) {
-        Ok(values) => DivisibleAssetSpec::from_strict_val_unchecked(&values[0]),
-        Err(err) => {
-            return Err(ExportContractError::StrictInconsistency(
-                contr_id,
-                err.to_string(),
-            ))
-        }
+    // RGB25/CFA contracts have no spec, only name, details and precision
+    let (ticker, name, details, precision) = if iface.name.as_str() == "RGB25" {
+        let ty: FieldName = FieldName::from("name");
+        let name = match contract_iface.global(ty) {
+            Ok(values) => Name::from_strict_val_unchecked(&values[0]),
+            Err(err) => {
+                return Err(ExportContractError::StrictInconsistency(
+                    contr_id,
+                    err.to_string(),
+                ))
+            }
+        };
+
+        let ty: FieldName = FieldName::from("precision");
+        let precision = match contract_iface.global(ty) {
+            Ok(values) => Precision::from_strict_val_unchecked(&values[0]),
+            Err(err) => {
+                return Err(ExportContractError::StrictInconsistency(
+                    contr_id,
+                    err.to_string(),
+                ))
+            }
+        };
+
+        let mut details = String::new();
+        let ty: FieldName = FieldName::from("details");
+        if let Ok(values) = contract_iface.global(ty) {
+            details = Details::from_strict_val_unchecked(&values[0]).to_string();
+        };
+
+        (String::new(), name.to_string(), details, precision as u8)
+    } else {
+        let ty: FieldName = FieldName::from("spec");
+        let specs = match contract_iface.global(ty) {
+            Ok(values) => DivisibleAssetSpec::from_strict_val_unchecked(&values[0]),
+            Err(err) => {
+                return Err(ExportContractError::StrictInconsistency(
+                    contr_id,
+                    err.to_string(),
+                ))
+            }
+        };
+
+        (
+            specs.ticker().to_string(),
+            specs.name().to_string(),
+            specs.details().unwrap_or_default().to_string(),
+            0,
+        )
     };
 
     let ty: FieldName = FieldName::from("created");
@@ -159,7 +202,7 @@ where
     };
 
     let iface_index = match iface.name.as_str() {
-        "RGB20" => 20,
+        "RGB20" | "RGB25" => 20,
         "RGB21" => 21,
         _ => 9,
     };
@@ -183,7 +226,13 @@ where
     }
 
     let mut supply = 0;
-    for (index, (_, global_assign)) in contract_bindle.genesis.assignments.iter().enumerate() {
+    for (index, (_, global_assign)) in contract_bindle
+        .genesis
+        .assignments
+        .iter()
+        .filter(|(ty, _)| **ty != OS_INFLATION)
+        .enumerate()
+    {
         let idx = index as u16;
         if global_assign.is_fungible() {
             if let Ok(Some(reveal)) = global_assign.as_fungible_state_at(idx) {
@@ -195,6 +244,24 @@ where
         }
     }
 
+    // Inflatable assets add one issued supply by issuance
+    let mut issued_supply = supply;
+    let ty: FieldName = FieldName::from("issuedSupply");
+    if let Ok(values) = contract_iface.global(ty) {
+        issued_supply = values
+            .iter()
+            .map(|value| Amount::from_strict_val_unchecked(value).into_inner())
+            .sum();
+    }
+
+    let mut max_supply = issued_supply;
+    if let Ok(allowances) = contract_iface.fungible(FieldName::from("inflationAllowance"), &None) {
+        max_supply += allowances
+            .iter()
+            .map(|allowance| allowance.value)
+            .sum::<u64>();
+    }
+
     // Only RGB21/UDA
     let mut meta = none!();
     let ty: FieldName = FieldName::from("tokens");
@@ -253,9 +320,9 @@ where
                         .to_string()
                         .parse()
                         .expect("invalid token_index"),
-                    ticker: specs.ticker().into(),
-                    name: specs.name().into(),
-                    description: specs.details().unwrap_or_default().into(),
+                    ticker: ticker.clone(),
+                    name: name.clone(),
+                    description: details.clone(),
                     balance,
                     media: vec![media],
                     attach,
@@ -334,11 +401,14 @@ where
         contract_id: contr_id,
         iimpl_id,
         iface: iface.name.to_string(),
-        ticker: specs.ticker().into(),
-        name: specs.name().into(),
+        ticker,
+        name,
         description,
-        precision: 0,
+        precision,
         supply,
+        issued_supply,
+        max_supply,
+        burned: 0,
         balance,
         allocations,
         created: created.into(),
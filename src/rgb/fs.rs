@@ -4,22 +4,25 @@ use super::carbonado::store_swap_offer_bid;
 use super::crdt::{LocalRgbOfferBid, LocalRgbOffers};
 use super::swap::{RgbBids, RgbOffers};
 use crate::constants::storage_keys::{
-    ASSETS_BIDS, ASSETS_OFFERS, ASSETS_STOCK, ASSETS_TRANSFERS, ASSETS_WALLETS, MARKETPLACE_OFFERS,
+    ASSETS_AIRDROPS, ASSETS_BIDS, ASSETS_BURNS, ASSETS_OFFERS, ASSETS_STOCK, ASSETS_TRANSFERS,
+    ASSETS_WALLETS, MARKETPLACE_OFFERS,
 };
 
 use crate::rgb::{
     carbonado::{
-        retrieve_bids as retrieve_rgb_bids, retrieve_fork_wallets,
+        retrieve_airdrops as retrieve_rgb_airdrops, retrieve_bids as retrieve_rgb_bids,
+        retrieve_burns as retrieve_rgb_burns, retrieve_fork_wallets,
         retrieve_offers as retrieve_rgb_offers,
         retrieve_public_offers as retrieve_rgb_public_offers, retrieve_stock as retrieve_rgb_stock,
         retrieve_swap_offer_bid as retrieve_rgb_swap_offer_bid,
         retrieve_transfers as retrieve_rgb_transfers, retrieve_wallets,
-        store_bids as store_rgb_bids, store_fork_wallets, store_offers as store_rgb_offers,
+        store_airdrops as store_rgb_airdrops, store_bids as store_rgb_bids,
+        store_burns as store_rgb_burns, store_fork_wallets, store_offers as store_rgb_offers,
         store_public_offers as store_rgb_public_offers, store_stock as store_rgb_stock,
         store_transfers as store_rgb_transfer, store_wallets,
     },
     crdt::LocalRgbAccount,
-    structs::{RgbAccount, RgbTransfers},
+    structs::{RgbAccount, RgbAirdrops, RgbBurns, RgbTransfers},
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Display, From, Error)]
@@ -41,6 +44,10 @@ pub enum RgbPersistenceError {
     RetrieveSwapBids(String),
     // Retrieve Public Offers Error. {0}
     RetrievePublicOffers(String),
+    // Retrieve Airdrops Error. {0}
+    RetrieveRgbAirdrops(String),
+    // Retrieve Burns Error. {0}
+    RetrieveRgbBurns(String),
     // Store Stock Error. {0}
     WriteStock(String),
     // Store RgbAccount Error. {0}
@@ -57,6 +64,10 @@ pub enum RgbPersistenceError {
     WriteRgbPublicOffers(String),
     // Store Swap Bid Error. {0}
     WriteSwapBids(String),
+    // Store Airdrops Error. {0}
+    WriteRgbAirdrops(String),
+    // Store Burns Error. {0}
+    WriteRgbBurns(String),
 }
 
 pub async fn retrieve_stock(sk: &str) -> Result<Stock, RgbPersistenceError> {
@@ -75,6 +86,22 @@ pub async fn retrieve_transfers(sk: &str) -> Result<RgbTransfers, RgbPersistence
     Ok(rgb_account)
 }
 
+pub async fn retrieve_airdrops(sk: &str) -> Result<RgbAirdrops, RgbPersistenceError> {
+    let rgb_airdrops = retrieve_rgb_airdrops(sk, ASSETS_AIRDROPS)
+        .await
+        .map_err(|op| RgbPersistenceError::RetrieveRgbAirdrops(op.to_string()))?;
+
+    Ok(rgb_airdrops)
+}
+
+pub async fn retrieve_burns(sk: &str) -> Result<RgbBurns, RgbPersistenceError> {
+    let rgb_burns = retrieve_rgb_burns(sk, ASSETS_BURNS)
+        .await
+        .map_err(|op| RgbPersistenceError::RetrieveRgbBurns(op.to_string()))?;
+
+    Ok(rgb_burns)
+}
+
 pub async fn retrieve_account(sk: &str) -> Result<RgbAccount, RgbPersistenceError> {
     let rgb_account = retrieve_wallets(sk, ASSETS_WALLETS)
         .await
@@ -159,6 +186,18 @@ pub async fn store_transfers(sk: &str, transfers: RgbTransfers) -> Result<(), Rg
         .map_err(|op| RgbPersistenceError::WriteRgbTransfers(op.to_string()))
 }
 
+pub async fn store_airdrops(sk: &str, airdrops: RgbAirdrops) -> Result<(), RgbPersistenceError> {
+    store_rgb_airdrops(sk, ASSETS_AIRDROPS, &airdrops)
+        .await
+        .map_err(|op| RgbPersistenceError::WriteRgbAirdrops(op.to_string()))
+}
+
+pub async fn store_burns(sk: &str, burns: RgbBurns) -> Result<(), RgbPersistenceError> {
+    store_rgb_burns(sk, ASSETS_BURNS, &burns)
+        .await
+        .map_err(|op| RgbPersistenceError::WriteRgbBurns(op.to_string()))
+}
+
 pub async fn store_account(sk: &str, account: RgbAccount) -> Result<(), RgbPersistenceError> {
     store_wallets(sk, ASSETS_WALLETS, &account)
         .await
@@ -5,18 +5,21 @@ use amplify::{
     hex::FromHex,
 };
 use bech32::{decode, FromBase32};
-use rgb_schemata::{nia_rgb20, nia_schema, uda_rgb21, uda_schema};
+use rgb_schemata::{cfa_rgb25, cfa_schema, nia_rgb20, nia_schema, uda_rgb21, uda_schema};
 use rgbstd::{
     containers::{Bindle, Contract},
     contract::Genesis,
-    interface::{rgb20, rgb21, IfacePair},
+    interface::{rgb20, rgb21, rgb25, IfacePair},
     persistence::{Inventory, Stash, Stock},
     resolvers::ResolveHeight,
     validation::ResolveTx,
 };
 use strict_encoding::StrictDeserialize;
 
-use crate::structs::AssetType;
+use crate::{
+    rgb::issue::{ifa_rgb20, ifa_schema},
+    structs::AssetType,
+};
 
 #[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
 #[display(doc_comments)]
@@ -96,8 +99,13 @@ pub fn contract_from_genesis(
     stock: Option<&mut Stock>,
 ) -> Contract {
     let (schema, iface, iimpl) = match asset_type {
+        // Inflatable assets share the interface, only the schema tells them apart
+        AssetType::RGB20 if genesis.schema_id == ifa_schema().schema_id() => {
+            (ifa_schema(), rgb20(), ifa_rgb20())
+        }
         AssetType::RGB20 => (nia_schema(), rgb20(), nia_rgb20()),
         AssetType::RGB21 => (uda_schema(), rgb21(), uda_rgb21()),
+        AssetType::RGB25 => (cfa_schema(), rgb25(), cfa_rgb25()),
         _ => (nia_schema(), rgb20(), nia_rgb20()),
     };
 
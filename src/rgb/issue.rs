@@ -1,26 +1,38 @@
-use amplify::{confinement::SmallBlob, Wrapper};
+use amplify::{confinement::SmallBlob, tiny_bmap, Wrapper};
 use bp::{seals::txout::ExplicitSeal, Chain, Txid};
-use rgb_schemata::{nia_rgb20, nia_schema, uda_rgb21, uda_schema};
+use rgb_schemata::{cfa_rgb25, cfa_schema, nia_rgb20, nia_schema, uda_rgb21, uda_schema};
 use rgbstd::{
     containers::Contract,
     contract::GenesisSeal,
     interface::{
         rgb20, rgb21,
         rgb21::{Allocation, EmbeddedMedia, OwnedFraction, TokenData, TokenIndex},
-        BuilderError, ContractBuilder,
+        rgb25, BuilderError, ContractBuilder, IfaceImpl, NamedField, NamedType,
     },
     persistence::{Inventory, Stash, Stock},
     resolvers::ResolveHeight,
+    schema::{
+        AssignmentType, FungibleType, GlobalStateSchema, Occurrences, StateSchema, SubSchema,
+        TransitionType,
+    },
     stl::{
-        Amount, Attachment, ContractData, DivisibleAssetSpec, MediaType, Name, Precision,
+        Amount, Attachment, ContractData, Details, DivisibleAssetSpec, MediaType, Name, Precision,
         RicardianContract, Ticker, Timestamp,
     },
     validation::{Failure, ResolveTx},
 };
 use std::{collections::BTreeMap, str::FromStr};
-use strict_types::encoding::TypeName;
+use strict_types::encoding::{FieldName, TypeName};
+
+use crate::structs::{InflationSeal, IssueMetaRequest, IssueMetadata};
 
-use crate::structs::{IssueMetaRequest, IssueMetadata};
+// The inflatable asset schema is local to BitMask, its types are not standardized by
+// rgb-schemata. They take the first ids after the ones of the NIA schema (asset owner 4000,
+// transfer 10000), so contracts issued with it are only understood by wallets sharing it.
+/// Owned state type of the inflation allowance of inflatable RGB20 assets
+pub const OS_INFLATION: AssignmentType = 4001;
+/// Transition type of the secondary issuance of inflatable RGB20 assets
+pub const TS_ISSUE: TransitionType = 10001;
 
 #[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
 #[display(doc_comments)]
@@ -32,6 +44,10 @@ pub enum IssueContractError {
     ContractInvalid(String, Vec<Failure>),
     /// The contract {0} cannot be imported (reason: {1})
     NoImport(String, String),
+    /// The contract interface {0} does not support inflation
+    NoInflationSupport(String),
+    /// The digest of the media {0} is not 32 bytes long
+    WrongMediaDigest(String),
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -45,6 +61,7 @@ pub fn issue_contract<T>(
     seal: &str,
     network: &str,
     meta: Option<IssueMetaRequest>,
+    inflation: Option<InflationSeal>,
     udas_data: BTreeMap<String, Vec<u8>>,
     resolver: &mut T,
     stock: &mut Stock,
@@ -60,10 +77,24 @@ where
         .iface_by_name(&iface_name)
         .map_err(|_| IssueContractError::Forge(BuilderError::InterfaceMismatch))?;
 
+    if inflation.is_some() && iface.name.as_str() != "RGB20" {
+        return Err(IssueContractError::NoInflationSupport(
+            iface.name.to_string(),
+        ));
+    }
+
     let contract_issued = match iface.name.as_str() {
-        "RGB20" => {
-            issue_fungible_asset(ticker, name, description, precision, supply, seal, network)
-        }
+        "RGB20" => issue_fungible_asset(
+            ticker,
+            name,
+            description,
+            precision,
+            supply,
+            seal,
+            network,
+            inflation,
+        )
+        .map_err(IssueContractError::Forge),
         "RGB21" => issue_uda_asset(
             ticker,
             name,
@@ -74,6 +105,17 @@ where
             network,
             meta,
             udas_data,
+        )
+        .map_err(IssueContractError::Forge),
+        "RGB25" => issue_cfa_asset(
+            name,
+            description,
+            precision,
+            supply,
+            seal,
+            network,
+            meta,
+            udas_data,
         ),
         _ => {
             return Err(IssueContractError::NoContractSupport(
@@ -82,7 +124,7 @@ where
         }
     };
 
-    let resp = contract_issued.map_err(IssueContractError::Forge)?;
+    let resp = contract_issued?;
     let contract_id = resp.contract_id().to_string();
     let resp = resp.validate(resolver).map_err(|consig| {
         IssueContractError::ContractInvalid(
@@ -98,7 +140,101 @@ where
     Ok(resp)
 }
 
+/// Non-inflatable asset schema extended with an inflation allowance, which is spent by the
+/// `Issue` transition to mint more supply. Each issuance adds an `issuedSupply` global.
+/// Transfers carry the allowance too, so it follows assets sharing its output.
+///
+/// The allowance is NOT a cap enforced by RGB validation: the schema has no validation script
+/// for the `Issue` transition nor for the allowance moved by transfers. Only `inflate_contract`
+/// keeps the minted amount within the allowance; a transition built by other software can mint
+/// past it or create allowance, and validating wallets accept it.
+pub fn ifa_schema() -> SubSchema {
+    let mut schema = nia_schema();
+    let iimpl = nia_rgb20();
+    let gs_issued = iimpl
+        .global_type(&FieldName::from("issuedSupply"))
+        .expect("RGB20 implementation without issued supply");
+    let os_asset = iimpl
+        .assignments_type(&FieldName::from("assetOwner"))
+        .expect("RGB20 implementation without asset owner");
+
+    let issued_supply = schema.global_types[&gs_issued].sem_id;
+    schema
+        .global_types
+        .insert(gs_issued, GlobalStateSchema::many(issued_supply))
+        .expect("schema global types overflow");
+    schema
+        .owned_types
+        .insert(
+            OS_INFLATION,
+            StateSchema::Fungible(FungibleType::Unsigned64Bit),
+        )
+        .expect("schema owned types overflow");
+    schema
+        .genesis
+        .assignments
+        .insert(OS_INFLATION, Occurrences::NoneOrMore)
+        .expect("genesis assignments overflow");
+
+    // Allowances sharing an output with the transferred assets are moved by the transfer
+    let (ts_transfer, mut transfer) = schema
+        .transitions
+        .iter()
+        .next()
+        .map(|(ty, transition)| (*ty, transition.clone()))
+        .expect("NIA schema without transfer");
+    transfer
+        .inputs
+        .insert(OS_INFLATION, Occurrences::NoneOrMore)
+        .expect("transfer inputs overflow");
+    transfer
+        .assignments
+        .insert(OS_INFLATION, Occurrences::NoneOrMore)
+        .expect("transfer assignments overflow");
+
+    // Assets sharing an output with the allowance are moved by the issuance
+    let mut issue = transfer.clone();
+    issue.globals = tiny_bmap! { gs_issued => Occurrences::Once };
+    issue.inputs = tiny_bmap! {
+        OS_INFLATION => Occurrences::OnceOrMore,
+        os_asset => Occurrences::NoneOrMore,
+    };
+    issue.assignments = tiny_bmap! {
+        os_asset => Occurrences::OnceOrMore,
+        OS_INFLATION => Occurrences::NoneOrMore,
+    };
+    schema
+        .transitions
+        .insert(ts_transfer, transfer)
+        .expect("schema transitions overflow");
+    schema
+        .transitions
+        .insert(TS_ISSUE, issue)
+        .expect("schema transitions overflow");
+
+    schema
+}
+
+/// RGB20 implementation of the inflatable asset schema
+pub fn ifa_rgb20() -> IfaceImpl {
+    let mut iimpl = nia_rgb20();
+    iimpl.schema_id = ifa_schema().schema_id();
+    iimpl
+        .assignments
+        .push(NamedField::with(
+            OS_INFLATION,
+            FieldName::from("inflationAllowance"),
+        ))
+        .expect("implementation assignments overflow");
+    iimpl
+        .transitions
+        .push(NamedType::with(TS_ISSUE, TypeName::from("Issue")))
+        .expect("implementation transitions overflow");
+    iimpl
+}
+
 /// RGB20 interface
+#[allow(clippy::too_many_arguments)]
 fn issue_fungible_asset(
     ticker: &str,
     name: &str,
@@ -107,10 +243,13 @@ fn issue_fungible_asset(
     supply: u64,
     seal: &str,
     network: &str,
+    inflation: Option<InflationSeal>,
 ) -> Result<Contract, BuilderError> {
     let iface = rgb20();
-    let schema = nia_schema();
-    let iimpl = nia_rgb20();
+    let (schema, iimpl) = match inflation {
+        Some(_) => (ifa_schema(), ifa_rgb20()),
+        None => (nia_schema(), nia_rgb20()),
+    };
 
     let ticker: &'static str = Box::leak(ticker.to_string().into_boxed_str());
     let name: &'static str = Box::leak(name.to_string().into_boxed_str());
@@ -126,7 +265,7 @@ fn issue_fungible_asset(
     let seal = ExplicitSeal::<Txid>::from_str(seal).expect("invalid seal definition");
     let seal = GenesisSeal::from(seal);
 
-    let contract = ContractBuilder::with(iface, schema, iimpl)
+    let mut contract = ContractBuilder::with(iface, schema, iimpl)
         .expect("schema fails to implement RGB20 interface")
         .set_chain(Chain::from_str(network).expect("invalid network"))
         .add_global_state("spec", spec)
@@ -138,7 +277,17 @@ fn issue_fungible_asset(
         .add_global_state("issuedSupply", Amount::from(supply))
         .expect("invalid issued supply")
         .add_fungible_state("assetOwner", seal, supply)
-        .expect("invalid asset amount")
+        .expect("invalid asset amount");
+
+    // Inflation Rights
+    if let Some(InflationSeal { seal, amount }) = inflation {
+        let seal = ExplicitSeal::<Txid>::from_str(&seal).expect("invalid seal definition");
+        contract = contract
+            .add_fungible_state("inflationAllowance", GenesisSeal::from(seal), amount)
+            .expect("invalid inflation allowance");
+    }
+
+    let contract = contract
         .issue_contract()
         .expect("contract doesn't fit schema requirements");
     Ok(contract)
@@ -266,3 +415,78 @@ fn issue_uda_asset(
         .expect("contract doesn't fit schema requirements");
     Ok(contract)
 }
+
+/// RGB25 interface
+#[allow(clippy::too_many_arguments)]
+fn issue_cfa_asset(
+    name: &str,
+    description: &str,
+    precision: u8,
+    supply: u64,
+    seal: &str,
+    network: &str,
+    meta: Option<IssueMetaRequest>,
+    udas_data: BTreeMap<String, Vec<u8>>,
+) -> Result<Contract, IssueContractError> {
+    let iface = rgb25();
+    let schema = cfa_schema();
+    let iimpl = cfa_rgb25();
+
+    let name = Name::from_str(name).expect("invalid name");
+    let precision = Precision::try_from(precision).expect("invalid precision");
+    let terms = RicardianContract::from_str(description).expect("invalid contract text");
+    let created = Timestamp::now();
+
+    // Attachment (first media of the metadata)
+    let mut media = None;
+    if let Some(IssueMetaRequest(IssueMetadata::UDA(items))) = meta {
+        if let Some(item) = items.first() {
+            let media_ty: &'static str = Box::leak(item.ty.to_string().into_boxed_str());
+            let mut hash: [u8; 32] = [0; 32];
+            if let Some(data) = udas_data.get(&item.source) {
+                hash = data
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| IssueContractError::WrongMediaDigest(item.source.clone()))?;
+            }
+
+            media = Some(Attachment {
+                ty: MediaType::with(media_ty),
+                digest: hash,
+            });
+        }
+    }
+    let contract_data = ContractData { terms, media };
+
+    // Issuer State
+    let seal = ExplicitSeal::<Txid>::from_str(seal).expect("invalid seal definition");
+    let seal = GenesisSeal::from(seal);
+
+    let mut contract = ContractBuilder::with(iface, schema, iimpl)
+        .expect("schema fails to implement RGB25 interface")
+        .set_chain(Chain::from_str(network).expect("invalid network"))
+        .add_global_state("name", name)
+        .expect("invalid name")
+        .add_global_state("precision", precision)
+        .expect("invalid precision")
+        .add_global_state("created", created)
+        .expect("invalid created")
+        .add_global_state("data", contract_data)
+        .expect("invalid contract text")
+        .add_global_state("issuedSupply", Amount::from(supply))
+        .expect("invalid issued supply");
+
+    if !description.is_empty() {
+        let details = Details::from_str(description).expect("invalid details");
+        contract = contract
+            .add_global_state("details", details)
+            .expect("invalid details");
+    }
+
+    let contract = contract
+        .add_fungible_state("assetOwner", seal, supply)
+        .expect("invalid asset amount")
+        .issue_contract()
+        .expect("contract doesn't fit schema requirements");
+    Ok(contract)
+}
@@ -13,14 +13,15 @@ use rgbstd::{
     persistence::{Inventory, Stash, Stock},
 };
 use rgbwallet::RgbInvoice;
-use strict_encoding::tn;
+use strict_encoding::{tn, FieldName};
 
 use crate::{
-    bitcoin::get_swap_new_address,
+    bitcoin::{get_swap_new_address, resolve_psbt_fee},
     constants::{get_marketplace_fee_percentage, NETWORK},
     structs::{
-        AllocationDetail, AllocationValue, AssetType, FullRgbTransferRequest, PsbtFeeRequest,
-        PsbtInputRequest, RgbBidRequest, RgbOfferRequest, SecretString,
+        AllocationDetail, AllocationValue, AssetType, FullRgbBatchTransferRequest,
+        FullRgbTransferRequest, InflateRequest, PsbtFeeRequest, PsbtInputRequest, RgbBidRequest,
+        RgbBurnRequest, RgbOfferRequest, SecretString,
     },
     validators::RGBContext,
 };
@@ -74,8 +75,350 @@ pub async fn prebuild_transfer_asset(
         errors.insert("contract_id".to_string(), "invalid contract id".to_string());
         TransferError::Validation(errors)
     })?;
+    let target_amount = invoice_amount(&request.rgb_invoice)?;
 
-    let invoice = RgbInvoice::from_str(&request.rgb_invoice).map_err(|_| {
+    let FullRgbTransferRequest {
+        contract_id: _,
+        iface: iface_name,
+        rgb_invoice: _,
+        descriptor,
+        change_terminal,
+        fee,
+        bitcoin_changes,
+    } = request;
+
+    let fee = resolve_psbt_fee(fee)
+        .await
+        .map_err(|op| TransferError::WrongFee(op.to_string()))?;
+
+    let universal_desc = universal_descriptor(&descriptor);
+
+    let mut assets_inputs = vec![];
+    let total_asset_bitcoin_unspend = prebuild_asset_inputs(
+        contract_id,
+        &iface_name,
+        target_amount,
+        &universal_desc,
+        &mut assets_inputs,
+        stock,
+        rgb_wallet,
+        resolver,
+    )
+    .await?;
+
+    let (bitcoin_inputs, bitcoin_changes, fee_value) = prebuild_bitcoin_inputs(
+        &assets_inputs,
+        total_asset_bitcoin_unspend,
+        &universal_desc,
+        fee,
+        change_terminal,
+        bitcoin_changes,
+        rgb_wallet,
+        resolver,
+    )
+    .await?;
+
+    Ok((assets_inputs, bitcoin_inputs, bitcoin_changes, fee_value))
+}
+
+/// Same as `prebuild_transfer_asset`, selecting the allocations of every invoice of the batch.
+/// Invoices of the same contract are paid from a single set of allocations.
+pub async fn prebuild_batch_transfer_asset(
+    request: FullRgbBatchTransferRequest,
+    stock: &mut Stock,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<
+    (
+        Vec<PsbtInputRequest>,
+        Vec<PsbtInputRequest>,
+        Vec<String>,
+        u64,
+    ),
+    TransferError,
+> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    // Target amount of each contract
+    let mut targets: BTreeMap<ContractId, (String, u64)> = BTreeMap::new();
+    for item in &request.invoices {
+        if let Err(err) = item.validate(&RGBContext::default()) {
+            let errors = err
+                .flatten()
+                .into_iter()
+                .map(|(f, e)| (f, e.to_string()))
+                .collect();
+            return Err(TransferError::Validation(errors));
+        }
+
+        let contract_id = ContractId::from_str(&item.contract_id).map_err(|_| {
+            let mut errors = BTreeMap::new();
+            errors.insert("contract_id".to_string(), "invalid contract id".to_string());
+            TransferError::Validation(errors)
+        })?;
+        let target_amount = invoice_amount(&item.rgb_invoice)?;
+
+        let (_, total) = targets
+            .entry(contract_id)
+            .or_insert_with(|| (item.iface.clone(), 0));
+        *total += target_amount;
+    }
+
+    let FullRgbBatchTransferRequest {
+        invoices: _,
+        descriptor,
+        change_terminal,
+        fee,
+        bitcoin_changes,
+    } = request;
+
+    let fee = resolve_psbt_fee(fee)
+        .await
+        .map_err(|op| TransferError::WrongFee(op.to_string()))?;
+
+    let universal_desc = universal_descriptor(&descriptor);
+
+    let mut assets_inputs = vec![];
+    let mut total_asset_bitcoin_unspend = 0;
+    for (contract_id, (iface_name, target_amount)) in targets {
+        total_asset_bitcoin_unspend += prebuild_asset_inputs(
+            contract_id,
+            &iface_name,
+            target_amount,
+            &universal_desc,
+            &mut assets_inputs,
+            stock,
+            rgb_wallet,
+            resolver,
+        )
+        .await?;
+    }
+
+    let (bitcoin_inputs, bitcoin_changes, fee_value) = prebuild_bitcoin_inputs(
+        &assets_inputs,
+        total_asset_bitcoin_unspend,
+        &universal_desc,
+        fee,
+        change_terminal,
+        bitcoin_changes,
+        rgb_wallet,
+        resolver,
+    )
+    .await?;
+
+    Ok((assets_inputs, bitcoin_inputs, bitcoin_changes, fee_value))
+}
+
+/// Selects the allocations holding the amount to burn, plus the bitcoin inputs paying the fee
+pub async fn prebuild_burn_asset(
+    request: RgbBurnRequest,
+    stock: &mut Stock,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<
+    (
+        Vec<PsbtInputRequest>,
+        Vec<PsbtInputRequest>,
+        Vec<String>,
+        u64,
+    ),
+    TransferError,
+> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    let contract_id = ContractId::from_str(&request.contract_id).map_err(|_| {
+        let mut errors = BTreeMap::new();
+        errors.insert("contract_id".to_string(), "invalid contract id".to_string());
+        TransferError::Validation(errors)
+    })?;
+
+    let RgbBurnRequest {
+        iface,
+        amount,
+        descriptor,
+        change_terminal,
+        fee,
+        bitcoin_changes,
+        ..
+    } = request;
+
+    let fee = resolve_psbt_fee(fee)
+        .await
+        .map_err(|op| TransferError::WrongFee(op.to_string()))?;
+
+    let universal_desc = universal_descriptor(&descriptor);
+
+    let mut assets_inputs = vec![];
+    let total_asset_bitcoin_unspend = prebuild_asset_inputs(
+        contract_id,
+        &iface,
+        amount,
+        &universal_desc,
+        &mut assets_inputs,
+        stock,
+        rgb_wallet,
+        resolver,
+    )
+    .await?;
+
+    let (bitcoin_inputs, bitcoin_changes, fee_value) = prebuild_bitcoin_inputs(
+        &assets_inputs,
+        total_asset_bitcoin_unspend,
+        &universal_desc,
+        fee,
+        change_terminal,
+        bitcoin_changes,
+        rgb_wallet,
+        resolver,
+    )
+    .await?;
+
+    Ok((assets_inputs, bitcoin_inputs, bitcoin_changes, fee_value))
+}
+
+/// Selects the inflation allowances of the contract owned by the wallet until the minted
+/// amount is reached, plus the bitcoin inputs paying the fee.
+pub async fn prebuild_inflation(
+    request: InflateRequest,
+    stock: &mut Stock,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<
+    (
+        Vec<PsbtInputRequest>,
+        Vec<PsbtInputRequest>,
+        Vec<String>,
+        u64,
+    ),
+    TransferError,
+> {
+    if let Err(err) = request.validate(&RGBContext::default()) {
+        let errors = err
+            .flatten()
+            .into_iter()
+            .map(|(f, e)| (f, e.to_string()))
+            .collect();
+        return Err(TransferError::Validation(errors));
+    }
+
+    let contract_id = ContractId::from_str(&request.contract_id).map_err(|_| {
+        let mut errors = BTreeMap::new();
+        errors.insert("contract_id".to_string(), "invalid contract id".to_string());
+        TransferError::Validation(errors)
+    })?;
+    let target_amount: u64 = request
+        .beneficiaries
+        .iter()
+        .map(|beneficiary| beneficiary.amount)
+        .sum();
+
+    let InflateRequest {
+        descriptor,
+        change_terminal,
+        fee,
+        bitcoin_changes,
+        ..
+    } = request;
+
+    let fee = resolve_psbt_fee(fee)
+        .await
+        .map_err(|op| TransferError::WrongFee(op.to_string()))?;
+
+    let universal_desc = universal_descriptor(&descriptor);
+
+    let contract_index = AssetType::RGB20 as u32;
+    let iface = stock
+        .iface_by_name(&tn!("RGB20"))
+        .map_err(|_| TransferError::NoIface)?;
+    let contract_iface = stock
+        .contract_iface(contract_id, iface.iface_id())
+        .map_err(|_| TransferError::NoContract)?;
+
+    sync_wallet(contract_index, rgb_wallet, resolver);
+    prefetch_resolver_utxos(
+        contract_index,
+        rgb_wallet,
+        resolver,
+        Some(RGB_DEFAULT_FETCH_LIMIT),
+    )
+    .await;
+
+    let allowances = contract_iface
+        .fungible(FieldName::from("inflationAllowance"), &None)
+        .map_err(|_| TransferError::NoContract)?;
+    let asset_unspent_utxos = next_utxos(contract_index, rgb_wallet.clone(), resolver)
+        .map_err(|_| TransferError::IO(RgbPersistenceError::RetrieveRgbAccount("".to_string())))?;
+
+    let mut assets_inputs: Vec<PsbtInputRequest> = vec![];
+    let mut allowance_total = 0;
+    let mut total_asset_bitcoin_unspend = 0;
+    for allowance in allowances {
+        if allowance_total >= target_amount {
+            break;
+        }
+
+        let utxo = match asset_unspent_utxos
+            .iter()
+            .find(|utxo| utxo.outpoint == allowance.owner)
+        {
+            Some(utxo) => utxo,
+            _ => continue,
+        };
+
+        let outpoint = utxo.outpoint.to_string();
+        if !assets_inputs.iter().any(|x| x.utxo == outpoint) {
+            let TerminalPath { app, index } = utxo.derivation.terminal;
+            assets_inputs.push(PsbtInputRequest {
+                descriptor: universal_desc.clone(),
+                utxo: outpoint,
+                utxo_terminal: format!("/{app}/{index}"),
+                tapret: None,
+            });
+            total_asset_bitcoin_unspend += utxo.amount;
+        }
+        allowance_total += allowance.value;
+    }
+
+    if allowance_total < target_amount {
+        let mut errors = BTreeMap::new();
+        errors.insert(
+            "beneficiaries".to_string(),
+            "insufficient inflation allowance".to_string(),
+        );
+        return Err(TransferError::Validation(errors));
+    }
+
+    let (bitcoin_inputs, bitcoin_changes, fee_value) = prebuild_bitcoin_inputs(
+        &assets_inputs,
+        total_asset_bitcoin_unspend,
+        &universal_desc,
+        fee,
+        change_terminal,
+        bitcoin_changes,
+        rgb_wallet,
+        resolver,
+    )
+    .await?;
+
+    Ok((assets_inputs, bitcoin_inputs, bitcoin_changes, fee_value))
+}
+
+pub fn invoice_amount(rgb_invoice: &str) -> Result<u64, TransferError> {
+    let invoice = RgbInvoice::from_str(rgb_invoice).map_err(|_| {
         let mut errors = BTreeMap::new();
         errors.insert(
             "rgb_invoice".to_string(),
@@ -84,28 +427,20 @@ pub async fn prebuild_transfer_asset(
         TransferError::Validation(errors)
     })?;
 
-    let target_amount = match invoice.owned_state {
-        TypedState::Amount(target_amount) => target_amount,
+    match invoice.owned_state {
+        TypedState::Amount(target_amount) => Ok(target_amount),
         _ => {
             let mut errors = BTreeMap::new();
             errors.insert(
                 "rgb_invoice".to_string(),
                 "invalid rgb invoice data".to_string(),
             );
-            return Err(TransferError::Validation(errors));
+            Err(TransferError::Validation(errors))
         }
-    };
-
-    let FullRgbTransferRequest {
-        contract_id: _,
-        iface: iface_name,
-        rgb_invoice: _,
-        descriptor,
-        change_terminal,
-        fee,
-        mut bitcoin_changes,
-    } = request;
+    }
+}
 
+fn universal_descriptor(descriptor: &SecretString) -> SecretString {
     let wildcard_terminal = "/*/*";
     let mut universal_desc = descriptor.to_string();
     for contract_type in [
@@ -123,18 +458,25 @@ pub async fn prebuild_transfer_asset(
         }
     }
 
-    let universal_desc = SecretString(universal_desc);
-    let mut all_unspents = vec![];
+    SecretString(universal_desc)
+}
 
-    // Get All Assets UTXOs
-    let contract_index = if let "RGB20" = iface_name.as_str() {
+/// Unspent allocations of the contract owned by the wallet
+async fn spendable_allocations(
+    contract_id: ContractId,
+    iface_name: &str,
+    stock: &mut Stock,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<Vec<AllocationDetail>, TransferError> {
+    let contract_index = if let "RGB20" | "RGB25" = iface_name {
         AssetType::RGB20
     } else {
         AssetType::RGB21
     };
 
     let iface = stock
-        .iface_by_name(&tn!(iface_name))
+        .iface_by_name(&tn!(iface_name.to_string()))
         .map_err(|_| TransferError::NoIface)?;
     let contract_iface = stock
         .contract_iface(contract_id, iface.iface_id())
@@ -154,21 +496,59 @@ pub async fn prebuild_transfer_asset(
     let contract = export_contract(contract_id, stock, resolver, &mut Some(rgb_wallet.clone()))
         .map_err(TransferError::Export)?;
 
-    let allocations: Vec<AllocationDetail> = contract
+    Ok(contract
         .allocations
         .into_iter()
         .filter(|x| x.is_mine && !x.is_spent)
-        .collect();
+        .collect())
+}
 
-    let asset_total: u64 = allocations
-        .clone()
-        .into_iter()
-        .filter(|a| a.is_mine && !a.is_spent)
-        .map(|a| match a.value {
+fn allocations_amount(allocations: &[AllocationDetail]) -> u64 {
+    allocations
+        .iter()
+        .map(|a| match &a.value {
             AllocationValue::Value(value) => value.to_owned(),
             AllocationValue::UDA(_) => 1,
         })
-        .sum();
+        .sum()
+}
+
+/// Amount of the contract the wallet can spend right now, held by its unspent allocations
+pub async fn prebuild_spendable_amount(
+    contract_id: ContractId,
+    iface_name: &str,
+    stock: &mut Stock,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<u64, TransferError> {
+    let allocations =
+        spendable_allocations(contract_id, iface_name, stock, rgb_wallet, resolver).await?;
+    Ok(allocations_amount(&allocations))
+}
+
+/// Selects allocations of the contract until the target amount is reached, skipping the UTXOs
+/// already in `assets_inputs`. Returns the bitcoin held by the selected UTXOs.
+#[allow(clippy::too_many_arguments)]
+async fn prebuild_asset_inputs(
+    contract_id: ContractId,
+    iface_name: &str,
+    target_amount: u64,
+    universal_desc: &SecretString,
+    assets_inputs: &mut Vec<PsbtInputRequest>,
+    stock: &mut Stock,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<u64, TransferError> {
+    // Get All Assets UTXOs
+    let contract_index = if let "RGB20" | "RGB25" = iface_name {
+        AssetType::RGB20
+    } else {
+        AssetType::RGB21
+    };
+    let contract_index = contract_index as u32;
+    let allocations =
+        spendable_allocations(contract_id, iface_name, stock, rgb_wallet, resolver).await?;
+    let asset_total = allocations_amount(&allocations);
 
     if asset_total < target_amount {
         let mut errors = BTreeMap::new();
@@ -180,10 +560,6 @@ pub async fn prebuild_transfer_asset(
         .map_err(|_| TransferError::IO(RgbPersistenceError::RetrieveRgbAccount("".to_string())))?;
 
     let mut asset_total = 0;
-    let mut assets_inputs = vec![];
-    let mut rng = StdRng::from_entropy();
-    let rnd_amount = rng.gen_range(600..1500);
-
     let mut total_asset_bitcoin_unspend: u64 = 0;
     for alloc in allocations.into_iter() {
         match alloc.value {
@@ -214,8 +590,8 @@ pub async fn prebuild_transfer_asset(
                         })
                         .map(|x| x.amount)
                         .sum::<u64>();
-                    asset_total += alloc_value;
                 }
+                asset_total += alloc_value;
             }
             AllocationValue::UDA(_) => {
                 let input = PsbtInputRequest {
@@ -246,6 +622,26 @@ pub async fn prebuild_transfer_asset(
         }
     }
 
+    Ok(total_asset_bitcoin_unspend)
+}
+
+/// Selects the bitcoin inputs paying the fee and the bitcoin changes, and adds the change of
+/// the transaction to the bitcoin changes
+#[allow(clippy::too_many_arguments)]
+async fn prebuild_bitcoin_inputs(
+    assets_inputs: &[PsbtInputRequest],
+    total_asset_bitcoin_unspend: u64,
+    universal_desc: &SecretString,
+    fee: PsbtFeeRequest,
+    change_terminal: String,
+    mut bitcoin_changes: Vec<String>,
+    rgb_wallet: &mut RgbWallet,
+    resolver: &mut ExplorerResolver,
+) -> Result<(Vec<PsbtInputRequest>, Vec<String>, u64), TransferError> {
+    let mut all_unspents = vec![];
+    let mut rng = StdRng::from_entropy();
+    let rnd_amount = rng.gen_range(600..1500);
+
     // Get All Bitcoin UTXOs
     let total_bitcoin_spend: u64 = bitcoin_changes
         .clone()
@@ -331,7 +727,7 @@ pub async fn prebuild_transfer_asset(
                 }
             }
 
-            let mut all_inputs = assets_inputs.clone();
+            let mut all_inputs = assets_inputs.to_vec();
             all_inputs.extend(bitcoin_inputs.clone());
 
             let txids = all_inputs
@@ -342,7 +738,7 @@ pub async fn prebuild_transfer_asset(
             prefetch_resolver_txs(txids, resolver).await;
 
             let (change_value, fee) = estimate_fee_tx(
-                assets_inputs.clone(),
+                assets_inputs.to_vec(),
                 bitcoin_inputs.clone(),
                 bitcoin_changes.clone(),
                 fee_rate,
@@ -355,6 +751,11 @@ pub async fn prebuild_transfer_asset(
 
             (change_value, fee)
         }
+        PsbtFeeRequest::Target(target) => {
+            return Err(TransferError::WrongFee(format!(
+                "unresolved fee target {target:?}"
+            )))
+        }
     };
 
     let total_spendable = fee_value + rnd_amount + total_bitcoin_spend;
@@ -378,7 +779,7 @@ pub async fn prebuild_transfer_asset(
         bitcoin_changes.push(change_bitcoin);
     }
 
-    Ok((assets_inputs, bitcoin_inputs, bitcoin_changes, fee_value))
+    Ok((bitcoin_inputs, bitcoin_changes, fee_value))
 }
 
 pub async fn prebuild_seller_swap(
@@ -439,7 +840,7 @@ pub async fn prebuild_seller_swap(
     let mut all_unspents = vec![];
 
     // Get All Assets UTXOs
-    let contract_index = if let "RGB20" = iface_name.as_str() {
+    let contract_index = if let "RGB20" | "RGB25" = iface_name.as_str() {
         AssetType::RGB20
     } else {
         AssetType::RGB21
@@ -669,6 +1070,10 @@ pub async fn prebuild_buyer_swap(
         ..
     } = request;
 
+    let fee = resolve_psbt_fee(fee)
+        .await
+        .map_err(|op| RgbSwapError::WrongFee(op.to_string()))?;
+
     let wildcard_terminal = "/*/*";
     let mut universal_desc = descriptor.to_string();
     for contract_type in [AssetType::Bitcoin, AssetType::Change] {
@@ -826,6 +1231,11 @@ pub async fn prebuild_buyer_swap(
 
             (change_value, fee)
         }
+        PsbtFeeRequest::Target(target) => {
+            return Err(RgbSwapError::WrongFee(format!(
+                "unresolved fee target {target:?}"
+            )))
+        }
     };
 
     let total_spendable = fee_value + offer.bitcoin_price;
@@ -18,11 +18,20 @@ use rgbstd::{
 };
 use wallet::onchain::{ResolveTx, TxResolverError};
 
-use crate::structs::{TxStatus, UtxoSpentStatus};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::bitcoin::{chain_source, ChainSource};
+use crate::{
+    bitcoin::{chain_backend, ChainBackend},
+    constants::{BITCOIN_ELECTRUM_API, BITCOIN_EXPLORER_API},
+    structs::{TxStatus, UtxoSpentStatus},
+    warn,
+};
 
 #[derive(Default)]
 pub struct ExplorerResolver {
     pub explorer_url: String,
+    pub electrum_url: String,
+    pub backend: ChainBackend,
     // Prefetch Data (wasm32)
     pub utxos: BTreeSet<Utxo>,
     pub utxos_spent: Vec<UtxoSpentStatus>,
@@ -30,6 +39,49 @@ pub struct ExplorerResolver {
     pub bp_txs: HashMap<Txid, Tx>,
     pub tx_height: HashMap<Txid, WitnessOrd>,
     pub txs_status: HashMap<bitcoin::Txid, TxStatus>,
+    // Chain connection, opened on the first query (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    chain: once_cell::sync::OnceCell<Box<dyn ChainSource>>,
+}
+
+impl ExplorerResolver {
+    /// Resolver of the current network, targeting the configured chain backend
+    pub async fn from_config() -> Self {
+        let backend = match chain_backend().await {
+            Ok(backend) => backend,
+            Err(err) => {
+                warn!(format!("Resolving with esplora: {err}"));
+                ChainBackend::Esplora
+            }
+        };
+
+        Self::new(
+            backend,
+            &BITCOIN_EXPLORER_API.read().await,
+            &BITCOIN_ELECTRUM_API.read().await,
+        )
+    }
+
+    /// Resolver targeting the given chain backend
+    pub fn new(backend: ChainBackend, explorer_url: &str, electrum_url: &str) -> Self {
+        Self {
+            explorer_url: explorer_url.to_owned(),
+            electrum_url: electrum_url.to_owned(),
+            backend,
+            ..Default::default()
+        }
+    }
+
+    /// Chain source of the backend. The connection is reused by every query of the resolver.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn chain(&self) -> &dyn ChainSource {
+        self.chain
+            .get_or_init(|| {
+                chain_source(self.backend, &self.explorer_url, &self.electrum_url)
+                    .expect("service unavaliable")
+            })
+            .as_ref()
+    }
 }
 
 impl rgb::Resolver for ExplorerResolver {
@@ -44,38 +96,20 @@ impl rgb::Resolver for ExplorerResolver {
         use std::collections::HashSet;
 
         let mut utxos = bset![];
-        let explorer_client = esplora_block::Builder::new(&self.explorer_url)
-            .build_blocking()
-            .expect("service unavaliable");
+        let chain = self.chain();
 
         for (derive, script) in scripts {
             // TODO: Remove that after bitcoin v.30 full compatibility
             let script_compatible =
                 Script::from_hex(&script.as_script().to_hex_string()).expect("invalid script");
 
-            let mut related_txs = explorer_client
-                .scripthash_txs(&script_compatible, None)
+            let related_txs = chain
+                .script_txs(&script_compatible)
                 .expect("Service unavaliable");
-            let n_confirmed = related_txs.iter().filter(|tx| tx.status.confirmed).count();
-            // esplora pages on 25 confirmed transactions. If there are 25 or more we
-            // keep requesting to see if there's more.
-            if n_confirmed >= 25 {
-                loop {
-                    let new_related_txs = explorer_client
-                        .scripthash_txs(&script_compatible, Some(related_txs.last().unwrap().txid))
-                        .expect("Service unavaliable");
-                    let n = new_related_txs.len();
-                    related_txs.extend(new_related_txs);
-                    // we've reached the end
-                    if n < 25 {
-                        break;
-                    }
-                }
-            }
 
             related_txs.into_iter().for_each(|tx| {
-                for (index, vout) in tx.vout.iter().enumerate() {
-                    if vout.scriptpubkey != script_compatible {
+                for (index, vout) in tx.output.iter().enumerate() {
+                    if vout.script_pubkey != script_compatible {
                         continue;
                     }
 
@@ -115,11 +149,7 @@ impl ResolveTx for ExplorerResolver {
         &self,
         txid: bitcoin::Txid,
     ) -> Result<bitcoin::Transaction, wallet::onchain::TxResolverError> {
-        let explorer_client = esplora_block::Builder::new(&self.explorer_url)
-            .build_blocking()
-            .expect("service unavaliable");
-
-        match explorer_client.get_tx(&txid).expect("service unavaliable") {
+        match self.chain().get_tx(&txid).expect("service unavaliable") {
             Some(tx) => Ok(tx),
             _ => Err(TxResolverError { txid, err: none!() }),
         }
@@ -141,16 +171,14 @@ impl ResolveHeight for ExplorerResolver {
     type Error = TxResolverError;
     #[cfg(not(target_arch = "wasm32"))]
     fn resolve_height(&mut self, txid: Txid) -> Result<WitnessOrd, Self::Error> {
-        let esplora_client = esplora_block::Builder::new(&self.explorer_url)
-            .build_blocking()
-            .expect("service unavaliable");
         let transaction_id =
             &bitcoin::Txid::from_str(&txid.to_hex()).expect("invalid transaction id parse");
-        let tx = esplora_client
+        let tx = self
+            .chain()
             .get_tx_status(transaction_id)
             .expect("service unavaliable");
 
-        let status = match tx.block_height {
+        let status = match tx.and_then(|tx| tx.block_height) {
             Some(height) => WitnessOrd::OnChain(WitnessHeight::new(height).unwrap()),
             _ => WitnessOrd::OffChain,
         };
@@ -168,13 +196,10 @@ impl ResolveHeight for ExplorerResolver {
 impl ResolveCommiment for ExplorerResolver {
     #[cfg(not(target_arch = "wasm32"))]
     fn resolve_tx(&self, txid: Txid) -> Result<Tx, rgbstd::validation::TxResolverError> {
-        let explorer_client = esplora_block::Builder::new(&self.explorer_url)
-            .build_blocking()
-            .expect("service unavaliable");
-
         let transaction_id =
             &bitcoin::Txid::from_str(&txid.to_hex()).expect("invalid transaction id parse");
-        let tx = explorer_client
+        let tx = self
+            .chain()
             .get_tx(transaction_id)
             .expect("service unavaliable");
 
@@ -241,13 +266,11 @@ impl ResolveSpent for ExplorerResolver {
         index: u64,
         block_height: bool,
     ) -> Result<UtxoSpentStatus, Self::Error> {
-        let explorer_client = esplora_block::Builder::new(&self.explorer_url)
-            .build_blocking()
-            .expect("service unavaliable");
+        let chain = self.chain();
 
         let mut block_h = TxStatus::NotFound;
         if block_height {
-            if let Ok(tx_status) = explorer_client.get_tx_status(&txid) {
+            if let Ok(Some(tx_status)) = chain.get_tx_status(&txid) {
                 if tx_status.confirmed {
                     block_h = TxStatus::Block(tx_status.block_height.unwrap_or_default());
                 } else {
@@ -256,7 +279,7 @@ impl ResolveSpent for ExplorerResolver {
             }
         }
 
-        match explorer_client
+        match chain
             .get_output_status(&txid, index)
             .expect("service unavaliable")
         {
@@ -12,6 +12,8 @@ use rgb::{RgbWallet, TerminalPath};
 use rgbstd::containers::{Bindle, Transfer};
 use serde::{Deserialize, Serialize};
 
+use crate::structs::RgbAirdropChunk;
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Display)]
 #[display("{address}:{amount}", alt = "{address:#}:{amount:#}")]
 pub struct AddressAmount {
@@ -65,6 +67,38 @@ pub struct RgbTransfer {
     pub is_send: bool,
 }
 
+/// Airdrops of the issuer, by airdrop id
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct RgbAirdrops {
+    pub airdrops: BTreeMap<String, RgbAirdrop>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RgbAirdrop {
+    pub contract_id: String,
+    pub iface: String,
+    pub chunks: Vec<RgbAirdropChunk>,
+}
+
+/// Burns of the wallet, by contract id
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct RgbBurns {
+    pub burns: BTreeMap<String, Vec<RgbBurn>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RgbBurn {
+    pub consig_id: String,
+    pub iface: String,
+    pub consig: String,
+    pub tx: Txid,
+    /// Unspendable output receiving the burned state
+    pub vout: u32,
+    /// Blinding of the burn seal, concealed in the consignment
+    pub blinding: u64,
+    pub amount: u64,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct RgbExtractTransfer {
     pub consig_id: String,
@@ -1,27 +1,39 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
 use amplify::{
     confinement::{Confined, U32},
     hex::ToHex,
 };
-use bitcoin_30::psbt::Psbt as PSBT;
+use bitcoin_30::{
+    opcodes::all::OP_RETURN,
+    psbt::{Output as PsbtOutput, Psbt as PSBT},
+    ScriptBuf, TxOut,
+};
 use bitcoin_hashes::hex::FromHex;
-use bp::{seals::txout::CloseMethod, Chain, Txid};
+use bp::{seals::txout::CloseMethod, Chain, Outpoint, Txid};
 use indexmap::IndexMap;
 use psbt::{serialize::Serialize, Psbt};
 use rgbstd::{
     containers::{Bindle, Transfer},
-    contract::{ContractId, GraphSeal},
+    contract::{ContractId, GraphSeal, SecretSeal, Transition},
     interface::TypedState,
     persistence::{Inventory, Stash, Stock},
     resolvers::ResolveHeight,
+    stl::Amount,
     validation::{AnchoredBundle, ConsignmentApi, ResolveTx, Status},
 };
-use rgbwallet::{InventoryWallet, InvoiceParseError, RgbInvoice, RgbTransport};
+use rgbwallet::{
+    psbt::{PsbtDbc, RgbExt, RgbInExt},
+    Beneficiary, InventoryWallet, InvoiceParseError, RgbInvoice, RgbTransport,
+};
 use seals::txout::ExplicitSeal;
-use strict_encoding::{StrictDeserialize, TypeName};
+use strict_encoding::{FieldName, StrictDeserialize, TypeName};
 
-use crate::rgb::prebuild::prebuild_extract_transfer;
+use crate::{rgb::prebuild::prebuild_extract_transfer, structs::InflationSeal};
 
 #[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
 #[display(doc_comments)]
@@ -40,6 +52,8 @@ pub enum NewInvoiceError {
     EmptyContracts,
     /// Error saving secret seal: {0}
     StoreSeal(String),
+    /// Invoice '{0}' does not request the expected contract, interface or amount
+    WrongInvoice(String),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
@@ -73,6 +87,8 @@ pub enum AcceptTransferError {
     InvalidConsig(Vec<String>),
     /// The Consignment is invalid (Unexpected behavior on validation).
     Inconclusive,
+    /// The output {0} does not burn any state provably
+    NoBurn(String),
 }
 
 pub fn create_invoice(
@@ -135,6 +151,56 @@ pub fn create_invoice(
     Ok(invoice)
 }
 
+/// Invoice paying `amount` of the contract to a beneficiary, given either as an RGB invoice
+/// or as a seal (blinded or explicit). A given invoice must request the same contract,
+/// interface and amount.
+pub fn beneficiary_invoice(
+    contract_id: &str,
+    iface: &str,
+    amount: u64,
+    beneficiary: &str,
+    network: &str,
+) -> Result<RgbInvoice, NewInvoiceError> {
+    let ty =
+        TypeName::from_str(iface).map_err(|_| NewInvoiceError::WrongIface(iface.to_string()))?;
+    let contract_id = ContractId::from_str(contract_id)
+        .map_err(|_| NewInvoiceError::WrongContract(contract_id.to_string()))?;
+    let chain =
+        Chain::from_str(network).map_err(|op| NewInvoiceError::WrongNetwork(op.to_string()))?;
+
+    if let Ok(invoice) = RgbInvoice::from_str(beneficiary) {
+        if invoice.contract != Some(contract_id)
+            || invoice.iface.as_ref().is_some_and(|name| name != &ty)
+            || invoice.owned_state != TypedState::Amount(amount)
+        {
+            return Err(NewInvoiceError::WrongInvoice(beneficiary.to_string()));
+        }
+        return Ok(invoice);
+    }
+
+    let seal = match SecretSeal::from_str(beneficiary) {
+        Ok(seal) => seal,
+        Err(_) => {
+            let seal = ExplicitSeal::<Txid>::from_str(beneficiary)
+                .map_err(|_| NewInvoiceError::WrongSeal(beneficiary.to_string()))?;
+            GraphSeal::new(seal.method, seal.txid, seal.vout).to_concealed_seal()
+        }
+    };
+
+    Ok(RgbInvoice {
+        transports: vec![RgbTransport::UnspecifiedMeans],
+        contract: Some(contract_id),
+        iface: Some(ty),
+        operation: None,
+        assignment: None,
+        beneficiary: seal.into(),
+        owned_state: TypedState::Amount(amount),
+        chain: Some(chain),
+        unknown_query: IndexMap::default(),
+        expiry: None,
+    })
+}
+
 pub fn pay_invoice(
     invoice: String,
     psbt: String,
@@ -158,6 +224,486 @@ pub fn pay_invoice(
     Ok((psbt_file, transfer))
 }
 
+/// Pays several invoices, possibly of different contracts, with one PSBT and a single tapret
+/// commitment. The change of every contract is assigned to the last output, the tapret host.
+/// Returns one consignment per invoice, in the order of the invoices.
+pub fn pay_invoices(
+    invoices: Vec<String>,
+    psbt: String,
+    stock: &mut Stock,
+) -> Result<(Psbt, Vec<Bindle<Transfer>>), NewPaymentError> {
+    let invoices = invoices
+        .iter()
+        .map(|invoice| RgbInvoice::from_str(invoice))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(NewPaymentError::WrongInvoice)?;
+    let psbt_file = Psbt::from_str(&psbt).map_err(|_| NewPaymentError::WrongHex)?;
+
+    let psbt = base64::decode(&base64::encode(&psbt_file.serialize()))
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+
+    let mut psbt_final =
+        PSBT::deserialize(&psbt).map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+
+    let mut prev_outputs = vec![];
+    for txin in &psbt_final.unsigned_tx.input {
+        let txid = Txid::from_str(&txin.previous_output.txid.to_string())
+            .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+        prev_outputs.push(Outpoint::new(txid, txin.previous_output.vout));
+    }
+
+    // Invoices of each contract
+    let mut contracts: BTreeMap<ContractId, Vec<&RgbInvoice>> = BTreeMap::new();
+    for invoice in &invoices {
+        let contract_id = invoice.contract.ok_or(NewPaymentError::Invalid)?;
+        contracts.entry(contract_id).or_default().push(invoice);
+    }
+
+    let change_vout = psbt_final.outputs.len().saturating_sub(1) as u32;
+    let change_seal = GraphSeal::new_vout(CloseMethod::TapretFirst, change_vout);
+    let mut transitions = BTreeMap::new();
+    for (contract_id, contract_invoices) in &contracts {
+        let first = contract_invoices[0];
+        let iface = first.iface.clone().ok_or(NewPaymentError::Invalid)?;
+        let mut builder = stock
+            .transition_builder(*contract_id, iface, first.operation.clone())
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+
+        let assignment_name = first
+            .assignment
+            .clone()
+            .or_else(|| builder.default_assignment().ok().cloned())
+            .ok_or(NewPaymentError::Invalid)?;
+        let assignment_id = builder
+            .assignments_type(&assignment_name)
+            .ok_or(NewPaymentError::Invalid)?;
+
+        let mut sum_inputs = 0u64;
+        let spent = stock
+            .state_for_outpoints(*contract_id, prev_outputs.iter().copied())
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        for (opout, state) in spent {
+            builder = builder
+                .add_input(opout)
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            if opout.ty != assignment_id {
+                builder = builder
+                    .add_raw_state(opout.ty, change_seal, state)
+                    .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            } else if let TypedState::Amount(value) = state {
+                sum_inputs += value;
+            }
+        }
+
+        let mut sum_outputs = 0u64;
+        for invoice in contract_invoices {
+            let amount = match invoice.owned_state {
+                TypedState::Amount(amount) => amount,
+                _ => {
+                    return Err(NewPaymentError::NoPay(
+                        "only fungible state can be paid in a batch".to_string(),
+                    ))
+                }
+            };
+            let seal = match invoice.beneficiary {
+                Beneficiary::BlindedSeal(seal) => seal,
+                _ => return Err(NewPaymentError::Invalid),
+            };
+            builder = builder
+                .add_raw_state(assignment_id, seal, TypedState::Amount(amount))
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            sum_outputs += amount;
+        }
+
+        match sum_inputs.cmp(&sum_outputs) {
+            Ordering::Greater => {
+                let change = TypedState::Amount(sum_inputs - sum_outputs);
+                builder = builder
+                    .add_raw_state(assignment_id, change_seal, change)
+                    .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            }
+            Ordering::Less => {
+                return Err(NewPaymentError::NoPay(format!(
+                    "insufficient state of {contract_id}"
+                )))
+            }
+            Ordering::Equal => {}
+        }
+
+        let transition = builder
+            .complete_transition(*contract_id)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        transitions.insert(*contract_id, transition);
+    }
+
+    anchor_transitions(
+        &mut psbt_final,
+        &prev_outputs,
+        transitions,
+        change_seal,
+        stock,
+    )?;
+
+    let mut transfers = vec![];
+    for invoice in &invoices {
+        let contract_id = invoice.contract.ok_or(NewPaymentError::Invalid)?;
+        let seal = match invoice.beneficiary {
+            Beneficiary::BlindedSeal(seal) => seal,
+            _ => return Err(NewPaymentError::Invalid),
+        };
+        let transfer = stock
+            .transfer(contract_id, [seal])
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        transfers.push(transfer);
+    }
+
+    let psbt_file = Psbt::from_str(&PSBT::serialize(&psbt_final).to_hex())
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+    Ok((psbt_file, transfers))
+}
+
+/// Exercises the inflation rights of the contract held by the PSBT inputs, minting the new
+/// supply to the beneficiary seals with an `Issue` transition. The remaining allowance, any
+/// other state of the inputs and the other contracts allocated on them are assigned to the last
+/// output, the tapret host.
+pub fn inflate_contract(
+    contract_id: &str,
+    beneficiaries: Vec<InflationSeal>,
+    psbt: String,
+    stock: &mut Stock,
+) -> Result<(Psbt, u64), NewPaymentError> {
+    let contract_id = ContractId::from_str(contract_id).map_err(|_| NewPaymentError::Invalid)?;
+    let psbt_file = Psbt::from_str(&psbt).map_err(|_| NewPaymentError::WrongHex)?;
+
+    let psbt = base64::decode(&base64::encode(&psbt_file.serialize()))
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+
+    let mut psbt_final =
+        PSBT::deserialize(&psbt).map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+
+    let mut prev_outputs = vec![];
+    for txin in &psbt_final.unsigned_tx.input {
+        let txid = Txid::from_str(&txin.previous_output.txid.to_string())
+            .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+        prev_outputs.push(Outpoint::new(txid, txin.previous_output.vout));
+    }
+
+    let change_vout = psbt_final.outputs.len().saturating_sub(1) as u32;
+    let change_seal = GraphSeal::new_vout(CloseMethod::TapretFirst, change_vout);
+    let mut builder = stock
+        .transition_builder(contract_id, "RGB20", Some("Issue"))
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    let allowance_id = builder
+        .assignments_type(&FieldName::from("inflationAllowance"))
+        .ok_or(NewPaymentError::Invalid)?;
+    let owner_id = builder
+        .assignments_type(&FieldName::from("assetOwner"))
+        .ok_or(NewPaymentError::Invalid)?;
+
+    let mut allowance = 0u64;
+    let spent = stock
+        .state_for_outpoints(contract_id, prev_outputs.iter().copied())
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    for (opout, state) in spent {
+        builder = builder
+            .add_input(opout)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        if opout.ty != allowance_id {
+            builder = builder
+                .add_raw_state(opout.ty, change_seal, state)
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        } else if let TypedState::Amount(value) = state {
+            allowance = allowance.checked_add(value).ok_or_else(|| {
+                NewPaymentError::NoPay(format!("inflation allowance of {contract_id} overflows"))
+            })?;
+        }
+    }
+
+    let mut minted = 0u64;
+    for InflationSeal { seal, amount } in beneficiaries {
+        let seal = ExplicitSeal::<Txid>::from_str(&seal)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        let seal = GraphSeal::new(seal.method, seal.txid, seal.vout);
+        builder = builder
+            .add_raw_state(owner_id, seal, TypedState::Amount(amount))
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        minted = minted.checked_add(amount).ok_or_else(|| {
+            NewPaymentError::NoPay(format!("inflation of {contract_id} overflows"))
+        })?;
+    }
+
+    match allowance.cmp(&minted) {
+        Ordering::Greater => {
+            let change = TypedState::Amount(allowance - minted);
+            builder = builder
+                .add_raw_state(allowance_id, change_seal, change)
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        }
+        Ordering::Less => {
+            return Err(NewPaymentError::NoPay(format!(
+                "insufficient inflation allowance of {contract_id}"
+            )))
+        }
+        Ordering::Equal => {}
+    }
+
+    let transition = builder
+        .add_global_state("issuedSupply", Amount::from(minted))
+        .and_then(|builder| builder.complete_transition(contract_id))
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+
+    let transitions = BTreeMap::from([(contract_id, transition)]);
+    anchor_transitions(
+        &mut psbt_final,
+        &prev_outputs,
+        transitions,
+        change_seal,
+        stock,
+    )?;
+
+    let psbt_file = Psbt::from_str(&PSBT::serialize(&psbt_final).to_hex())
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+    Ok((psbt_file, minted))
+}
+
+/// Burns state of the contract held by the PSBT inputs, assigning it to an `OP_RETURN` output
+/// added to the anchor transaction, which can never be spent. The fungible change and any other
+/// state of the inputs go to the last output, the tapret host. Returns the consignment of the
+/// burn (the burn proof), the unspendable output, the blinding of its seal and the amount
+/// burned. UDA tokens are burned whole.
+///
+/// None of the schemas issued by BitMask (NIA, UDA, CFA and IFA) define burn or replace rights,
+/// so there is no burn transition to use and every burn is a transfer to the unspendable output.
+pub fn burn_contract(
+    contract_id: &str,
+    iface: &str,
+    amount: u64,
+    psbt: String,
+    stock: &mut Stock,
+) -> Result<(Psbt, Bindle<Transfer>, u32, u64, u64), NewPaymentError> {
+    let contract_id = ContractId::from_str(contract_id).map_err(|_| NewPaymentError::Invalid)?;
+    let iface = TypeName::from_str(iface).map_err(|_| NewPaymentError::Invalid)?;
+    let psbt_file = Psbt::from_str(&psbt).map_err(|_| NewPaymentError::WrongHex)?;
+
+    let psbt = base64::decode(&base64::encode(&psbt_file.serialize()))
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+
+    let mut psbt_final =
+        PSBT::deserialize(&psbt).map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+
+    let mut prev_outputs = vec![];
+    for txin in &psbt_final.unsigned_tx.input {
+        let txid = Txid::from_str(&txin.previous_output.txid.to_string())
+            .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+        prev_outputs.push(Outpoint::new(txid, txin.previous_output.vout));
+    }
+
+    // Provably unspendable output receiving the burned state
+    let burn_vout = 0;
+    psbt_final.unsigned_tx.output.insert(
+        burn_vout as usize,
+        TxOut {
+            value: 0,
+            script_pubkey: ScriptBuf::from(vec![OP_RETURN.to_u8()]),
+        },
+    );
+    psbt_final
+        .outputs
+        .insert(burn_vout as usize, PsbtOutput::default());
+    let burn_seal = GraphSeal::new_vout(CloseMethod::TapretFirst, burn_vout);
+    let blinding = burn_seal.blinding;
+
+    let change_vout = psbt_final.outputs.len().saturating_sub(1) as u32;
+    let change_seal = GraphSeal::new_vout(CloseMethod::TapretFirst, change_vout);
+    let mut builder = stock
+        .transition_builder(contract_id, iface, None::<TypeName>)
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    let assignment_name = builder
+        .default_assignment()
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?
+        .clone();
+    let assignment_id = builder
+        .assignments_type(&assignment_name)
+        .ok_or(NewPaymentError::Invalid)?;
+
+    let mut burned = 0u64;
+    let mut sum_inputs = 0u64;
+    let spent = stock
+        .state_for_outpoints(contract_id, prev_outputs.iter().copied())
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    for (opout, state) in spent {
+        builder = builder
+            .add_input(opout)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        if opout.ty != assignment_id {
+            builder = builder
+                .add_raw_state(opout.ty, change_seal, state)
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        } else if let TypedState::Amount(value) = state {
+            sum_inputs += value;
+        } else {
+            builder = builder
+                .add_raw_state(assignment_id, burn_seal, state)
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            burned += 1;
+        }
+    }
+
+    if sum_inputs > 0 {
+        match sum_inputs.cmp(&amount) {
+            Ordering::Greater => {
+                let change = TypedState::Amount(sum_inputs - amount);
+                builder = builder
+                    .add_raw_state(assignment_id, change_seal, change)
+                    .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            }
+            Ordering::Less => {
+                return Err(NewPaymentError::NoPay(format!(
+                    "insufficient state of {contract_id}"
+                )))
+            }
+            Ordering::Equal => {}
+        }
+        builder = builder
+            .add_raw_state(assignment_id, burn_seal, TypedState::Amount(amount))
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        burned = amount;
+    } else if burned == 0 {
+        return Err(NewPaymentError::NoPay(format!(
+            "no state of {contract_id} to burn"
+        )));
+    }
+
+    let transition = builder
+        .complete_transition(contract_id)
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    let transitions = BTreeMap::from([(contract_id, transition)]);
+    anchor_transitions(
+        &mut psbt_final,
+        &prev_outputs,
+        transitions,
+        change_seal,
+        stock,
+    )?;
+
+    let witness_txid = Txid::from_str(&psbt_final.unsigned_tx.txid().to_string())
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+    let burn_seal = burn_seal.resolve(witness_txid).to_concealed_seal();
+    let transfer = stock
+        .transfer(contract_id, [burn_seal])
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+
+    let psbt_file = Psbt::from_str(&PSBT::serialize(&psbt_final).to_hex())
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+    Ok((psbt_file, transfer, burn_vout, blinding, burned))
+}
+
+/// Interface implemented by the contract, to build its blank transitions
+fn contract_iface_name(
+    contract_id: ContractId,
+    stock: &mut Stock,
+) -> Result<TypeName, NewPaymentError> {
+    for iface_name in ["RGB20", "RGB21", "RGB25"] {
+        let iface_id = match stock.iface_by_name(&TypeName::from(iface_name)) {
+            Ok(iface) => iface.iface_id(),
+            Err(_) => continue,
+        };
+        if stock.contract_iface(contract_id, iface_id).is_ok() {
+            return Ok(TypeName::from(iface_name));
+        }
+    }
+
+    Err(NewPaymentError::NoPay(format!(
+        "no known interface implemented by {contract_id}"
+    )))
+}
+
+/// Moves the other contracts allocated on the inputs to the change with blank transitions,
+/// built with the interface of each contract, then commits all the bundles with a single
+/// tapret commitment and consumes them.
+fn anchor_transitions(
+    psbt_final: &mut PSBT,
+    prev_outputs: &[Outpoint],
+    mut transitions: BTreeMap<ContractId, Transition>,
+    change_seal: GraphSeal,
+    stock: &mut Stock,
+) -> Result<(), NewPaymentError> {
+    let mut contract_inputs: HashMap<ContractId, Vec<Outpoint>> = HashMap::new();
+    let mut blank_state = BTreeMap::new();
+    for outpoint in prev_outputs {
+        let contract_ids = stock
+            .contracts_by_outpoints([*outpoint])
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        for id in contract_ids {
+            contract_inputs.entry(id).or_default().push(*outpoint);
+            if transitions.contains_key(&id) {
+                continue;
+            }
+            let state = stock
+                .state_for_outpoints(id, [*outpoint])
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            blank_state
+                .entry(id)
+                .or_insert_with(BTreeMap::new)
+                .extend(state);
+        }
+    }
+    for (id, opouts) in blank_state {
+        let blank_iface = contract_iface_name(id, stock)?;
+        let mut blank_builder = stock
+            .blank_builder(id, blank_iface)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        for (opout, state) in opouts {
+            blank_builder = blank_builder
+                .add_input(opout)
+                .and_then(|builder| builder.add_raw_state(opout.ty, change_seal, state))
+                .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        }
+        let transition = blank_builder
+            .complete_transition(id)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+        transitions.insert(id, transition);
+    }
+
+    for (id, transition) in transitions {
+        let inputs = contract_inputs.remove(&id).unwrap_or_default();
+        for (input, outpoint) in psbt_final.inputs.iter_mut().zip(prev_outputs) {
+            if inputs.contains(outpoint) {
+                input
+                    .set_rgb_consumer(id, transition.id())
+                    .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+            }
+        }
+        psbt_final
+            .push_rgb_transition(transition)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    }
+
+    // Single commitment for all the bundles
+    let bundles = psbt_final
+        .rgb_bundles()
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    psbt_final
+        .rgb_bundle_to_lnpbp4()
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    let anchor = psbt_final
+        .dbc_conclude(CloseMethod::TapretFirst)
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+
+    let witness_txid = Txid::from_str(&psbt_final.unsigned_tx.txid().to_string())
+        .map_err(|err| NewPaymentError::WrongPSBT(err.to_string()))?;
+    stock
+        .consume_anchor(anchor)
+        .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    for (id, bundle) in bundles {
+        stock
+            .consume_bundle(id, bundle, witness_txid)
+            .map_err(|err| NewPaymentError::NoPay(err.to_string()))?;
+    }
+
+    Ok(())
+}
+
 pub fn validate_transfer<R: ResolveTx>(
     transfer: String,
     resolver: &mut R,
@@ -215,6 +761,75 @@ where
     }
 }
 
+/// Verifies a burn proof: the consignment is valid and assigns state of the contract to an
+/// `OP_RETURN` output of its anchor transaction. The burn seal is concealed in the consignment,
+/// its blinding reveals it to the stock. Returns the contract and the amount burned.
+pub fn verify_burn<T>(
+    consig: String,
+    iface: &str,
+    tx: &bitcoin::Transaction,
+    vout: u32,
+    blinding: u64,
+    resolver: &mut T,
+    stock: &mut Stock,
+) -> Result<(ContractId, u64), AcceptTransferError>
+where
+    T: ResolveHeight + ResolveTx,
+    T::Error: 'static,
+{
+    let txid = Txid::from_str(&tx.txid().to_string())
+        .map_err(|err| AcceptTransferError::WrongConsig(err.to_string()))?;
+    let burn_outpoint = Outpoint::new(txid, vout);
+    let unspendable = tx
+        .output
+        .get(vout as usize)
+        .map(|txout| txout.script_pubkey.is_op_return())
+        .unwrap_or_default();
+    if !unspendable {
+        return Err(AcceptTransferError::NoBurn(burn_outpoint.to_string()));
+    }
+
+    let burn_seal = GraphSeal::with_blinding(CloseMethod::TapretFirst, txid, vout, blinding);
+    stock
+        .store_seal_secret(burn_seal)
+        .map_err(|err| AcceptTransferError::WrongConsig(err.to_string()))?;
+
+    let transfer = accept_transfer(consig, false, resolver, stock)?;
+    let contract_id = transfer.contract_id();
+
+    let iface_name = TypeName::from_str(iface)
+        .map_err(|err| AcceptTransferError::WrongConsig(err.to_string()))?;
+    let iface = stock
+        .iface_by_name(&iface_name)
+        .map_err(|err| AcceptTransferError::WrongConsig(err.to_string()))?;
+    let contract = stock
+        .contract_iface(contract_id, iface.iface_id())
+        .map_err(|err| AcceptTransferError::WrongContract(err.to_string()))?;
+
+    let mut burned = 0;
+    for owned in &contract.iface.assignments {
+        if let Ok(allocations) = contract.fungible(owned.name.clone(), &None) {
+            burned += allocations
+                .iter()
+                .filter(|allocation| allocation.owner == burn_outpoint)
+                .map(|allocation| allocation.value)
+                .sum::<u64>();
+        }
+        if let Ok(allocations) = contract.data(owned.name.clone()) {
+            burned += allocations
+                .iter()
+                .filter(|allocation| allocation.owner == burn_outpoint)
+                .count() as u64;
+        }
+    }
+
+    if burned == 0 {
+        return Err(AcceptTransferError::NoBurn(burn_outpoint.to_string()));
+    }
+
+    Ok((contract_id, burned))
+}
+
 pub fn extract_transfer(transfer: String) -> Result<(Txid, Bindle<Transfer>), AcceptTransferError> {
     let serialized = Vec::<u8>::from_hex(&transfer).map_err(|_| AcceptTransferError::WrongHex)?;
     let confined = Confined::try_from_iter(serialized.iter().copied())
@@ -1,5 +1,6 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Range,
     str::FromStr,
 };
 
@@ -14,6 +15,7 @@ use bitcoin_scripts::{
 use rgb::{DeriveInfo, MiningStatus, Resolver, RgbDescr, RgbWallet, SpkDescriptor, Tapret, Utxo};
 use rgbstd::{
     contract::ContractId,
+    interface::IfaceId,
     persistence::{Inventory, Stash, Stock},
     validation::ResolveTx,
 };
@@ -225,6 +227,33 @@ pub fn sync_wallet(iface_index: u32, wallet: &mut RgbWallet, resolver: &mut impl
         .resolve_utxo(new_scripts)
         .expect("service unavalible");
 
+    merge_utxos(wallet, new_utxos);
+}
+
+/// Registers the outputs received by the terminal scripts in `range`.
+/// Returns the outputs of the wallet in that range.
+pub fn discover_utxos(
+    iface_index: u32,
+    range: Range<u32>,
+    wallet: &mut RgbWallet,
+    resolver: &mut impl Resolver,
+) -> Result<Vec<Utxo>, anyhow::Error> {
+    let scripts = wallet.descr.derive(iface_index, range.clone());
+    let new_utxos = resolver.resolve_utxo(scripts).map_err(|err| anyhow!(err))?;
+    merge_utxos(wallet, new_utxos);
+
+    Ok(wallet
+        .utxos
+        .iter()
+        .filter(|utxo| {
+            utxo.derivation.terminal.app == iface_index
+                && range.contains(&utxo.derivation.terminal.index)
+        })
+        .cloned()
+        .collect())
+}
+
+fn merge_utxos(wallet: &mut RgbWallet, new_utxos: BTreeSet<Utxo>) {
     for mut new_utxo in new_utxos {
         if let Some(current_utxo) = wallet
             .utxos
@@ -313,6 +342,25 @@ where
     Ok(utxos)
 }
 
+/// Interfaces of the contracts held by the keychain. RGB25 assets share the fungible keychain.
+fn keychain_ifaces(iface_index: u32, stock: &Stock) -> Result<Vec<IfaceId>, anyhow::Error> {
+    let iface_names: &[&str] = match iface_index {
+        20 => &["RGB20", "RGB25"],
+        21 => &["RGB21"],
+        _ => &["Contract"],
+    };
+
+    let mut iface_ids = vec![];
+    for (index, iface_name) in iface_names.iter().enumerate() {
+        match stock.iface_by_name(&tn!(*iface_name)) {
+            Ok(iface) => iface_ids.push(iface.iface_id()),
+            Err(err) if index == 0 => return Err(err.into()),
+            Err(_) => {}
+        }
+    }
+    Ok(iface_ids)
+}
+
 pub fn list_allocations<T>(
     wallet: &mut RgbWallet,
     stock: &mut Stock,
@@ -322,19 +370,22 @@ pub fn list_allocations<T>(
 where
     T: ResolveSpent + Resolver,
 {
-    let iface_name = match iface_index {
-        20 => "RGB20",
-        21 => "RGB21",
-        _ => "Contract",
-    };
+    let iface_ids = keychain_ifaces(iface_index, stock)?;
 
     sync_wallet(iface_index, wallet, resolver);
     let mut details = vec![];
     for contract_id in stock.contract_ids()? {
-        let iface = stock.iface_by_name(&tn!(iface_name))?;
-        if let Ok(contract) = stock.contract_iface(contract_id, iface.iface_id()) {
+        if let Some(contract) = iface_ids
+            .iter()
+            .find_map(|iface_id| stock.contract_iface(contract_id, *iface_id).ok())
+        {
             let mut owners = vec![];
             for owned in &contract.iface.assignments {
+                // Inflation rights are not part of the balance
+                if owned.name.as_str() == "inflationAllowance" {
+                    continue;
+                }
+
                 if let Ok(allocations) = contract.fungible(owned.name.clone(), &None) {
                     for allocation in allocations {
                         let txid = bitcoin::Txid::from_str(&allocation.owner.txid.to_hex())
@@ -420,17 +471,20 @@ pub fn contract_allocations<T>(
 where
     T: ResolveSpent + Resolver,
 {
-    let iface_name = match iface_index {
-        20 => "RGB20",
-        21 => "RGB21",
-        _ => "Contract",
-    };
-    let iface = stock.iface_by_name(&tn!(iface_name))?;
+    let iface_ids = keychain_ifaces(iface_index, stock)?;
 
     let mut owners = vec![];
-    if let Ok(contract) = stock.contract_iface(contract_id, iface.iface_id()) {
+    if let Some(contract) = iface_ids
+        .iter()
+        .find_map(|iface_id| stock.contract_iface(contract_id, *iface_id).ok())
+    {
         sync_wallet(iface_index, wallet, resolver);
         for owned in &contract.iface.assignments {
+            // Inflation rights are not part of the balance
+            if owned.name.as_str() == "inflationAllowance" {
+                continue;
+            }
+
             if let Ok(allocations) = contract.fungible(owned.name.clone(), &None) {
                 for allocation in allocations {
                     let txid = bitcoin::Txid::from_str(&allocation.owner.txid.to_hex())
@@ -2,7 +2,10 @@ use bp::Outpoint;
 use garde::Validate;
 use rgb::MiningStatus;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 pub use bdk::{Balance, BlockTime, TransactionDetails};
@@ -24,6 +27,8 @@ pub struct WalletData {
     pub balance: Balance,
     pub transactions: Vec<WalletTransaction>,
     pub utxos: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<WalletLabel>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -35,6 +40,46 @@ pub struct WalletTransaction {
     pub fee: Option<u64>,
     pub confirmed: bool,
     pub confirmation_time: Option<BlockTime>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelType {
+    Tx,
+    Addr,
+    Pubkey,
+    Input,
+    Output,
+    Xpub,
+}
+
+/// Wallet label record (BIP-329)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WalletLabel {
+    /// Type of the labeled item
+    #[serde(rename = "type")]
+    pub label_type: LabelType,
+    /// Reference to the labeled item (txid, address, pubkey, {txid}:{vout} or xpub)
+    #[serde(rename = "ref")]
+    pub reference: String,
+    /// Label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Key origin of the wallet, as a descriptor fragment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// Output is spendable (only for outputs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spendable: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletLabelsResponse {
+    /// Labels of the wallet
+    pub labels: Vec<WalletLabel>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Zeroize, ZeroizeOnDrop, Display, Default)]
@@ -67,14 +112,119 @@ pub struct PublicWalletData {
     pub nostr_npub: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletAccount {
+    /// Name of the account
+    pub name: String,
+    /// Account index, hardened level of the derivation path
+    pub index: u32,
+    pub private: PrivateWalletData,
+    pub public: PublicWalletData,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Zeroize, ZeroizeOnDrop)]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptedWalletData {
+    pub mnemonic: String,
+    /// Default account (index 0)
+    pub private: PrivateWalletData,
+    pub public: PublicWalletData,
+    /// Named accounts of the same seed
+    #[serde(default)]
+    pub accounts: Vec<WalletAccount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Bip85Application {
+    /// BIP-39 mnemonic of 12, 18 or 24 words, in English
+    Mnemonic { words: u8 },
+    /// Private key in the wallet import format
+    Wif,
+    /// Extended private key
+    Xprv,
+    /// Hex entropy of 16 to 64 bytes
+    #[serde(rename_all = "camelCase")]
+    Hex { num_bytes: u8 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bip85Request {
+    pub application: Bip85Application,
+    /// Index of the child
+    pub index: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bip85Response {
+    /// Derivation path of the child
+    pub path: String,
+    /// Derived mnemonic, WIF, xprv or hex entropy
+    pub child: SecretString,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Slip39Group {
+    /// Shares of the group needed to recover it
+    pub member_threshold: u8,
+    /// Shares of the group
+    pub member_count: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Slip39SplitRequest {
+    /// Groups needed to recover the wallet
+    pub group_threshold: u8,
+    pub groups: Vec<Slip39Group>,
+    /// SLIP-39 passphrase encrypting the shares
+    #[serde(default)]
+    pub passphrase: Option<SecretString>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Slip39Shares {
+    /// Share mnemonics of each group
+    pub groups: Vec<Vec<SecretString>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Slip39RecoverRequest {
+    /// Share mnemonics, of any group
+    pub shares: Vec<SecretString>,
+    /// SLIP-39 passphrase the shares were split with
+    #[serde(default)]
+    pub passphrase: Option<SecretString>,
+    /// BIP-39 seed password of the wallet
+    pub seed_password: SecretString,
+}
+
+/// Wallet data encrypted before named accounts
+#[derive(Serialize, Deserialize, Clone, Debug, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedWalletDataV06 {
     pub mnemonic: String,
     pub private: PrivateWalletData,
     pub public: PublicWalletData,
 }
 
+impl From<DecryptedWalletDataV06> for DecryptedWalletData {
+    fn from(wallet_data: DecryptedWalletDataV06) -> Self {
+        Self {
+            mnemonic: wallet_data.mnemonic.clone(),
+            private: wallet_data.private.clone(),
+            public: wallet_data.public.clone(),
+            accounts: vec![],
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct EncryptedWalletDataV04 {
@@ -101,6 +251,262 @@ pub struct FundVaultDetails {
     pub is_funded: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedFundVaultResponse {
+    pub psbt: UnsignedPsbtResponse,
+    pub vault: FundVaultDetails,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSlotRequest {
+    /// Asset type the slots are reserved for (RGB20, RGB21 or RGB25)
+    pub asset_type: AssetType,
+    /// Number of outputs to create
+    pub count: u32,
+    /// Value of each output, in sats
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultUtxoPlanRequest {
+    /// Descriptor paying for the slots
+    pub descriptor: SecretString,
+    pub change_descriptor: SecretString,
+    /// RGB vault descriptors receiving the slots
+    pub rgb_assets_descriptor_xpub: SecretString,
+    pub rgb_udas_descriptor_xpub: SecretString,
+    pub slots: Vec<VaultSlotRequest>,
+    pub fee_rate: Option<FeeRateRequest>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSlotOutpoints {
+    pub asset_type: AssetType,
+    pub outpoints: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultUtxoPlanResponse {
+    /// Transaction paying the missing slots, none when the vault was already topped up
+    pub txid: Option<String>,
+    pub fee: Option<u64>,
+    pub slots: Vec<VaultSlotOutpoints>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeTarget {
+    /// Next block
+    Fast,
+    /// Within an hour
+    Medium,
+    /// Within a day
+    Slow,
+    /// Within a custom number of blocks
+    Blocks(u16),
+}
+
+impl FeeTarget {
+    pub fn blocks(&self) -> u16 {
+        match self {
+            Self::Fast => 1,
+            Self::Medium => 6,
+            Self::Slow => 144,
+            Self::Blocks(blocks) => *blocks,
+        }
+    }
+}
+
+impl FromStr for FeeTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fast" => Ok(Self::Fast),
+            "medium" => Ok(Self::Medium),
+            "slow" => Ok(Self::Slow),
+            blocks => blocks
+                .parse()
+                .map(Self::Blocks)
+                .map_err(|_| format!("invalid fee target: {blocks}")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRequest {
+    /// Key spending at any time, as a descriptor key or single-key `tr()` descriptor
+    pub primary: SecretString,
+    /// Key spending once the timelock matures
+    pub recovery: SecretString,
+    /// Relative timelock of the recovery path (blocks)
+    pub timelock: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultResponse {
+    /// Taproot descriptor of the vault, with the keys of the request
+    pub descriptor: SecretString,
+    pub wallet: WalletData,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRecoveryRequest {
+    /// Public primary key of the vault
+    pub primary: SecretString,
+    /// Private recovery key of the vault
+    pub recovery: SecretString,
+    /// Relative timelock of the recovery path (blocks)
+    pub timelock: u32,
+    /// Address receiving the recovered coins
+    pub destination: String,
+    #[serde(default)]
+    pub fee_rate: Option<FeeRateRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum FeeRateRequest {
+    /// Fee rate (sats/vB)
+    Rate(f32),
+    /// Confirmation target, resolved against the explorer fee estimates
+    Target(FeeTarget),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimatesResponse {
+    /// Fee rate for the fast target (sats/vB)
+    pub fast: f32,
+    /// Fee rate for the medium target (sats/vB)
+    pub medium: f32,
+    /// Fee rate for the slow target (sats/vB)
+    pub slow: f32,
+    /// Minimum relay fee rate (sats/vB)
+    pub min_relay: f32,
+    /// Fee rates by confirmation target in blocks (sats/vB)
+    pub estimates: BTreeMap<u16, f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BumpFeeRequest {
+    /// Descriptor of the wallet that sent the transaction
+    pub descriptor: SecretString,
+    /// Change descriptor of the wallet that sent the transaction
+    pub change_descriptor: SecretString,
+    /// Transaction id of the transaction to replace
+    pub txid: String,
+    /// New fee rate (sats/vB)
+    pub fee_rate: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BumpFeeResponse {
+    /// Transaction id of the replaced transaction
+    pub original_txid: String,
+    /// Transaction id of the replacement transaction
+    pub txid: String,
+    /// Fee paid by the replaced transaction (sats)
+    pub original_fee: Option<u64>,
+    /// Fee paid by the replacement transaction (sats)
+    pub fee: Option<u64>,
+    /// Fee rate of the replacement transaction (sats/vB)
+    pub fee_rate: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CpfpRequest {
+    /// Descriptor of the wallet receiving an output of the parent transaction
+    pub descriptor: SecretString,
+    /// Change descriptor of the wallet
+    pub change_descriptor: SecretString,
+    /// Transaction id of the unconfirmed parent transaction
+    pub txid: String,
+    /// Target fee rate of the package (sats/vB)
+    pub fee_rate: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CpfpResponse {
+    /// Transaction id of the parent transaction
+    pub parent_txid: String,
+    /// Transaction id of the child transaction
+    pub txid: String,
+    /// Fee paid by the parent transaction (sats)
+    pub parent_fee: u64,
+    /// Fee paid by the child transaction (sats)
+    pub fee: u64,
+    /// Virtual size of the parent transaction (vB)
+    pub parent_vsize: u64,
+    /// Virtual size of the child transaction (vB)
+    pub vsize: u64,
+    /// Fee rate of the parent and child package (sats/vB)
+    pub package_fee_rate: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinControl {
+    /// Outpoints that must be spent (format: {txid}:{vout})
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Outpoints that must not be spent (format: {txid}:{vout})
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Spend only the included outpoints
+    #[serde(default)]
+    pub manual: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FreezeUtxosRequest {
+    /// Outpoints (format: {txid}:{vout})
+    pub utxos: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FrozenUtxosResponse {
+    /// Outpoints frozen by the user
+    pub frozen: Vec<String>,
+    /// Outpoints frozen because they hold RGB allocations
+    pub rgb: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PayjoinReceiverRequest {
+    pub descriptor: SecretString,
+    pub change_descriptor: SecretString,
+    /// Amount requested in the BIP-21 URI (in sats)
+    pub amount: Option<u64>,
+    /// Outpoints the receiver may contribute. Frozen and RGB outpoints are always excluded
+    #[serde(default)]
+    pub coin_control: CoinControl,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PayjoinUriResponse {
+    /// Address receiving the payment
+    pub address: String,
+    /// BIP-21 URI with the pj= endpoint
+    pub uri: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IssueAssetRequest {
@@ -138,9 +544,65 @@ pub struct IssueRequest {
     /// The name of the iface (ex: RGB20)
     #[garde(alphanumeric)]
     pub iface: String,
-    /// contract metadata (only RGB21/UDA)
+    /// contract metadata (RGB21/UDA, or the RGB25/CFA attachment)
     #[garde(custom(verify_media_types))]
     pub meta: Option<IssueMetaRequest>,
+    /// Inflation allowance of the issuer (only RGB20). It is not enforced by RGB validation,
+    /// see `ifa_schema`.
+    #[serde(default)]
+    #[garde(dive)]
+    pub inflation: Option<InflationSeal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct InflationSeal {
+    /// Seal receiving the state
+    #[garde(ascii)]
+    #[garde(custom(verify_tapret_seal))]
+    pub seal: String,
+    /// Amount of the asset
+    #[garde(range(min = 1, max = u64::MAX))]
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct InflateRequest {
+    /// The contract id
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub contract_id: String,
+    /// Seals receiving the new supply
+    #[garde(length(min = 1, max = 999))]
+    pub beneficiaries: Vec<InflationSeal>,
+    /// Asset Descriptor
+    #[garde(custom(verify_descriptor))]
+    pub descriptor: SecretString,
+    /// Asset Terminal Change (receives the remaining inflation allowance)
+    #[garde(ascii)]
+    pub change_terminal: String,
+    /// Bitcoin Fee
+    #[garde(dive)]
+    pub fee: PsbtFeeRequest,
+    /// Bitcoin Change Addresses (format: {address}:{amount})
+    #[garde(length(min = 0, max = 999))]
+    pub bitcoin_changes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InflateResponse {
+    /// PSBT File Information with tapret (in hexadecimal)
+    pub psbt: String,
+    /// Tapret Commitment (used to spend output)
+    pub commit: String,
+    /// Amount of the asset minted
+    pub minted: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -329,6 +791,8 @@ pub enum AssetType {
     RGB20 = 20,
     #[serde(rename = "rgb21")]
     RGB21 = 21,
+    #[serde(rename = "rgb25")]
+    RGB25 = 25,
 }
 
 impl std::fmt::Display for AssetType {
@@ -370,6 +834,16 @@ pub struct ContractResponse {
     pub description: String,
     /// Amount of the asset
     pub supply: u64,
+    /// Amount of the asset issued, including secondary issuances
+    #[serde(default)]
+    pub issued_supply: u64,
+    /// Maximum amount of the asset: issued plus the remaining inflation allowance. It is only
+    /// respected by this library, RGB validation does not enforce it.
+    #[serde(default)]
+    pub max_supply: u64,
+    /// Amount of the asset burned by the user, once the anchor of the burn is seen by the explorer
+    #[serde(default)]
+    pub burned: u64,
     /// Precision of the asset
     pub precision: u8,
     /// The user contract balance
@@ -534,6 +1008,7 @@ pub struct PsbtInputRequest {
 pub enum PsbtFeeRequest {
     Value(#[garde(range(min = 0, max = u64::MAX))] u64),
     FeeRate(#[garde(skip)] f32),
+    Target(#[garde(skip)] FeeTarget),
 }
 
 impl Default for PsbtFeeRequest {
@@ -584,6 +1059,128 @@ pub struct PublishedPsbtResponse {
     pub txid: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Bip322Format {
+    /// Witness of the virtual transaction
+    #[default]
+    Simple,
+    /// Whole virtual transaction
+    Full,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageRequest {
+    /// Descriptor of the wallet, with its private key
+    pub descriptor: SecretString,
+    /// P2TR address of the wallet
+    pub address: String,
+    pub message: String,
+    #[serde(default)]
+    pub format: Bip322Format,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageResponse {
+    pub address: String,
+    pub message: String,
+    /// BIP-322 signature encoded in Base64
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMessageRequest {
+    pub address: String,
+    pub message: String,
+    /// BIP-322 signature encoded in Base64, in the simple or full format
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMessageResponse {
+    /// Signature is valid for the address and message?
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishPsbtRequest {
+    /// PSBT signed by an external signer, encoded in Base64 or hex
+    pub psbt: String,
+    /// Watch-only descriptor of the wallet
+    pub descriptor: SecretString,
+    /// Watch-only change descriptor of the wallet
+    pub change_descriptor: Option<SecretString>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedPsbtResponse {
+    /// Unsigned PSBT encoded in Base64
+    pub psbt: String,
+    /// Transaction id
+    pub txid: String,
+    /// Transaction fee (sats)
+    pub fee: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewInput {
+    /// Spent outpoint
+    pub outpoint: String,
+    /// Value of the spent output (sats)
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewOutput {
+    pub address: Option<String>,
+    /// Output value (sats)
+    pub amount: u64,
+    /// Output paid back to the wallet
+    pub is_change: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionPreview {
+    /// Unsigned PSBT encoded in Base64
+    pub psbt: String,
+    /// Transaction id
+    pub txid: String,
+    pub inputs: Vec<PreviewInput>,
+    pub outputs: Vec<PreviewOutput>,
+    /// Transaction fee (sats)
+    pub fee: u64,
+    /// Effective fee rate (sat/vB)
+    pub fee_rate: f32,
+    /// Estimated size of the signed transaction (vB)
+    pub vsize: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FundVaultPreview {
+    pub preview: TransactionPreview,
+    pub vault: FundVaultDetails,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPreviewRequest {
+    /// PSBT of the preview, encoded in Base64
+    pub psbt: String,
+    /// Descriptors signing the PSBT
+    pub descriptor: SecretString,
+    pub change_descriptor: Option<SecretString>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RgbTransferInternalParams {
@@ -640,6 +1237,152 @@ pub struct FullRgbTransferRequest {
     pub bitcoin_changes: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct RgbBurnRequest {
+    /// The contract id
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub contract_id: String,
+    /// The contract interface
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 32))]
+    pub iface: String,
+    /// Amount to burn (UDA burns the whole token)
+    #[garde(range(min = 1, max = u64::MAX))]
+    pub amount: u64,
+    /// Asset Descriptor
+    #[garde(custom(verify_descriptor))]
+    pub descriptor: SecretString,
+    /// Asset Terminal Change
+    #[garde(ascii)]
+    pub change_terminal: String,
+    /// Bitcoin Fee
+    #[garde(dive)]
+    pub fee: PsbtFeeRequest,
+    /// Bitcoin Change Addresses (format: {address}:{amount})
+    #[garde(length(min = 0, max = 999))]
+    pub bitcoin_changes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RgbBurnResponse {
+    /// PSBT File Information with tapret (in hexadecimal)
+    pub psbt: String,
+    /// Tapret Commitment (used to spend output)
+    pub commit: String,
+    /// Consignment ID of the burn
+    pub consig_id: String,
+    /// Consignment of the burn
+    pub consig: String,
+    /// Unspendable output receiving the burned state
+    pub vout: u32,
+    /// Amount of the asset burned
+    pub burned: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct RgbBurnProofRequest {
+    /// The contract id
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub contract_id: String,
+    /// Consignment ID of the burn
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub consig_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct RgbBurnProof {
+    /// The contract id
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub contract_id: String,
+    /// The contract interface
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 32))]
+    pub iface: String,
+    /// Consignment of the burn
+    #[garde(ascii)]
+    pub consig: String,
+    /// Anchor transaction of the burn (in hexadecimal)
+    #[garde(ascii)]
+    pub tx: String,
+    /// Unspendable output receiving the burned state
+    #[garde(skip)]
+    pub vout: u32,
+    /// Blinding of the burn seal, revealing it to the verifier
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 20))]
+    pub blinding: String,
+    /// Amount of the asset burned
+    #[garde(skip)]
+    pub burned: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RgbBurnVerifyResponse {
+    /// The contract id
+    pub contract_id: String,
+    /// Anchor transaction id of the burn
+    pub txid: String,
+    /// Amount of the asset provably burned
+    pub burned: u64,
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct RgbBatchInvoice {
+    /// The contract id
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub contract_id: String,
+    /// The contract interface
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 32))]
+    pub iface: String,
+    /// RGB Invoice
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 512))]
+    pub rgb_invoice: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct FullRgbBatchTransferRequest {
+    /// RGB Invoices paid by the transfer
+    #[garde(length(min = 1, max = 999))]
+    pub invoices: Vec<RgbBatchInvoice>,
+    /// Asset Descriptor
+    #[garde(custom(verify_descriptor))]
+    pub descriptor: SecretString,
+    /// Asset Terminal Change
+    #[garde(ascii)]
+    pub change_terminal: String,
+    /// Bitcoin Fee
+    #[garde(dive)]
+    pub fee: PsbtFeeRequest,
+    /// Bitcoin Change Addresses (format: {address}:{amount})
+    #[garde(length(min = 0, max = 999))]
+    pub bitcoin_changes: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[derive(Validate)]
@@ -682,6 +1425,121 @@ pub struct RgbTransferResponse {
     pub commit: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RgbBatchConsignment {
+    /// The contract id
+    pub contract_id: String,
+    /// RGB Invoice paid by the consignment
+    pub rgb_invoice: String,
+    /// Consignment ID
+    pub consig_id: String,
+    /// Consignment encoded (in hexadecimal)
+    pub consig: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RgbBatchTransferResponse {
+    /// PSBT File Information with tapret (in hexadecimal)
+    pub psbt: String,
+    /// Tapret Commitment (used to spend output)
+    pub commit: String,
+    /// One consignment per invoice
+    pub consignments: Vec<RgbBatchConsignment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct RgbAirdropRecipient {
+    /// RGB Invoice or blinded seal (utxob:...) of the recipient
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 512))]
+    pub beneficiary: String,
+    /// Amount of the asset
+    #[garde(range(min = 1, max = u64::MAX))]
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[derive(Validate)]
+#[garde(context(RGBContext))]
+pub struct RgbAirdropRequest {
+    /// The contract id
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 100))]
+    pub contract_id: String,
+    /// The contract interface
+    #[garde(ascii)]
+    #[garde(length(min = 0, max = 32))]
+    pub iface: String,
+    /// Recipients of the airdrop
+    #[garde(length(min = 1, max = 99999))]
+    pub recipients: Vec<RgbAirdropRecipient>,
+    /// Maximum recipients paid by one anchor transaction
+    #[garde(skip)]
+    pub chunk_size: Option<u16>,
+    /// Asset Descriptor
+    #[garde(custom(verify_descriptor))]
+    pub descriptor: SecretString,
+    /// Asset Terminal Change
+    #[garde(ascii)]
+    pub change_terminal: String,
+    /// Bitcoin Fee of each anchor transaction
+    #[garde(dive)]
+    pub fee: PsbtFeeRequest,
+    /// Bitcoin Change Addresses (format: {address}:{amount})
+    #[garde(length(min = 0, max = 999))]
+    pub bitcoin_changes: Vec<String>,
+    /// Descriptors signing the anchor transactions
+    #[garde(length(min = 1, max = 99))]
+    pub signers: Vec<SecretString>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RgbAirdropChunkStatus {
+    /// Not paid yet
+    Pending,
+    /// Anchor transaction signed, but not handed to the broadcast queue
+    Signed,
+    /// Anchor transaction in the broadcast queue, waiting for a retry of its broadcast
+    Queued,
+    /// Anchor transaction seen in the mempool by the explorer
+    Mempool,
+    /// Anchor transaction confirmed at this height
+    Confirmed(u32),
+    /// Anchor transaction refused by the node, the chunk is paid again on resume
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RgbAirdropChunk {
+    /// RGB Invoices paid by the chunk
+    pub invoices: Vec<String>,
+    /// Progress of the chunk
+    pub status: RgbAirdropChunkStatus,
+    /// Anchor transaction id
+    pub txid: Option<String>,
+    /// Anchor transaction (in hexadecimal)
+    pub tx: Option<String>,
+    /// One consignment per invoice
+    pub consignments: Vec<RgbBatchConsignment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RgbAirdropResponse {
+    /// Airdrop ID, derived from the contract and the recipients
+    pub airdrop_id: String,
+    /// Chunks of the airdrop, one anchor transaction each
+    pub chunks: Vec<RgbAirdropChunk>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[derive(Validate)]
@@ -1019,6 +1877,8 @@ pub enum TransferType {
     Received,
     #[serde(rename = "unknown")]
     Unknown,
+    #[serde(rename = "burned")]
+    Burned,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -1325,3 +2185,127 @@ impl From<RgbBid> for RgbBidDetail {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WalletSyncStatus {
+    Started,
+    Synced,
+    /// Sync failed with this error, other wallets keep syncing
+    Failed(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSyncEvent {
+    /// Key of the wallet in the wallet cache (hash of its descriptors)
+    pub wallet: String,
+    pub status: WalletSyncStatus,
+    /// Script pubkeys of the wallet checked against the chain
+    pub scanned_scripts: usize,
+    /// Chain tip height the wallet is synced to
+    pub tip_height: Option<u32>,
+    /// Job given to `sync_wallet_descriptors`, none for the syncs of `sync_wallets`
+    #[serde(default)]
+    pub job: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSyncRequest {
+    pub wallets: Vec<WalletSyncDescriptors>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSyncDescriptors {
+    pub descriptor: SecretString,
+    #[serde(default)]
+    pub change_descriptor: Option<SecretString>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSyncResponse {
+    /// Keys of the synced wallets, as found in their sync events
+    pub wallets: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletDiscoveryRequest {
+    pub descriptor: SecretString,
+    pub change_descriptor: SecretString,
+    /// RGB watcher of the wallet, "default" if missing
+    #[serde(default)]
+    pub watcher_name: Option<String>,
+    /// Consecutive unused addresses that end the scan of a keychain
+    #[serde(default)]
+    pub gap_limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredKeychain {
+    /// BTC keychain (bitcoin, change) or RGB terminal (contract, rgb20, rgb21)
+    pub asset_type: AssetType,
+    /// Addresses checked against the chain
+    pub scanned_addresses: u32,
+    /// Addresses that received at least one output
+    pub used_addresses: u32,
+    pub last_used_index: Option<u32>,
+    pub received_outputs: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletDiscoveryResponse {
+    pub gap_limit: u32,
+    pub bitcoin: Vec<DiscoveredKeychain>,
+    /// Empty when the wallet has no RGB watcher
+    pub rgb: Vec<DiscoveredKeychain>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BroadcastStatus {
+    /// Not broadcast yet, or dropped from the mempool before confirming
+    Pending,
+    /// Seen in the mempool
+    Mempool,
+    /// Confirmed at this height
+    Confirmed(u32),
+    /// Given up after too many failed broadcasts
+    Failed,
+    /// Refused by the node, it is not broadcast again
+    Rejected,
+    /// Replaced by a fee bump with this txid, it is not broadcast again
+    Replaced(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedTransaction {
+    pub txid: String,
+    /// Raw transaction in hex
+    pub tx: String,
+    pub status: BroadcastStatus,
+    /// Broadcast attempts, rebroadcasts included
+    pub attempts: u32,
+    /// Unix timestamp after which a pending transaction is broadcast again
+    pub next_attempt: i64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastQueueRequest {
+    pub descriptor: SecretString,
+    pub change_descriptor: Option<SecretString>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastQueueResponse {
+    pub transactions: Vec<QueuedTransaction>,
+}
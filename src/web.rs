@@ -1,7 +1,12 @@
 use crate::structs::{
-    AcceptRequest, FullRgbTransferRequest, ImportRequest, InvoiceRequest, IssueRequest,
-    PsbtRequest, ReIssueRequest, RgbBidRequest, RgbOfferRequest, RgbRemoveTransferRequest,
-    RgbSaveTransferRequest, RgbSwapRequest, RgbTransferRequest, SecretString, SignPsbtRequest,
+    AcceptRequest, Bip85Request, BroadcastQueueRequest, CoinControl, ConfirmPreviewRequest,
+    FeeRateRequest, FeeTarget, FreezeUtxosRequest, FullRgbBatchTransferRequest,
+    FullRgbTransferRequest, ImportRequest, InflateRequest, InvoiceRequest, IssueRequest,
+    PsbtRequest, PublishPsbtRequest, ReIssueRequest, RgbAirdropRequest, RgbBidRequest,
+    RgbBurnProof, RgbBurnProofRequest, RgbBurnRequest, RgbOfferRequest, RgbRemoveTransferRequest,
+    RgbSaveTransferRequest, RgbSwapRequest, RgbTransferRequest, SecretString, SignMessageRequest,
+    SignPsbtRequest, Slip39RecoverRequest, Slip39SplitRequest, VaultRecoveryRequest, VaultRequest,
+    VaultUtxoPlanRequest, VerifyMessageRequest, WalletDiscoveryRequest, WalletLabel,
     WatcherRequest,
 };
 // use crate::{carbonado, lightning, rgb};
@@ -109,8 +114,25 @@ pub mod constants {
 }
 
 pub mod bitcoin {
+    use std::str::FromStr;
+
+    use tokio::sync::broadcast::error::RecvError;
+    use wasm_bindgen_futures::spawn_local;
+
     use super::*;
 
+    fn fee_rate_request(
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Result<Option<FeeRateRequest>, JsValue> {
+        match fee_target {
+            Some(fee_target) => FeeTarget::from_str(&fee_target)
+                .map(|target| Some(FeeRateRequest::Target(target)))
+                .map_err(JsValue::from_string),
+            None => Ok(fee_rate.map(FeeRateRequest::Rate)),
+        }
+    }
+
     #[wasm_bindgen]
     pub fn hash_password(password: String) -> String {
         set_panic_hook();
@@ -135,14 +157,108 @@ pub mod bitcoin {
     }
 
     #[wasm_bindgen]
-    pub fn decrypt_wallet(hash: String, encrypted_descriptors: String) -> Promise {
+    pub fn decrypt_wallet(
+        hash: String,
+        encrypted_descriptors: String,
+        account: Option<String>,
+    ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
             match crate::bitcoin::decrypt_wallet(
                 &SecretString(hash),
                 &SecretString(encrypted_descriptors),
-            ) {
+            )
+            .and_then(|wallet_data| {
+                crate::bitcoin::select_account(&wallet_data, account.as_deref())
+            }) {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn add_account(
+        hash: String,
+        encrypted_descriptors: String,
+        seed_password: String,
+        name: String,
+        index: u32,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            match crate::bitcoin::add_account(
+                &SecretString(hash),
+                &SecretString(encrypted_descriptors),
+                &SecretString(seed_password),
+                &name,
+                index,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn get_bip85(mnemonic: String, seed_password: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let bip85_req: Bip85Request = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::get_bip85(
+                &SecretString(mnemonic),
+                &SecretString(seed_password),
+                &bip85_req.application,
+                bip85_req.index,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn split_mnemonic(mnemonic: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let split_req: Slip39SplitRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::split_mnemonic(&SecretString(mnemonic), &split_req) {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn recover_mnemonic(request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let recover_req: Slip39RecoverRequest =
+                serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::recover_mnemonic(
+                &recover_req.shares,
+                recover_req.passphrase.as_ref(),
+                &recover_req.seed_password,
+            )
+            .await
+            {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -212,11 +328,16 @@ pub mod bitcoin {
     }
 
     #[wasm_bindgen]
-    pub fn get_wallet_data(descriptor: String, change_descriptor: Option<String>) -> Promise {
+    pub fn get_wallet_data(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: Option<String>,
+    ) -> Promise {
         set_panic_hook();
         future_to_promise(async move {
             let change_descriptor = change_descriptor.map(SecretString);
             match crate::bitcoin::get_wallet_data(
+                &nostr_hex_sk,
                 &SecretString(descriptor),
                 change_descriptor.as_ref(),
             )
@@ -244,6 +365,44 @@ pub mod bitcoin {
         })
     }
 
+    #[wasm_bindgen]
+    pub fn discover_wallet(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let discovery_req: WalletDiscoveryRequest =
+                serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::discover_wallet(&nostr_hex_sk, discovery_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    /// Calls `callback` with every wallet sync event, as a JSON string
+    #[wasm_bindgen]
+    pub fn subscribe_sync_events(callback: js_sys::Function) {
+        set_panic_hook();
+
+        let mut events = crate::bitcoin::subscribe_sync_events();
+        spawn_local(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        let event = JsValue::from_string(serde_json::to_string(&event).unwrap());
+                        if let Err(err) = callback.call1(&JsValue::NULL, &event) {
+                            crate::warn!(format!("Sync event callback failed: {err:?}"));
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
     #[wasm_bindgen]
     pub fn get_new_address(descriptor: String, change_descriptor: Option<String>) -> Promise {
         set_panic_hook();
@@ -264,22 +423,19 @@ pub mod bitcoin {
     }
 
     #[wasm_bindgen]
-    pub fn send_sats(
+    pub fn create_payjoin_uri(
         descriptor: String,
         change_descriptor: String,
-        destination: String,
-        amount: u64,
-        fee_rate: Option<f32>,
+        amount: Option<u64>,
+        endpoint: String,
     ) -> Promise {
         set_panic_hook();
-
         future_to_promise(async move {
-            match crate::bitcoin::send_sats(
+            match crate::bitcoin::create_payjoin_uri(
                 &SecretString(descriptor),
                 &SecretString(change_descriptor),
-                &destination,
                 amount,
-                fee_rate,
+                &endpoint,
             )
             .await
             {
@@ -292,22 +448,30 @@ pub mod bitcoin {
     }
 
     #[wasm_bindgen]
-    pub fn drain_wallet(
-        destination: String,
-        descriptor: String,
-        change_descriptor: Option<String>,
-        fee_rate: Option<f32>,
-    ) -> Promise {
+    pub fn list_wallet_labels(nostr_hex_sk: String, descriptor: String) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let change_descriptor = change_descriptor.map(SecretString);
+            match crate::bitcoin::list_wallet_labels(&nostr_hex_sk, &SecretString(descriptor)).await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
 
-            match crate::bitcoin::drain_wallet(
-                &destination,
+    #[wasm_bindgen]
+    pub fn set_wallet_labels(nostr_hex_sk: String, descriptor: String, labels: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let labels: Vec<WalletLabel> = serde_wasm_bindgen::from_value(labels).unwrap();
+            match crate::bitcoin::set_wallet_labels(
+                &nostr_hex_sk,
                 &SecretString(descriptor),
-                change_descriptor.as_ref(),
-                fee_rate,
+                labels,
             )
             .await
             {
@@ -320,26 +484,18 @@ pub mod bitcoin {
     }
 
     #[wasm_bindgen]
-    pub fn fund_vault(
+    pub fn import_wallet_labels(
+        nostr_hex_sk: String,
         descriptor: String,
-        change_descriptor: String,
-        asset_address_1: String,
-        asset_address_2: String,
-        uda_address_1: String,
-        uda_address_2: String,
-        fee_rate: Option<f32>,
+        jsonl: String,
     ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            match crate::bitcoin::fund_vault(
+            match crate::bitcoin::import_wallet_labels(
+                &nostr_hex_sk,
                 &SecretString(descriptor),
-                &SecretString(change_descriptor),
-                &asset_address_1,
-                &asset_address_2,
-                &uda_address_1,
-                &uda_address_2,
-                fee_rate,
+                &jsonl,
             )
             .await
             {
@@ -352,19 +508,25 @@ pub mod bitcoin {
     }
 
     #[wasm_bindgen]
-    pub fn get_assets_vault(
-        rgb_assets_descriptor_xpub: String,
-        rgb_udas_descriptor_xpub: String,
-    ) -> Promise {
+    pub fn export_wallet_labels(nostr_hex_sk: String, descriptor: String) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            match crate::bitcoin::get_assets_vault(
-                &SecretString(rgb_assets_descriptor_xpub),
-                &SecretString(rgb_udas_descriptor_xpub),
-            )
-            .await
+            match crate::bitcoin::export_wallet_labels(&nostr_hex_sk, &SecretString(descriptor))
+                .await
             {
+                Ok(result) => Ok(JsValue::from_string(result)),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn get_fee_estimates() -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            match crate::bitcoin::get_fee_estimates().await {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -372,19 +534,31 @@ pub mod bitcoin {
             }
         })
     }
-}
-
-pub mod rgb {
-    use super::*;
 
-    #[allow(clippy::too_many_arguments)]
     #[wasm_bindgen]
-    pub fn issue_contract(nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn send_sats(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        destination: String,
+        amount: u64,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let req: IssueRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::rgb::issue_contract(&nostr_hex_sk, req).await {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            match crate::bitcoin::send_sats(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &destination,
+                amount,
+                fee_rate,
+            )
+            .await
+            {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -393,14 +567,34 @@ pub mod rgb {
         })
     }
 
-    #[allow(clippy::too_many_arguments)]
     #[wasm_bindgen]
-    pub fn reissue_contract(nostr_hex_sk: String, request: JsValue) -> Promise {
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_sats_with_coin_control(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        destination: String,
+        amount: u64,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+        coin_control: JsValue,
+    ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let req: ReIssueRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::rgb::reissue_contract(&nostr_hex_sk, req).await {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            let coin_control: CoinControl = serde_wasm_bindgen::from_value(coin_control).unwrap();
+            match crate::bitcoin::send_sats_with_coin_control(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &destination,
+                amount,
+                fee_rate,
+                coin_control,
+            )
+            .await
+            {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -410,12 +604,11 @@ pub mod rgb {
     }
 
     #[wasm_bindgen]
-    pub fn rgb_create_invoice(nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn list_frozen_utxos(nostr_hex_sk: String) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let req: InvoiceRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::rgb::create_invoice(&nostr_hex_sk, req).await {
+            match crate::bitcoin::list_frozen_utxos(&nostr_hex_sk).await {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -425,12 +618,12 @@ pub mod rgb {
     }
 
     #[wasm_bindgen]
-    pub fn create_psbt(nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn freeze_utxos(nostr_hex_sk: String, request: JsValue) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let psbt_req: PsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::rgb::create_psbt(&nostr_hex_sk, psbt_req).await {
+            let req: FreezeUtxosRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::freeze_utxos(&nostr_hex_sk, req.utxos).await {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -440,12 +633,12 @@ pub mod rgb {
     }
 
     #[wasm_bindgen]
-    pub fn psbt_sign_file(_nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn unfreeze_utxos(nostr_hex_sk: String, request: JsValue) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let psbt_req: SignPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::bitcoin::sign_psbt_file(psbt_req).await {
+            let req: FreezeUtxosRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::unfreeze_utxos(&nostr_hex_sk, req.utxos).await {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -455,12 +648,25 @@ pub mod rgb {
     }
 
     #[wasm_bindgen]
-    pub fn psbt_sign_and_publish_file(_nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn bump_fee(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        txid: String,
+        fee_rate: f32,
+    ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let psbt_req: SignPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::bitcoin::sign_and_publish_psbt_file(psbt_req).await {
+            match crate::bitcoin::bump_fee(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &txid,
+                fee_rate,
+            )
+            .await
+            {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -470,12 +676,25 @@ pub mod rgb {
     }
 
     #[wasm_bindgen]
-    pub fn transfer_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn cpfp(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        txid: String,
+        fee_rate: f32,
+    ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let pay_req: RgbTransferRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::rgb::transfer_asset(&nostr_hex_sk, pay_req).await {
+            match crate::bitcoin::cpfp(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &txid,
+                fee_rate,
+            )
+            .await
+            {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
@@ -485,12 +704,659 @@ pub mod rgb {
     }
 
     #[wasm_bindgen]
-    pub fn full_transfer_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+    pub fn drain_wallet(
+        nostr_hex_sk: String,
+        destination: String,
+        descriptor: String,
+        change_descriptor: Option<String>,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
         set_panic_hook();
 
         future_to_promise(async move {
-            let pay_req: FullRgbTransferRequest = serde_wasm_bindgen::from_value(request).unwrap();
-            match crate::rgb::full_transfer_asset(&nostr_hex_sk, pay_req).await {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            let change_descriptor = change_descriptor.map(SecretString);
+
+            match crate::bitcoin::drain_wallet(
+                &nostr_hex_sk,
+                &destination,
+                &SecretString(descriptor),
+                change_descriptor.as_ref(),
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fund_vault(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        asset_address_1: String,
+        asset_address_2: String,
+        uda_address_1: String,
+        uda_address_2: String,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            match crate::bitcoin::fund_vault(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &asset_address_1,
+                &asset_address_2,
+                &uda_address_1,
+                &uda_address_2,
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn build_send_sats(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        destination: String,
+        amount: u64,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            match crate::bitcoin::build_send_sats(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &destination,
+                amount,
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn build_drain_wallet(
+        nostr_hex_sk: String,
+        destination: String,
+        descriptor: String,
+        change_descriptor: Option<String>,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            let change_descriptor = change_descriptor.map(SecretString);
+
+            match crate::bitcoin::build_drain_wallet(
+                &nostr_hex_sk,
+                &destination,
+                &SecretString(descriptor),
+                change_descriptor.as_ref(),
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_fund_vault(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        asset_address_1: String,
+        asset_address_2: String,
+        uda_address_1: String,
+        uda_address_2: String,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            match crate::bitcoin::build_fund_vault(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &asset_address_1,
+                &asset_address_2,
+                &uda_address_1,
+                &uda_address_2,
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn preview_send_sats(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        destination: String,
+        amount: u64,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            match crate::bitcoin::preview_send_sats(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &destination,
+                amount,
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn preview_drain_wallet(
+        nostr_hex_sk: String,
+        destination: String,
+        descriptor: String,
+        change_descriptor: Option<String>,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            let change_descriptor = change_descriptor.map(SecretString);
+
+            match crate::bitcoin::preview_drain_wallet(
+                &nostr_hex_sk,
+                &destination,
+                &SecretString(descriptor),
+                change_descriptor.as_ref(),
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn preview_fund_vault(
+        nostr_hex_sk: String,
+        descriptor: String,
+        change_descriptor: String,
+        asset_address_1: String,
+        asset_address_2: String,
+        uda_address_1: String,
+        uda_address_2: String,
+        fee_rate: Option<f32>,
+        fee_target: Option<String>,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let fee_rate = fee_rate_request(fee_rate, fee_target)?;
+            match crate::bitcoin::preview_fund_vault(
+                &nostr_hex_sk,
+                &SecretString(descriptor),
+                &SecretString(change_descriptor),
+                &asset_address_1,
+                &asset_address_2,
+                &uda_address_1,
+                &uda_address_2,
+                fee_rate,
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn confirm_preview(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let confirm_req: ConfirmPreviewRequest =
+                serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::confirm_preview(&nostr_hex_sk, confirm_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn plan_vault_utxos(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let plan_req: VaultUtxoPlanRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::plan_vault_utxos(&nostr_hex_sk, plan_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn publish_psbt_file(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let psbt_req: PublishPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::publish_psbt_file(&nostr_hex_sk, psbt_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn queue_psbt_file(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let psbt_req: PublishPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::queue_psbt_file(&nostr_hex_sk, psbt_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn sign_and_queue_psbt_file(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let psbt_req: SignPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::sign_and_queue_psbt_file(&nostr_hex_sk, psbt_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn get_broadcast_queue(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let queue_req: BroadcastQueueRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::get_broadcast_queue(&nostr_hex_sk, queue_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn sign_message(request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let sign_req: SignMessageRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::sign_bip322(sign_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn verify_message(request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let verify_req: VerifyMessageRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::verify_bip322(verify_req) {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn create_vault(request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let vault_req: VaultRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::create_vault(vault_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn recover_vault(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let recovery_req: VaultRecoveryRequest =
+                serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::recover_vault(&nostr_hex_sk, recovery_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn get_assets_vault(
+        rgb_assets_descriptor_xpub: String,
+        rgb_udas_descriptor_xpub: String,
+    ) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            match crate::bitcoin::get_assets_vault(
+                &SecretString(rgb_assets_descriptor_xpub),
+                &SecretString(rgb_udas_descriptor_xpub),
+            )
+            .await
+            {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+}
+
+pub mod rgb {
+    use super::*;
+
+    #[allow(clippy::too_many_arguments)]
+    #[wasm_bindgen]
+    pub fn issue_contract(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let req: IssueRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::issue_contract(&nostr_hex_sk, req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[wasm_bindgen]
+    pub fn reissue_contract(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let req: ReIssueRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::reissue_contract(&nostr_hex_sk, req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn rgb_create_invoice(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let req: InvoiceRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::create_invoice(&nostr_hex_sk, req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn create_psbt(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let psbt_req: PsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::create_psbt(&nostr_hex_sk, psbt_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn psbt_sign_file(_nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let psbt_req: SignPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::sign_psbt_file(psbt_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn psbt_sign_and_publish_file(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let psbt_req: SignPsbtRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::bitcoin::sign_and_publish_psbt_file(&nostr_hex_sk, psbt_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn transfer_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let pay_req: RgbTransferRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::transfer_asset(&nostr_hex_sk, pay_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn full_transfer_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let pay_req: FullRgbTransferRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::full_transfer_asset(&nostr_hex_sk, pay_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn full_batch_transfer_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let pay_req: FullRgbBatchTransferRequest =
+                serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::full_batch_transfer_asset(&nostr_hex_sk, pay_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn inflate_contract(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let inflate_req: InflateRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::inflate_contract(&nostr_hex_sk, inflate_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn burn_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let burn_req: RgbBurnRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::burn_asset(&nostr_hex_sk, burn_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn burn_proof(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let proof_req: RgbBurnProofRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::burn_proof(&nostr_hex_sk, proof_req).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn verify_burn_proof(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let proof: RgbBurnProof = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::verify_burn_proof(&nostr_hex_sk, proof).await {
+                Ok(result) => Ok(JsValue::from_string(
+                    serde_json::to_string(&result).unwrap(),
+                )),
+                Err(err) => Err(JsValue::from_string(err.to_string())),
+            }
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn airdrop_asset(nostr_hex_sk: String, request: JsValue) -> Promise {
+        set_panic_hook();
+
+        future_to_promise(async move {
+            let airdrop_req: RgbAirdropRequest = serde_wasm_bindgen::from_value(request).unwrap();
+            match crate::rgb::airdrop_asset(&nostr_hex_sk, airdrop_req).await {
                 Ok(result) => Ok(JsValue::from_string(
                     serde_json::to_string(&result).unwrap(),
                 )),
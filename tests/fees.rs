@@ -0,0 +1,55 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{check_fee_rate, BitcoinFeesError, MAX_FEE_RATE, MIN_RELAY_FEE_RATE},
+    structs::{FeeRateRequest, FeeTarget, PsbtFeeRequest},
+    util::init_logging,
+};
+
+#[tokio::test]
+async fn fee_rate_limits() -> Result<()> {
+    init_logging("fees=info");
+
+    assert_eq!(check_fee_rate(0.5)?, MIN_RELAY_FEE_RATE);
+    assert_eq!(check_fee_rate(12.5)?, 12.5);
+    assert!(matches!(
+        check_fee_rate(MAX_FEE_RATE + 1.0),
+        Err(BitcoinFeesError::FeeRateTooHigh(_))
+    ));
+    assert!(matches!(
+        check_fee_rate(-1.0),
+        Err(BitcoinFeesError::InvalidFeeRate(_))
+    ));
+    assert!(matches!(
+        check_fee_rate(f32::NAN),
+        Err(BitcoinFeesError::InvalidFeeRate(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fee_targets() -> Result<()> {
+    init_logging("fees=info");
+
+    assert_eq!(FeeTarget::from_str("fast"), Ok(FeeTarget::Fast));
+    assert_eq!(FeeTarget::from_str("medium"), Ok(FeeTarget::Medium));
+    assert_eq!(FeeTarget::from_str("slow"), Ok(FeeTarget::Slow));
+    assert_eq!(FeeTarget::from_str("3"), Ok(FeeTarget::Blocks(3)));
+    assert!(FeeTarget::from_str("soon").is_err());
+
+    let fee_rate: FeeRateRequest = serde_json::from_str("1.5")?;
+    assert_eq!(fee_rate, FeeRateRequest::Rate(1.5));
+    let fee_rate: FeeRateRequest = serde_json::from_str("\"fast\"")?;
+    assert_eq!(fee_rate, FeeRateRequest::Target(FeeTarget::Fast));
+    let fee_rate: FeeRateRequest = serde_json::from_str("{\"blocks\":12}")?;
+    assert_eq!(fee_rate, FeeRateRequest::Target(FeeTarget::Blocks(12)));
+
+    let fee: PsbtFeeRequest = serde_json::from_str("{\"target\":\"slow\"}")?;
+    assert!(matches!(fee, PsbtFeeRequest::Target(FeeTarget::Slow)));
+
+    Ok(())
+}
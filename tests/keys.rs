@@ -1,10 +1,15 @@
 #![cfg(not(target_arch = "wasm32"))]
 
+use std::str::FromStr;
+
 use anyhow::Result;
+use bitcoin::util::bip32::ExtendedPrivKey;
 use bitmask_core::{
-    bitcoin::{get_wallet_data, save_mnemonic},
+    bitcoin::{
+        compile_vault_descriptor, derive_bip85, get_account, get_wallet_data, save_mnemonic,
+    },
     constants::switch_network,
-    structs::SecretString,
+    structs::{Bip85Application, SecretString},
     util::init_logging,
 };
 
@@ -24,6 +29,7 @@ pub async fn taproot() -> Result<()> {
     .await?;
 
     let wallet_data = get_wallet_data(
+        &decrypted_wallet.private.nostr_prv,
         &SecretString(decrypted_wallet.public.btc_descriptor_xpub.to_owned()),
         None,
     )
@@ -44,6 +50,56 @@ pub async fn taproot() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+pub async fn accounts() -> Result<()> {
+    init_logging("nostr_tests=debug");
+
+    const MNEMONIC: &str =
+        "empty faculty salute fortune select asthma attract question violin movie smile erupt half step lion deposit render stumble double mobile fossil height usual topple";
+
+    switch_network("bitcoin").await?;
+
+    let seed_password = SecretString("".to_owned());
+    let decrypted_wallet =
+        save_mnemonic(&SecretString(MNEMONIC.to_owned()), &seed_password).await?;
+    let default_account = get_account(
+        &SecretString(MNEMONIC.to_owned()),
+        &seed_password,
+        "personal",
+        0,
+    )
+    .await?;
+    let business_account = get_account(
+        &SecretString(MNEMONIC.to_owned()),
+        &seed_password,
+        "business",
+        1,
+    )
+    .await?;
+
+    assert_eq!(
+        default_account.public.btc_descriptor_xpub, decrypted_wallet.public.btc_descriptor_xpub,
+        "account 0 is the default account"
+    );
+    assert!(
+        business_account
+            .public
+            .btc_descriptor_xpub
+            .starts_with("tr([496f1ccc/86'/0'/1']xpub"),
+        "account index is the hardened account level"
+    );
+    assert!(business_account
+        .public
+        .rgb_assets_descriptor_xpub
+        .ends_with("/20/*)"));
+    assert_ne!(
+        business_account.public.btc_descriptor_xpub,
+        default_account.public.btc_descriptor_xpub
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 pub async fn nip06() -> Result<()> {
     init_logging("nostr_tests=debug");
@@ -73,3 +129,83 @@ pub async fn nip06() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+pub async fn bip85() -> Result<()> {
+    init_logging("nostr_tests=debug");
+
+    // Test vectors of BIP-85
+    let xprv = ExtendedPrivKey::from_str("xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb")?;
+
+    let vectors = [
+        (
+            Bip85Application::Mnemonic { words: 12 },
+            "m/83696968'/39'/0'/12'/0'",
+            "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose",
+        ),
+        (
+            Bip85Application::Mnemonic { words: 18 },
+            "m/83696968'/39'/0'/18'/0'",
+            "near account window bike charge season chef number sketch tomorrow excuse sniff circle vital hockey outdoor supply token",
+        ),
+        (
+            Bip85Application::Mnemonic { words: 24 },
+            "m/83696968'/39'/0'/24'/0'",
+            "puppy ocean match cereal symbol another shed magic wrap hammer bulb intact gadget divorce twin tonight reason outdoor destroy simple truth cigar social volcano",
+        ),
+        (
+            Bip85Application::Wif,
+            "m/83696968'/2'/0'",
+            "Kzyv4uF39d4Jrw2W7UryTHwZr1zQVNk4dAFyqE6BuMrMh1Za7uhp",
+        ),
+        (
+            Bip85Application::Xprv,
+            "m/83696968'/32'/0'",
+            "xprv9s21ZrQH143K2srSbCSg4m4kLvPMzcWydgmKEnMmoZUurYuBuYG46c6P71UGXMzmriLzCCBvKQWBUv3vPB3m1SATMhp3uEjXHJ42jFg7myX",
+        ),
+        (
+            Bip85Application::Hex { num_bytes: 64 },
+            "m/83696968'/128169'/64'/0'",
+            "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f878555d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c",
+        ),
+    ];
+
+    for (application, path, child) in vectors {
+        let derived = derive_bip85(&xprv, &application, 0)?;
+        assert_eq!(derived.path, path);
+        assert_eq!(derived.child.0, child, "{application:?} child is derived");
+    }
+
+    assert!(derive_bip85(&xprv, &Bip85Application::Mnemonic { words: 15 }, 0).is_err());
+    assert!(derive_bip85(&xprv, &Bip85Application::Hex { num_bytes: 8 }, 0).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn vault_descriptor() -> Result<()> {
+    init_logging("nostr_tests=debug");
+
+    let primary = "[496f1ccc/86'/0'/0']xpub6CBkARCPxmbRjaxzHxC38e9sKUVtMTRFqBYUFdXAHFBpeQzJz6mYSaQ1qSvCrNzYUNuvpD9FS6fmK9YowdCxaiCUSpjzNm5hvV2JxEodZ1q/0/*";
+    let recovery = "[496f1ccc/86'/0'/1']xpub6CBkARCPxmbRjaxzHxC38e9sKUVtMTRFqBYUFdXAHFBpeQzJz6mYSaQ1qSvCrNzYUNuvpD9FS6fmK9YowdCxaiCUSpjzNm5hvV2JxEodZ1q/0/*";
+
+    let descriptor = compile_vault_descriptor(
+        &SecretString(format!("tr({primary})")),
+        &SecretString(recovery.to_owned()),
+        144,
+    )?;
+
+    assert_eq!(
+        descriptor.0,
+        format!("tr({primary},and_v(v:pk({recovery}),older(144)))"),
+        "primary key is the internal key, recovery key is timelocked"
+    );
+    assert!(compile_vault_descriptor(
+        &SecretString(primary.to_owned()),
+        &SecretString(recovery.to_owned()),
+        70_000
+    )
+    .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{
+        decrypt_wallet, export_wallet_labels, format_labels, get_wallet_data, hash_password,
+        import_wallet_labels, list_frozen_utxos, list_wallet_labels, new_wallet, parse_labels,
+        set_wallet_labels,
+    },
+    structs::{LabelType, SecretString, WalletLabel},
+    util::init_logging,
+};
+
+const ENCRYPTION_PASSWORD: &str = "hunter2";
+const SEED_PASSWORD: &str = "";
+
+// Records from the BIP-329 specification
+const BIP329_LABELS: &str = r#"{"type":"tx","ref":"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd","label":"Transaction","origin":"wpkh([d34db33f/84'/0'/0'])"}
+{"type":"addr","ref":"bc1q34aq5drpuwy3wgl9lhup9892qp6svr8ldzyy7c","label":"Address"}
+{"type":"pubkey","ref":"0283409659355b6d1cc3c32decd5d561abaac86c37a353b52895a5e6c196d6f448","label":"Public Key"}
+{"type":"input","ref":"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd:0","label":"Input"}
+{"type":"output","ref":"f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd:1","label":"Output","spendable":false}
+{"type":"xpub","ref":"xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8","label":"Extended Public Key"}
+"#;
+
+#[tokio::test]
+async fn parse_bip329_labels() -> Result<()> {
+    init_logging("labels=info");
+
+    let labels = parse_labels(BIP329_LABELS)?;
+    assert_eq!(labels.len(), 6);
+    assert_eq!(labels[0].label_type, LabelType::Tx);
+    assert_eq!(
+        labels[0].origin.as_deref(),
+        Some("wpkh([d34db33f/84'/0'/0'])")
+    );
+    assert_eq!(labels[4].label_type, LabelType::Output);
+    assert_eq!(labels[4].spendable, Some(false));
+
+    let jsonl = format_labels(&labels)?;
+    assert_eq!(jsonl, BIP329_LABELS);
+
+    assert!(parse_labels("{\"type\":\"unknown\",\"ref\":\"\"}").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_and_export_labels() -> Result<()> {
+    init_logging("labels=info");
+
+    let hash = hash_password(&SecretString(ENCRYPTION_PASSWORD.to_owned()));
+    let encrypted_descriptors = new_wallet(&hash, &SecretString(SEED_PASSWORD.to_owned())).await?;
+    let vault = decrypt_wallet(&hash, &encrypted_descriptors)?;
+    let sk = vault.private.nostr_prv.as_str();
+    let descriptor = SecretString(vault.private.btc_descriptor_xprv.clone());
+    let watch_descriptor = SecretString(vault.public.btc_descriptor_xpub.clone());
+
+    let imported = import_wallet_labels(sk, &descriptor, BIP329_LABELS).await?;
+    assert_eq!(imported.labels.len(), 6);
+
+    // Unspendable outputs are frozen
+    let frozen = list_frozen_utxos(sk).await?;
+    assert_eq!(
+        frozen.frozen,
+        vec!["f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd:1".to_owned()]
+    );
+
+    // Labels are shared by the xprv and xpub descriptors of the wallet
+    let listed = list_wallet_labels(sk, &watch_descriptor).await?;
+    assert_eq!(listed.labels.len(), 6);
+
+    let updated = set_wallet_labels(
+        sk,
+        &descriptor,
+        vec![WalletLabel {
+            label_type: LabelType::Addr,
+            reference: "bc1q34aq5drpuwy3wgl9lhup9892qp6svr8ldzyy7c".to_owned(),
+            label: None,
+            origin: None,
+            spendable: None,
+        }],
+    )
+    .await?;
+    assert_eq!(updated.labels.len(), 5);
+
+    // Output labels must reference an outpoint
+    let invalid = set_wallet_labels(
+        sk,
+        &descriptor,
+        vec![WalletLabel {
+            label_type: LabelType::Output,
+            reference: "not an outpoint".to_owned(),
+            label: None,
+            origin: None,
+            spendable: Some(false),
+        }],
+    )
+    .await;
+    assert!(invalid.is_err());
+    assert_eq!(list_frozen_utxos(sk).await?.frozen.len(), 1);
+
+    let exported = export_wallet_labels(sk, &watch_descriptor).await?;
+    assert_eq!(parse_labels(&exported)?, updated.labels);
+
+    // Labels are attached to the wallet data
+    let wallet_data = get_wallet_data(sk, &watch_descriptor, None).await?;
+    assert_eq!(wallet_data.labels, updated.labels);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{get_wallet_data, save_mnemonic, sign_message, verify_message},
+    structs::{Bip322Format, SecretString},
+    util::init_logging,
+};
+
+const MNEMONIC: &str =
+    "empty faculty salute fortune select asthma attract question violin movie smile erupt half step lion deposit render stumble double mobile fossil height usual topple";
+
+#[tokio::test]
+async fn bip322_vector() -> Result<()> {
+    init_logging("message=debug");
+
+    // P2TR test vector of BIP-322
+    let address = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+    let signature = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+
+    assert!(verify_message(address, "Hello World", signature)?);
+    assert!(!verify_message(address, "", signature)?);
+    assert!(verify_message(
+        "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l",
+        "Hello World",
+        signature
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bip322_sign_and_verify() -> Result<()> {
+    init_logging("message=debug");
+
+    let keys = save_mnemonic(
+        &SecretString(MNEMONIC.to_owned()),
+        &SecretString("".to_owned()),
+    )
+    .await?;
+    let wallet_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &SecretString(keys.public.btc_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+    let descriptor = SecretString(keys.private.btc_descriptor_xprv.clone());
+    let message = "I own this address";
+
+    for format in [Bip322Format::Simple, Bip322Format::Full] {
+        let signature = sign_message(&descriptor, &wallet_data.address, message, format).await?;
+
+        assert!(verify_message(&wallet_data.address, message, &signature)?);
+        assert!(!verify_message(
+            &wallet_data.address,
+            "I do not",
+            &signature
+        )?);
+    }
+
+    // Addresses of other wallets cannot be signed for
+    let other = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+    assert!(
+        sign_message(&descriptor, other, message, Bip322Format::Simple)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -1,12 +1,17 @@
 #![cfg(not(target_arch = "wasm32"))]
 
-use std::env;
+use std::{
+    env,
+    process::{Command, Stdio},
+};
 
 use anyhow::Result;
 use bitmask_core::{
-    bitcoin::{decrypt_wallet, encrypt_wallet, get_wallet_data, hash_password, send_sats},
-    constants::switch_network,
-    structs::SecretString,
+    bitcoin::{
+        decrypt_wallet, encrypt_wallet, get_wallet_data, hash_password, new_mnemonic, send_sats,
+    },
+    constants::{switch_network, BITMASK_ENDPOINT},
+    structs::{FeeRateRequest, PayjoinReceiverRequest, PayjoinUriResponse, SecretString},
     util::init_logging,
 };
 use log::{debug, info};
@@ -34,6 +39,7 @@ async fn payjoin() -> Result<()> {
     let vault = decrypt_wallet(&hash, &encrypted_descriptors)?;
 
     let wallet = get_wallet_data(
+        &vault.private.nostr_prv,
         &SecretString(vault.private.btc_descriptor_xprv.clone()),
         Some(&SecretString(
             vault.private.btc_change_descriptor_xprv.clone(),
@@ -48,11 +54,12 @@ async fn payjoin() -> Result<()> {
     let amount = 1000;
 
     match send_sats(
+        &vault.private.nostr_prv,
         &SecretString(vault.private.btc_descriptor_xprv.clone()),
         &SecretString(vault.private.btc_change_descriptor_xprv.clone()),
         &destination,
         amount,
-        Some(1.1),
+        Some(FeeRateRequest::Rate(1.1)),
     )
     .await
     {
@@ -67,3 +74,104 @@ async fn payjoin() -> Result<()> {
 
     Ok(())
 }
+
+/// Requires the regtest node and a bitmaskd instance reachable through the HTTPS
+/// endpoint set in PAYJOIN_ENDPOINT (BIP-78 senders refuse plain HTTP endpoints).
+#[ignore]
+#[tokio::test]
+async fn payjoin_receiver() -> Result<()> {
+    init_logging("payjoin=warn");
+
+    switch_network("regtest").await?;
+
+    info!("Create wallets");
+    let sender_keys = new_mnemonic(&SecretString("".to_owned())).await?;
+    let receiver_keys = new_mnemonic(&SecretString("".to_owned())).await?;
+    let sender_descriptor = SecretString(sender_keys.private.btc_descriptor_xprv.clone());
+    let sender_change_descriptor =
+        SecretString(sender_keys.private.btc_change_descriptor_xprv.clone());
+    let receiver_descriptor = SecretString(receiver_keys.private.btc_descriptor_xprv.clone());
+    let receiver_change_descriptor =
+        SecretString(receiver_keys.private.btc_change_descriptor_xprv.clone());
+
+    let sender_wallet = get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+    let receiver_wallet = get_wallet_data(
+        &receiver_keys.private.nostr_prv,
+        &receiver_descriptor,
+        Some(&receiver_change_descriptor),
+    )
+    .await?;
+    send_coins(&sender_wallet.address, "0.1");
+    send_coins(&receiver_wallet.address, "0.1");
+
+    info!("Register payjoin receiver");
+    let bitmask_endpoint = BITMASK_ENDPOINT.read().await.to_string();
+    let request = PayjoinReceiverRequest {
+        descriptor: receiver_descriptor.clone(),
+        change_descriptor: receiver_change_descriptor.clone(),
+        amount: Some(100_000),
+        coin_control: Default::default(),
+    };
+    let receiver: PayjoinUriResponse = reqwest::Client::new()
+        .post(format!("{bitmask_endpoint}/payjoin"))
+        .bearer_auth(&receiver_keys.private.nostr_prv)
+        .json(&request)
+        .send()
+        .await?
+        .json()
+        .await?;
+    info!("Payjoin URI: {}", receiver.uri);
+
+    info!("Send payjoin");
+    get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+    let tx = send_sats(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        &sender_change_descriptor,
+        &receiver.uri,
+        100_000,
+        Some(FeeRateRequest::Rate(1.0)),
+    )
+    .await?;
+    debug!("{:#?}", tx);
+
+    let receiver_wallet = get_wallet_data(
+        &receiver_keys.private.nostr_prv,
+        &receiver_descriptor,
+        Some(&receiver_change_descriptor),
+    )
+    .await?;
+    let payjoin_tx = receiver_wallet
+        .transactions
+        .iter()
+        .find(|wallet_tx| wallet_tx.txid == tx.txid)
+        .expect("payjoin transaction is known by the receiver");
+
+    // The receiver contributed one of its inputs
+    assert!(payjoin_tx.sent > 0);
+    assert!(payjoin_tx.received > payjoin_tx.sent);
+
+    Ok(())
+}
+
+fn send_coins(address: &str, amount: &str) {
+    let path = env::current_dir().expect("oh no!");
+    let path = path.to_str().expect("oh no!");
+    let full_file = format!("{}/tests/scripts/send_coins.sh", path);
+    Command::new("bash")
+        .arg(full_file)
+        .args([address, amount])
+        .stdout(Stdio::null())
+        .status()
+        .expect("send coins");
+}
@@ -13,18 +13,33 @@ mod rgb {
         // TODO: Review after support multi-token transfer
         // mod collectibles;
         mod accept;
+        mod airdrop;
+        mod batch;
+        mod broadcast;
+        mod burn;
+        mod cfa;
         mod collectibles;
+        mod cpfp;
         mod crdt;
+        mod discovery;
         mod drain;
         mod dustless;
+        mod electrum;
         mod fungibles;
         mod import;
+        mod inflation;
         mod issue;
+        mod preview;
+        mod rbf;
+        mod slots;
         mod states;
         mod swaps;
+        mod sync;
         mod transfers;
         mod udas;
         pub mod utils;
+        mod vault;
+        mod watch_only;
         mod watcher;
     }
 
@@ -97,7 +97,7 @@ pub async fn allow_save_read_remove_transfers() -> Result<()> {
         )]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     assert!(resp.is_ok());
 
     // 5. Save Consig (Owner Side)
@@ -258,7 +258,7 @@ pub async fn allow_save_and_accept_all_transfers() -> Result<()> {
         )]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     assert!(resp.is_ok());
 
     // 5. Save Consig (Owner Side)
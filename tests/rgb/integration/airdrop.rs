@@ -0,0 +1,133 @@
+#![cfg(not(target_arch = "wasm32"))]
+use bdk::wallet::AddressIndex;
+use bitmask_core::{
+    bitcoin::{get_wallet, save_mnemonic, sync_wallet},
+    rgb::airdrop_asset,
+    structs::{
+        PsbtFeeRequest, RgbAirdropChunkStatus, RgbAirdropRecipient, RgbAirdropRequest, SecretString,
+    },
+};
+
+use crate::rgb::integration::utils::{
+    create_new_invoice, issuer_issue_contract_v2, send_some_coins, UtxoFilter,
+    ANOTHER_OWNER_MNEMONIC, ISSUER_MNEMONIC, OWNER_MNEMONIC,
+};
+
+#[tokio::test]
+async fn allow_resumable_airdrop_in_chunks() -> anyhow::Result<()> {
+    // 1. Initial Setup
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let another_owner_keys = save_mnemonic(
+        &SecretString(ANOTHER_OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB20",
+        10,
+        false,
+        true,
+        None,
+        Some("0.00000546".to_string()),
+        Some(UtxoFilter::with_amount_less_than(546)),
+        None,
+    )
+    .await?;
+
+    // 2. Get Recipients
+    let issuer_resp = issuer_resp[0].clone();
+    let mut recipients = vec![];
+    for (keys, amount) in [
+        (owner_keys.clone(), 1),
+        (another_owner_keys, 2),
+        (owner_keys, 3),
+    ] {
+        let invoice_resp = create_new_invoice(
+            &issuer_resp.contract_id,
+            &issuer_resp.iface,
+            amount,
+            keys,
+            None,
+            Some(issuer_resp.clone().contract.strict),
+        )
+        .await?;
+        recipients.push(RgbAirdropRecipient {
+            beneficiary: invoice_resp.invoice.to_string(),
+            amount,
+        });
+    }
+
+    // 3. Get Bitcoin UTXO
+    let issuer_btc_desc = &issuer_keys.public.btc_change_descriptor_xpub;
+    let issuer_vault = get_wallet(&SecretString(issuer_btc_desc.to_string()), None).await?;
+    let issuer_address = &issuer_vault
+        .lock()
+        .await
+        .get_address(AddressIndex::LastUnused)?
+        .address
+        .to_string();
+
+    send_some_coins(issuer_address, "0.001").await;
+    sync_wallet(&issuer_vault).await?;
+
+    // 4. Airdrop two recipients per anchor
+    let airdrop_req = RgbAirdropRequest {
+        contract_id: issuer_resp.contract_id.clone(),
+        iface: issuer_resp.iface.clone(),
+        recipients,
+        chunk_size: Some(2),
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+        signers: vec![
+            SecretString(issuer_keys.private.rgb_assets_descriptor_xprv.clone()),
+            SecretString(issuer_keys.private.btc_descriptor_xprv.clone()),
+        ],
+    };
+
+    let issue_sk = issuer_keys.private.nostr_prv.to_string();
+    let first = airdrop_asset(&issue_sk, airdrop_req.clone()).await?;
+    assert_eq!(first.chunks.len(), 2);
+    assert_eq!(first.chunks[0].status, RgbAirdropChunkStatus::Mempool);
+    assert_eq!(first.chunks[1].status, RgbAirdropChunkStatus::Pending);
+    assert_eq!(first.chunks[0].consignments.len(), 2);
+
+    // 5. Once the change of the first chunk confirms, resuming pays the rest
+    let whatever_address = "bcrt1p76gtucrxhmn8s5622r859dpnmkj0kgfcel9xy0sz6yj84x6ppz2qk5hpsw";
+    send_some_coins(whatever_address, "0.001").await;
+    let resp = airdrop_asset(&issue_sk, airdrop_req.clone()).await?;
+    assert_eq!(resp.airdrop_id, first.airdrop_id);
+    assert_eq!(resp.chunks.len(), 2);
+    assert_eq!(resp.chunks[0].txid, first.chunks[0].txid);
+    assert_eq!(resp.chunks[1].consignments.len(), 1);
+    assert!(matches!(
+        resp.chunks[0].status,
+        RgbAirdropChunkStatus::Confirmed(_)
+    ));
+    assert_eq!(resp.chunks[1].status, RgbAirdropChunkStatus::Mempool);
+    assert!(resp.chunks.iter().all(|chunk| chunk.txid.is_some()));
+
+    // 6. Running it again pays nobody twice
+    let resumed = airdrop_asset(&issue_sk, airdrop_req).await?;
+    assert_eq!(resumed.airdrop_id, resp.airdrop_id);
+    let txids: Vec<_> = resp.chunks.iter().map(|chunk| chunk.txid.clone()).collect();
+    let resumed_txids: Vec<_> = resumed
+        .chunks
+        .iter()
+        .map(|chunk| chunk.txid.clone())
+        .collect();
+    assert_eq!(txids, resumed_txids);
+
+    Ok(())
+}
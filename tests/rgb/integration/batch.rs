@@ -0,0 +1,227 @@
+#![cfg(not(target_arch = "wasm32"))]
+use std::collections::BTreeSet;
+
+use bdk::wallet::AddressIndex;
+use bitmask_core::{
+    bitcoin::{get_wallet, save_mnemonic, sync_wallet},
+    rgb::{accept_transfer, full_batch_transfer_asset, issue_contract, list_transfers},
+    structs::{
+        AcceptRequest, FullRgbBatchTransferRequest, IssueRequest, PsbtFeeRequest, RgbBatchInvoice,
+        SecretString, TransferType,
+    },
+};
+
+use crate::rgb::integration::utils::{
+    create_new_invoice, get_uda_data, issuer_issue_contract_v2, send_some_coins, UtxoFilter,
+    ANOTHER_OWNER_MNEMONIC, ISSUER_MNEMONIC, OWNER_MNEMONIC,
+};
+
+#[tokio::test]
+async fn allow_batch_transfer_to_many_beneficiaries() -> anyhow::Result<()> {
+    // 1. Initial Setup
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let another_owner_keys = save_mnemonic(
+        &SecretString(ANOTHER_OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB20",
+        5,
+        false,
+        true,
+        None,
+        Some("0.00000546".to_string()),
+        Some(UtxoFilter::with_amount_less_than(546)),
+        None,
+    )
+    .await?;
+
+    // 2. Get Invoices
+    let issuer_resp = issuer_resp[0].clone();
+    let mut invoices = vec![];
+    for (keys, amount) in [(owner_keys, 1), (another_owner_keys, 2)] {
+        let invoice_resp = create_new_invoice(
+            &issuer_resp.contract_id,
+            &issuer_resp.iface,
+            amount,
+            keys,
+            None,
+            Some(issuer_resp.clone().contract.strict),
+        )
+        .await?;
+        invoices.push(RgbBatchInvoice {
+            contract_id: issuer_resp.contract_id.clone(),
+            iface: issuer_resp.iface.clone(),
+            rgb_invoice: invoice_resp.invoice.to_string(),
+        });
+    }
+
+    // 3. Get Bitcoin UTXO
+    let issuer_btc_desc = &issuer_keys.public.btc_change_descriptor_xpub;
+    let issuer_vault = get_wallet(&SecretString(issuer_btc_desc.to_string()), None).await?;
+    let issuer_address = &issuer_vault
+        .lock()
+        .await
+        .get_address(AddressIndex::LastUnused)?
+        .address
+        .to_string();
+
+    send_some_coins(issuer_address, "0.001").await;
+    sync_wallet(&issuer_vault).await?;
+
+    // 4. Pay both invoices at once
+    let batch_pay_req = FullRgbBatchTransferRequest {
+        invoices,
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+    };
+
+    let issue_sk = issuer_keys.private.nostr_prv.to_string();
+    let resp = full_batch_transfer_asset(&issue_sk, batch_pay_req).await?;
+    assert_eq!(resp.consignments.len(), 2);
+
+    let consig_ids: BTreeSet<_> = resp
+        .consignments
+        .iter()
+        .map(|consig| consig.consig_id.clone())
+        .collect();
+    assert_eq!(consig_ids.len(), 2, "one consignment per beneficiary");
+
+    // 5. Every consignment is recorded
+    let transfers = list_transfers(&issue_sk, issuer_resp.contract_id).await?;
+    let sent: BTreeSet<_> = transfers
+        .transfers
+        .into_iter()
+        .filter(|transfer| transfer.ty == TransferType::Sended)
+        .map(|transfer| transfer.consig_id)
+        .collect();
+    assert!(consig_ids.is_subset(&sent));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allow_batch_transfer_of_many_contracts() -> anyhow::Result<()> {
+    // 1. Issue two fungible contracts and a UDA on the same UTXO
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let another_owner_keys = save_mnemonic(
+        &SecretString(ANOTHER_OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        2,
+        "RGB20",
+        5,
+        false,
+        true,
+        None,
+        Some("0.00000546".to_string()),
+        Some(UtxoFilter::with_amount_less_than(546)),
+        None,
+    )
+    .await?;
+    assert_eq!(issuer_resp[0].issue_utxo, issuer_resp[1].issue_utxo);
+
+    let issue_sk = issuer_keys.private.nostr_prv.to_string();
+    let uda_resp = issue_contract(
+        &issue_sk,
+        IssueRequest {
+            ticker: "DIBA".to_string(),
+            name: "DIBA".to_string(),
+            description: "DIBA".to_string(),
+            precision: 0,
+            supply: 1,
+            seal: format!("tapret1st:{}", issuer_resp[0].issue_utxo),
+            iface: "RGB21".to_string(),
+            meta: Some(get_uda_data()),
+            inflation: None,
+        },
+    )
+    .await?;
+    assert_eq!(uda_resp.issue_utxo, issuer_resp[0].issue_utxo);
+
+    // 2. Get one invoice of each fungible contract
+    let mut invoices = vec![];
+    let mut beneficiaries = vec![];
+    for (contract, keys, amount) in [
+        (&issuer_resp[0], owner_keys, 1),
+        (&issuer_resp[1], another_owner_keys, 2),
+    ] {
+        let invoice_resp = create_new_invoice(
+            &contract.contract_id,
+            &contract.iface,
+            amount,
+            keys.clone(),
+            None,
+            Some(contract.contract.strict.clone()),
+        )
+        .await?;
+        invoices.push(RgbBatchInvoice {
+            contract_id: contract.contract_id.clone(),
+            iface: contract.iface.clone(),
+            rgb_invoice: invoice_resp.invoice.to_string(),
+        });
+        beneficiaries.push(keys.private.nostr_prv.to_string());
+    }
+
+    // 3. Get Bitcoin UTXO
+    let issuer_btc_desc = &issuer_keys.public.btc_change_descriptor_xpub;
+    let issuer_vault = get_wallet(&SecretString(issuer_btc_desc.to_string()), None).await?;
+    let issuer_address = &issuer_vault
+        .lock()
+        .await
+        .get_address(AddressIndex::LastUnused)?
+        .address
+        .to_string();
+
+    send_some_coins(issuer_address, "0.001").await;
+    sync_wallet(&issuer_vault).await?;
+
+    // 4. Pay both contracts at once, the UDA moves to the change with its own interface
+    let batch_pay_req = FullRgbBatchTransferRequest {
+        invoices,
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+    };
+    let resp = full_batch_transfer_asset(&issue_sk, batch_pay_req).await?;
+    assert_eq!(resp.consignments.len(), 2);
+
+    // 5. Every beneficiary accepts its consignment
+    let whatever_address = "bcrt1p76gtucrxhmn8s5622r859dpnmkj0kgfcel9xy0sz6yj84x6ppz2qk5hpsw";
+    send_some_coins(whatever_address, "0.001").await;
+    for (consig, sk) in resp.consignments.iter().zip(&beneficiaries) {
+        let request = AcceptRequest {
+            consignment: consig.consig.clone(),
+            force: false,
+        };
+        let accept_resp = accept_transfer(sk, request).await?;
+        assert!(accept_resp.valid);
+    }
+
+    Ok(())
+}
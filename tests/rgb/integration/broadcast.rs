@@ -0,0 +1,86 @@
+#![cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{
+        build_send_sats, get_broadcast_queue, get_wallet_data, new_mnemonic, save_mnemonic,
+        sign_and_queue_psbt_file,
+    },
+    structs::{
+        BroadcastQueueRequest, BroadcastStatus, FeeRateRequest, SecretString, SignPsbtRequest,
+    },
+};
+
+use crate::rgb::integration::utils::{send_some_coins, OWNER_MNEMONIC};
+
+#[tokio::test]
+pub async fn queue_and_confirm_transaction() -> Result<()> {
+    // 1. Initial Setup
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let dest_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let change_descriptor = SecretString(keys.public.btc_change_descriptor_xpub.clone());
+    let wallet_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &descriptor,
+        Some(&change_descriptor),
+    )
+    .await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+
+    let dest_wallet_data = get_wallet_data(
+        &dest_keys.private.nostr_prv,
+        &SecretString(dest_keys.public.btc_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+
+    // 2. Sign and queue
+    let unsigned = build_send_sats(
+        &keys.private.nostr_prv,
+        &descriptor,
+        &change_descriptor,
+        &dest_wallet_data.address,
+        1_000_000,
+        Some(FeeRateRequest::Rate(2.0)),
+    )
+    .await?;
+
+    let queued = sign_and_queue_psbt_file(
+        &keys.private.nostr_prv,
+        SignPsbtRequest {
+            psbt: unsigned.psbt,
+            descriptors: vec![
+                SecretString(keys.private.btc_descriptor_xprv.clone()),
+                SecretString(keys.private.btc_change_descriptor_xprv.clone()),
+            ],
+        },
+    )
+    .await?;
+    assert_eq!(queued.status, BroadcastStatus::Mempool);
+    assert_eq!(queued.attempts, 1);
+
+    // 3. Queue follows the transaction into a block
+    send_some_coins(&dest_wallet_data.address, "0.1").await;
+    let queue = get_broadcast_queue(
+        &keys.private.nostr_prv,
+        BroadcastQueueRequest {
+            descriptor: SecretString(keys.private.btc_descriptor_xprv.clone()),
+            change_descriptor: None,
+        },
+    )
+    .await?;
+
+    let tracked = queue
+        .transactions
+        .iter()
+        .find(|tx| tx.txid == queued.txid)
+        .expect("queued transaction is tracked");
+    assert!(matches!(tracked.status, BroadcastStatus::Confirmed(_)));
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+#![cfg(not(target_arch = "wasm32"))]
+use bdk::wallet::AddressIndex;
+use bitmask_core::{
+    bitcoin::{get_wallet, save_mnemonic, sign_and_publish_psbt_file, sync_wallet},
+    rgb::{burn_asset, burn_proof, get_contract, list_transfers, verify_burn_proof},
+    structs::{
+        PsbtFeeRequest, RgbBurnProofRequest, RgbBurnRequest, SecretString, SignPsbtRequest,
+        TransferType,
+    },
+};
+
+use crate::rgb::integration::utils::{
+    issuer_issue_contract_v2, send_some_coins, UtxoFilter, ISSUER_MNEMONIC, OWNER_MNEMONIC,
+};
+
+#[tokio::test]
+async fn allow_issuer_burn_and_prove_it() -> anyhow::Result<()> {
+    // 1. Initial Setup
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB20",
+        10,
+        false,
+        true,
+        None,
+        Some("0.00000546".to_string()),
+        Some(UtxoFilter::with_amount_less_than(546)),
+        None,
+    )
+    .await?;
+    let issuer_resp = issuer_resp[0].clone();
+
+    // 2. Get Bitcoin UTXO
+    let issuer_btc_desc = &issuer_keys.public.btc_change_descriptor_xpub;
+    let issuer_vault = get_wallet(&SecretString(issuer_btc_desc.to_string()), None).await?;
+    let issuer_address = &issuer_vault
+        .lock()
+        .await
+        .get_address(AddressIndex::LastUnused)?
+        .address
+        .to_string();
+
+    send_some_coins(issuer_address, "0.001").await;
+    sync_wallet(&issuer_vault).await?;
+
+    // 3. Burn
+    let burn_req = RgbBurnRequest {
+        contract_id: issuer_resp.contract_id.clone(),
+        iface: issuer_resp.iface.clone(),
+        amount: 4,
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+    };
+
+    let issue_sk = issuer_keys.private.nostr_prv.to_string();
+    let burn_resp = burn_asset(&issue_sk, burn_req).await?;
+    assert_eq!(burn_resp.burned, 4);
+
+    // An unpublished burn is not counted
+    let contract = get_contract(&issue_sk, &issuer_resp.contract_id).await?;
+    assert_eq!(contract.burned, 0);
+    let transfers = list_transfers(&issue_sk, issuer_resp.contract_id.clone()).await?;
+    assert!(!transfers
+        .transfers
+        .iter()
+        .any(|transfer| transfer.consig_id == burn_resp.consig_id));
+
+    let request = SignPsbtRequest {
+        psbt: burn_resp.psbt.clone(),
+        descriptors: vec![
+            SecretString(issuer_keys.private.rgb_assets_descriptor_xprv.clone()),
+            SecretString(issuer_keys.private.btc_descriptor_xprv.clone()),
+        ],
+    };
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
+    assert!(resp.is_ok());
+    send_some_coins(issuer_address, "0.001").await;
+
+    // 4. The burn is listed and counted
+    let transfers = list_transfers(&issue_sk, issuer_resp.contract_id.clone()).await?;
+    assert!(transfers
+        .transfers
+        .iter()
+        .any(|transfer| transfer.consig_id == burn_resp.consig_id
+            && transfer.ty == TransferType::Burned));
+
+    let contract = get_contract(&issue_sk, &issuer_resp.contract_id).await?;
+    assert_eq!(contract.burned, 4);
+    assert_eq!(contract.balance, 6);
+
+    // 5. Burn Proof
+    let proof_req = RgbBurnProofRequest {
+        contract_id: issuer_resp.contract_id.clone(),
+        consig_id: burn_resp.consig_id,
+    };
+    let proof = burn_proof(&issue_sk, proof_req).await?;
+
+    // 6. Anyone can verify the proof
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let owner_sk = owner_keys.private.nostr_prv.to_string();
+    let verify_resp = verify_burn_proof(&owner_sk, proof).await?;
+    assert!(verify_resp.valid);
+    assert_eq!(verify_resp.burned, 4);
+    assert_eq!(verify_resp.contract_id, issuer_resp.contract_id);
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+#![cfg(not(target_arch = "wasm32"))]
+use crate::rgb::integration::utils::{
+    create_new_invoice, create_new_psbt, create_new_transfer, issuer_issue_contract_v2, UtxoFilter,
+    ISSUER_MNEMONIC, OWNER_MNEMONIC,
+};
+use bitmask_core::{
+    bitcoin::{save_mnemonic, sign_and_publish_psbt_file},
+    rgb::{accept_transfer, create_seller_offer, get_contract, list_contracts, watcher_details},
+    structs::{AcceptRequest, AllocationValue, RgbOfferRequest, SecretString, SignPsbtRequest},
+};
+
+#[tokio::test]
+async fn allow_issuer_list_cfa_allocations() -> anyhow::Result<()> {
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB25",
+        5,
+        false,
+        true,
+        None,
+        Some("0.1".to_string()),
+        Some(UtxoFilter::with_amount_equal_than(10000000)),
+        None,
+    )
+    .await?;
+    let issuer_resp = &issuer_resp[0];
+    assert_eq!(issuer_resp.iface, "RGB25");
+
+    // CFA allocations are listed with the fungible keychain
+    let sk = issuer_keys.private.nostr_prv.to_string();
+    let details = watcher_details(&sk, "default").await?;
+    let contract = details
+        .contracts
+        .into_iter()
+        .find(|contract| contract.contract_id == issuer_resp.contract_id)
+        .expect("cfa contract is listed");
+    let allocation = contract
+        .allocations
+        .into_iter()
+        .find(|allocation| allocation.utxo == issuer_resp.issue_utxo)
+        .expect("issue allocation is listed");
+    assert!(allocation.is_mine);
+    assert!(!allocation.is_spent);
+    assert_eq!(allocation.value, AllocationValue::Value(5));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allow_issuer_list_cfa_contracts() -> anyhow::Result<()> {
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB25",
+        5,
+        false,
+        true,
+        None,
+        Some("0.1".to_string()),
+        Some(UtxoFilter::with_amount_equal_than(10000000)),
+        None,
+    )
+    .await?;
+    let issuer_resp = &issuer_resp[0];
+
+    let sk = issuer_keys.private.nostr_prv.to_string();
+    let contracts = list_contracts(&sk).await?;
+    let listed = contracts
+        .contracts
+        .into_iter()
+        .find(|contract| contract.contract_id == issuer_resp.contract_id)
+        .expect("cfa contract is listed");
+    assert_eq!(listed.iface, "RGB25");
+    assert_eq!(listed.balance, 5);
+
+    let contract = get_contract(&sk, &issuer_resp.contract_id).await?;
+    assert_eq!(contract.contract_id, issuer_resp.contract_id);
+    assert_eq!(contract.balance, 5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allow_beneficiary_accept_cfa_transfer() -> anyhow::Result<()> {
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB25",
+        5,
+        false,
+        true,
+        None,
+        Some("0.1".to_string()),
+        Some(UtxoFilter::with_amount_equal_than(10000000)),
+        None,
+    )
+    .await?;
+    let issuer_resp = &issuer_resp[0];
+
+    let owner_resp = &create_new_invoice(
+        &issuer_resp.contract_id,
+        &issuer_resp.iface,
+        1,
+        owner_keys.clone(),
+        None,
+        Some(issuer_resp.clone().contract.legacy),
+    )
+    .await?;
+    let psbt_resp = create_new_psbt(
+        &issuer_resp.contract_id,
+        &issuer_resp.iface,
+        vec![issuer_resp.issue_utxo.clone()],
+        issuer_keys.clone(),
+    )
+    .await?;
+    let transfer_resp =
+        &create_new_transfer(issuer_keys.clone(), owner_resp.clone(), psbt_resp).await?;
+
+    let request = SignPsbtRequest {
+        psbt: transfer_resp.psbt.clone(),
+        descriptors: [SecretString(
+            issuer_keys.private.rgb_assets_descriptor_xprv.clone(),
+        )]
+        .to_vec(),
+    };
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
+    assert!(resp.is_ok());
+
+    let request = AcceptRequest {
+        consignment: transfer_resp.consig.clone(),
+        force: false,
+    };
+    let owner_sk = owner_keys.private.nostr_prv.to_string();
+    let resp = accept_transfer(&owner_sk, request).await?;
+    assert!(resp.valid);
+    assert_eq!(resp.contract_id, issuer_resp.contract_id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allow_seller_offer_cfa_contract() -> anyhow::Result<()> {
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let issuer_resp = issuer_issue_contract_v2(
+        1,
+        "RGB25",
+        5,
+        false,
+        true,
+        None,
+        Some("0.1".to_string()),
+        Some(UtxoFilter::with_amount_equal_than(10000000)),
+        None,
+    )
+    .await?;
+    let issuer_resp = &issuer_resp[0];
+
+    let expire_at = (chrono::Local::now() + chrono::Duration::minutes(5))
+        .naive_utc()
+        .timestamp();
+    let seller_swap_req = RgbOfferRequest {
+        contract_id: issuer_resp.contract_id.clone(),
+        iface: issuer_resp.iface.clone(),
+        contract_amount: 4,
+        bitcoin_price: 100000,
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.clone()),
+        change_terminal: "/20/1".to_string(),
+        bitcoin_changes: vec![],
+        expire_at: Some(expire_at),
+    };
+
+    let sk = issuer_keys.private.nostr_prv.to_string();
+    let offer = create_seller_offer(&sk, seller_swap_req).await?;
+    assert_eq!(offer.contract_id, issuer_resp.contract_id);
+    assert_eq!(offer.contract_amount, 4);
+    assert!(!offer.seller_psbt.is_empty());
+
+    Ok(())
+}
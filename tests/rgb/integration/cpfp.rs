@@ -0,0 +1,69 @@
+#![cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{cpfp, get_wallet_data, new_mnemonic, send_sats},
+    structs::{FeeRateRequest, SecretString},
+};
+
+use crate::rgb::integration::utils::send_some_coins;
+
+#[tokio::test]
+pub async fn accelerate_incoming_payment() -> Result<()> {
+    // 1. Initial Setup
+    let sender_keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let receiver_keys = new_mnemonic(&SecretString("".to_string())).await?;
+
+    let sender_descriptor = SecretString(sender_keys.private.btc_descriptor_xprv.clone());
+    let sender_change_descriptor =
+        SecretString(sender_keys.private.btc_change_descriptor_xprv.clone());
+    let receiver_descriptor = SecretString(receiver_keys.private.btc_descriptor_xprv.clone());
+    let receiver_change_descriptor =
+        SecretString(receiver_keys.private.btc_change_descriptor_xprv.clone());
+
+    let sender_wallet_data = get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+    send_some_coins(&sender_wallet_data.address, "0.1").await;
+    get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+
+    // 2. Receive a low fee payment
+    let receiver_wallet_data = get_wallet_data(
+        &receiver_keys.private.nostr_prv,
+        &receiver_descriptor,
+        Some(&receiver_change_descriptor),
+    )
+    .await?;
+    let parent = send_sats(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        &sender_change_descriptor,
+        &receiver_wallet_data.address,
+        1_000_000,
+        Some(FeeRateRequest::Rate(1.0)),
+    )
+    .await?;
+
+    // 3. Accelerate it from the receiver side
+    let child = cpfp(
+        &receiver_keys.private.nostr_prv,
+        &receiver_descriptor,
+        &receiver_change_descriptor,
+        &parent.txid.to_string(),
+        10.0,
+    )
+    .await?;
+
+    assert_eq!(child.parent_txid, parent.txid.to_string());
+    assert_eq!(Some(child.parent_fee), parent.fee);
+    assert!(child.package_fee_rate >= 9.5);
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+#![cfg(not(target_arch = "wasm32"))]
+use bdk::wallet::AddressIndex;
+use bitmask_core::{
+    bitcoin::{discover_wallet, get_wallet, new_mnemonic},
+    rgb::create_watcher,
+    structs::{AssetType, SecretString, WalletDiscoveryRequest, WatcherRequest},
+};
+
+use crate::rgb::integration::utils::send_some_coins;
+
+#[tokio::test]
+async fn allow_discover_funds_past_gap() -> anyhow::Result<()> {
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let sk = keys.private.nostr_prv.clone();
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let change_descriptor = SecretString(keys.public.btc_change_descriptor_xpub.clone());
+
+    create_watcher(
+        &sk,
+        WatcherRequest {
+            name: "default".to_string(),
+            xpub: keys.public.watcher_xpub.clone(),
+            force: true,
+        },
+    )
+    .await?;
+
+    // 1. Fund addresses past the regular sync window
+    let btc_wallet = get_wallet(&descriptor, Some(&change_descriptor)).await?;
+    let btc_address = btc_wallet
+        .lock()
+        .await
+        .get_address(AddressIndex::Peek(25))?;
+    send_some_coins(&btc_address.address.to_string(), "0.01").await;
+
+    let rgb_wallet = get_wallet(
+        &SecretString(keys.public.rgb_assets_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+    let rgb_address = rgb_wallet
+        .lock()
+        .await
+        .get_address(AddressIndex::Peek(22))?;
+    send_some_coins(&rgb_address.address.to_string(), "0.01").await;
+
+    // 2. Discover the wallet with a gap covering both
+    let request = WalletDiscoveryRequest {
+        descriptor,
+        change_descriptor,
+        watcher_name: None,
+        gap_limit: Some(30),
+    };
+    let discovery = discover_wallet(&sk, request).await?;
+
+    let external = &discovery.bitcoin[0];
+    assert_eq!(external.asset_type, AssetType::Bitcoin);
+    assert_eq!(external.last_used_index, Some(25));
+    assert_eq!(external.used_addresses, 1);
+    assert_eq!(external.scanned_addresses, 56);
+    assert_eq!(discovery.bitcoin[1].used_addresses, 0);
+
+    let rgb20 = discovery
+        .rgb
+        .iter()
+        .find(|terminal| terminal.asset_type == AssetType::RGB20)
+        .expect("RGB20 terminal is scanned");
+    assert_eq!(rgb20.last_used_index, Some(22));
+    assert_eq!(rgb20.received_outputs, 1);
+    assert_eq!(rgb20.scanned_addresses, 53);
+
+    // 3. A zero gap never stops
+    let request = WalletDiscoveryRequest {
+        descriptor: SecretString(keys.public.btc_descriptor_xpub.clone()),
+        change_descriptor: SecretString(keys.public.btc_change_descriptor_xpub.clone()),
+        watcher_name: None,
+        gap_limit: Some(0),
+    };
+    assert!(discover_wallet(&sk, request).await.is_err());
+
+    Ok(())
+}
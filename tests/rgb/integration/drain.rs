@@ -2,7 +2,7 @@
 use anyhow::Result;
 use bitmask_core::{
     bitcoin::{drain_wallet, get_wallet_data, new_mnemonic, save_mnemonic},
-    structs::SecretString,
+    structs::{FeeRateRequest, SecretString},
 };
 
 use crate::rgb::integration::utils::{send_some_coins, OWNER_MNEMONIC};
@@ -18,6 +18,7 @@ pub async fn drain() -> Result<()> {
     .await?;
 
     let old_wallet_data = get_wallet_data(
+        &old_keys.private.nostr_prv,
         &SecretString(old_keys.public.btc_descriptor_xpub.clone()),
         Some(&SecretString(
             old_keys.public.btc_change_descriptor_xpub.clone(),
@@ -30,6 +31,7 @@ pub async fn drain() -> Result<()> {
     send_some_coins(&old_wallet_data.address, "0.1").await;
 
     let new_wallet_data = get_wallet_data(
+        &new_keys.private.nostr_prv,
         &SecretString(new_keys.public.btc_descriptor_xpub.clone()),
         Some(&SecretString(
             new_keys.public.btc_change_descriptor_xpub.clone(),
@@ -39,12 +41,13 @@ pub async fn drain() -> Result<()> {
 
     // 2. Drain sats from original wallet to new wallet
     let drain_wallet_details = drain_wallet(
+        &old_keys.private.nostr_prv,
         &new_wallet_data.address,
         &SecretString(old_keys.private.btc_descriptor_xprv.clone()),
         Some(&SecretString(
             old_keys.private.btc_change_descriptor_xprv.clone(),
         )),
-        Some(2.0),
+        Some(FeeRateRequest::Rate(2.0)),
     )
     .await?;
 
@@ -11,8 +11,8 @@ use bitmask_core::{
     },
     rgb::{accept_transfer, create_watcher, full_transfer_asset, get_contract},
     structs::{
-        AcceptRequest, FullRgbTransferRequest, PsbtFeeRequest, PsbtInputRequest, SecretString,
-        SignPsbtRequest, WatcherRequest,
+        AcceptRequest, FeeRateRequest, FullRgbTransferRequest, PsbtFeeRequest, PsbtInputRequest,
+        SecretString, SignPsbtRequest, WatcherRequest,
     },
 };
 
@@ -100,7 +100,7 @@ async fn create_dustless_transfer_with_fee_value() -> anyhow::Result<()> {
         ]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     assert!(resp.is_ok());
 
     let whatever_address = "bcrt1p76gtucrxhmn8s5622r859dpnmkj0kgfcel9xy0sz6yj84x6ppz2qk5hpsw";
@@ -176,13 +176,14 @@ async fn create_dustless_transfer_with_fee_rate() -> anyhow::Result<()> {
     sync_wallet(&btc_wallet).await?;
 
     let fund_vault = fund_vault(
+        &issuer_keys.private.nostr_prv,
         &btc_descriptor_xprv,
         &btc_change_descriptor_xprv,
         &assets_address_1,
         &assets_address_2,
         &uda_address_1,
         &uda_address_2,
-        Some(1.1),
+        Some(FeeRateRequest::Rate(1.1)),
     )
     .await?;
 
@@ -235,7 +236,7 @@ async fn create_dustless_transfer_with_fee_rate() -> anyhow::Result<()> {
         ]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     // println!("{:?}", resp);
     assert!(resp.is_ok());
 
@@ -0,0 +1,90 @@
+#![cfg(not(target_arch = "wasm32"))]
+use std::{str::FromStr, sync::Arc};
+
+use anyhow::Result;
+use bdk::{
+    database::{AnyDatabase, MemoryDatabase},
+    Wallet,
+};
+use bitcoin::Network;
+use bitmask_core::{
+    bitcoin::{get_wallet_data, new_mnemonic, sync_wallet_with_backend, ChainBackend},
+    constants::{BITCOIN_ELECTRUM_API, BITCOIN_EXPLORER_API},
+    rgb::resolvers::ExplorerResolver,
+    structs::SecretString,
+};
+use tokio::sync::Mutex;
+use wallet::onchain::ResolveTx;
+
+use crate::rgb::integration::utils::send_some_coins;
+
+#[tokio::test]
+pub async fn electrum_and_esplora_backends_agree() -> Result<()> {
+    assert_eq!(ChainBackend::from_str("electrum")?, ChainBackend::Electrum);
+    assert_eq!(ChainBackend::from_str("Esplora")?, ChainBackend::Esplora);
+    assert!(ChainBackend::from_str("bitcoind").is_err());
+
+    // 1. Fund a wallet and sync it with esplora
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let change_descriptor = SecretString(keys.public.btc_change_descriptor_xpub.clone());
+
+    let wallet_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &descriptor,
+        Some(&change_descriptor),
+    )
+    .await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+    let esplora_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &descriptor,
+        Some(&change_descriptor),
+    )
+    .await?;
+
+    // 2. Sync the same descriptors with electrum, in a wallet of its own. The backend is
+    // passed explicitly, the configuration is shared by the tests running in parallel.
+    let electrum_wallet = Arc::new(Mutex::new(Wallet::new(
+        &descriptor.0,
+        Some(&change_descriptor.0),
+        Network::Regtest,
+        AnyDatabase::Memory(MemoryDatabase::default()),
+    )?));
+    sync_wallet_with_backend(&electrum_wallet, ChainBackend::Electrum).await?;
+    // A second sync only updates what changed
+    sync_wallet_with_backend(&electrum_wallet, ChainBackend::Electrum).await?;
+
+    let electrum_wallet = electrum_wallet.lock().await;
+    let mut electrum_utxos: Vec<String> = electrum_wallet
+        .list_unspent()?
+        .into_iter()
+        .map(|utxo| utxo.outpoint.to_string())
+        .collect();
+    electrum_utxos.sort();
+    let mut esplora_utxos = esplora_data.utxos.clone();
+    esplora_utxos.sort();
+    assert_eq!(electrum_utxos, esplora_utxos);
+    assert_eq!(
+        electrum_wallet.get_balance()?.confirmed,
+        esplora_data.balance.confirmed
+    );
+    assert_eq!(
+        electrum_wallet.list_transactions(false)?.len(),
+        esplora_data.transactions.len()
+    );
+
+    // 3. Resolve the funding transaction with electrum
+    let funding_txid = esplora_data.transactions[0].txid;
+    let resolver = ExplorerResolver::new(
+        ChainBackend::Electrum,
+        &BITCOIN_EXPLORER_API.read().await,
+        &BITCOIN_ELECTRUM_API.read().await,
+    );
+    let funding_tx = resolver
+        .resolve_tx(funding_txid)
+        .expect("funding tx is resolved");
+    assert_eq!(funding_tx.txid(), funding_txid);
+
+    Ok(())
+}
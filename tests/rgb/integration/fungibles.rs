@@ -62,7 +62,7 @@ async fn allow_beneficiary_accept_transfer() -> anyhow::Result<()> {
         )]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     assert!(resp.is_ok());
 
     let request = AcceptRequest {
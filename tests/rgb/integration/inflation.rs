@@ -0,0 +1,265 @@
+#![cfg(not(target_arch = "wasm32"))]
+use bdk::wallet::AddressIndex;
+use bitmask_core::{
+    bitcoin::{get_wallet, save_mnemonic, sign_and_publish_psbt_file, sync_wallet},
+    rgb::{
+        create_watcher, full_transfer_asset, get_contract, inflate_contract, issue_contract,
+        watcher_next_address, watcher_unspent_utxos,
+    },
+    structs::{
+        FullRgbTransferRequest, InflateRequest, InflationSeal, IssueRequest, PsbtFeeRequest,
+        SecretString, SignPsbtRequest, WatcherRequest,
+    },
+};
+
+use crate::rgb::integration::utils::{
+    create_new_invoice, send_some_coins, setup_regtest, ISSUER_MNEMONIC, OWNER_MNEMONIC,
+};
+
+#[tokio::test]
+async fn allow_issuer_inflate_contract() -> anyhow::Result<()> {
+    // 1. Initial Setup
+    setup_regtest(false, None).await;
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let watcher_name = "default";
+    let sk = &issuer_keys.private.nostr_prv;
+    let create_watch_req = WatcherRequest {
+        name: watcher_name.to_string(),
+        xpub: issuer_keys.public.watcher_xpub.clone(),
+        force: true,
+    };
+    create_watcher(sk, create_watch_req).await?;
+
+    // 2. Get Asset UTXOs (issue and inflation seals)
+    for _ in 0..2 {
+        let next_address = watcher_next_address(sk, watcher_name, "RGB20").await?;
+        send_some_coins(&next_address.address, "0.00001").await;
+    }
+    let utxos = watcher_unspent_utxos(sk, watcher_name, "RGB20")
+        .await?
+        .utxos;
+    assert!(utxos.len() >= 2);
+    let issue_seal = format!("tapret1st:{}", utxos[0].outpoint);
+    let inflation_seal = format!("tapret1st:{}", utxos[1].outpoint);
+
+    // 3. Issue Contract with Inflation Rights
+    let request = IssueRequest {
+        ticker: "DIBA".to_string(),
+        name: "DIBA".to_string(),
+        description: "DIBA".to_string(),
+        precision: 2,
+        supply: 5,
+        seal: issue_seal.clone(),
+        iface: "RGB20".to_string(),
+        meta: None,
+        inflation: Some(InflationSeal {
+            seal: inflation_seal,
+            amount: 500,
+        }),
+    };
+    let issue_resp = issue_contract(sk, request).await?;
+
+    let contract = get_contract(sk, &issue_resp.contract_id).await?;
+    assert_eq!(contract.issued_supply, 5);
+    assert_eq!(contract.max_supply, 505);
+    assert_eq!(contract.balance, 5);
+
+    // 4. Get Bitcoin UTXO
+    let issuer_btc_desc = &issuer_keys.public.btc_change_descriptor_xpub;
+    let issuer_vault = get_wallet(&SecretString(issuer_btc_desc.to_string()), None).await?;
+    let issuer_address = &issuer_vault
+        .lock()
+        .await
+        .get_address(AddressIndex::LastUnused)?
+        .address
+        .to_string();
+
+    send_some_coins(issuer_address, "0.001").await;
+    sync_wallet(&issuer_vault).await?;
+
+    // 5. Mint More Supply
+    let inflate_req = InflateRequest {
+        contract_id: issue_resp.contract_id.clone(),
+        beneficiaries: vec![InflationSeal {
+            seal: issue_seal,
+            amount: 200,
+        }],
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+    };
+    let inflate_resp = inflate_contract(sk, inflate_req).await?;
+    assert_eq!(inflate_resp.minted, 200);
+
+    let request = SignPsbtRequest {
+        psbt: inflate_resp.psbt,
+        descriptors: vec![
+            SecretString(issuer_keys.private.rgb_assets_descriptor_xprv.clone()),
+            SecretString(issuer_keys.private.btc_descriptor_xprv.clone()),
+        ],
+    };
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
+    assert!(resp.is_ok());
+    send_some_coins(issuer_address, "0.001").await;
+
+    // 6. Issued supply grows, the maximum supply does not
+    let contract = get_contract(sk, &issue_resp.contract_id).await?;
+    assert_eq!(contract.issued_supply, 205);
+    assert_eq!(contract.max_supply, 505);
+    assert_eq!(contract.balance, 205);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allow_allowance_to_share_assets_output() -> anyhow::Result<()> {
+    // 1. Initial Setup
+    setup_regtest(false, None).await;
+    let issuer_keys = save_mnemonic(
+        &SecretString(ISSUER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let watcher_name = "default";
+    let sk = &issuer_keys.private.nostr_prv;
+    let create_watch_req = WatcherRequest {
+        name: watcher_name.to_string(),
+        xpub: issuer_keys.public.watcher_xpub.clone(),
+        force: true,
+    };
+    create_watcher(sk, create_watch_req).await?;
+
+    // 2. Get Asset UTXOs (shared seal and beneficiary seal)
+    for _ in 0..2 {
+        let next_address = watcher_next_address(sk, watcher_name, "RGB20").await?;
+        send_some_coins(&next_address.address, "0.00001").await;
+    }
+    let utxos = watcher_unspent_utxos(sk, watcher_name, "RGB20")
+        .await?
+        .utxos;
+    assert!(utxos.len() >= 2);
+    let shared_seal = format!("tapret1st:{}", utxos[0].outpoint);
+    let beneficiary_seal = format!("tapret1st:{}", utxos[1].outpoint);
+
+    // 3. Issue an inflatable contract and another contract on the same output
+    let request = IssueRequest {
+        ticker: "DIBA".to_string(),
+        name: "DIBA".to_string(),
+        description: "DIBA".to_string(),
+        precision: 2,
+        supply: 5,
+        seal: shared_seal.clone(),
+        iface: "RGB20".to_string(),
+        meta: None,
+        inflation: Some(InflationSeal {
+            seal: shared_seal.clone(),
+            amount: 500,
+        }),
+    };
+    let issue_resp = issue_contract(sk, request).await?;
+    let other_request = IssueRequest {
+        ticker: "OTHER".to_string(),
+        name: "OTHER".to_string(),
+        description: "OTHER".to_string(),
+        precision: 2,
+        supply: 7,
+        seal: shared_seal,
+        iface: "RGB20".to_string(),
+        meta: None,
+        inflation: None,
+    };
+    let other_resp = issue_contract(sk, other_request).await?;
+
+    // 4. Get Bitcoin UTXO
+    let issuer_btc_desc = &issuer_keys.public.btc_change_descriptor_xpub;
+    let issuer_vault = get_wallet(&SecretString(issuer_btc_desc.to_string()), None).await?;
+    let issuer_address = &issuer_vault
+        .lock()
+        .await
+        .get_address(AddressIndex::LastUnused)?
+        .address
+        .to_string();
+
+    send_some_coins(issuer_address, "0.001").await;
+    sync_wallet(&issuer_vault).await?;
+
+    // 5. Transfer part of the assets, the allowance moves to the change
+    let owner_resp = create_new_invoice(
+        &issue_resp.contract_id,
+        &issue_resp.iface,
+        1,
+        owner_keys,
+        None,
+        Some(issue_resp.contract.strict.clone()),
+    )
+    .await?;
+    let transfer_req = FullRgbTransferRequest {
+        contract_id: issue_resp.contract_id.clone(),
+        iface: issue_resp.iface.clone(),
+        rgb_invoice: owner_resp.invoice.to_string(),
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+    };
+    let transfer_resp = full_transfer_asset(sk, transfer_req).await?;
+    let request = SignPsbtRequest {
+        psbt: transfer_resp.psbt,
+        descriptors: vec![
+            SecretString(issuer_keys.private.rgb_assets_descriptor_xprv.clone()),
+            SecretString(issuer_keys.private.btc_descriptor_xprv.clone()),
+        ],
+    };
+    sign_and_publish_psbt_file(sk, request).await?;
+    send_some_coins(issuer_address, "0.001").await;
+
+    let contract = get_contract(sk, &issue_resp.contract_id).await?;
+    assert_eq!(contract.balance, 4);
+    assert_eq!(contract.max_supply, 505);
+
+    // 6. Mint from the moved allowance
+    let inflate_req = InflateRequest {
+        contract_id: issue_resp.contract_id.clone(),
+        beneficiaries: vec![InflationSeal {
+            seal: beneficiary_seal,
+            amount: 100,
+        }],
+        descriptor: SecretString(issuer_keys.public.rgb_assets_descriptor_xpub.to_string()),
+        change_terminal: "/20/1".to_string(),
+        fee: PsbtFeeRequest::Value(1000),
+        bitcoin_changes: vec![],
+    };
+    let inflate_resp = inflate_contract(sk, inflate_req).await?;
+    assert_eq!(inflate_resp.minted, 100);
+
+    let request = SignPsbtRequest {
+        psbt: inflate_resp.psbt,
+        descriptors: vec![
+            SecretString(issuer_keys.private.rgb_assets_descriptor_xprv.clone()),
+            SecretString(issuer_keys.private.btc_descriptor_xprv.clone()),
+        ],
+    };
+    sign_and_publish_psbt_file(sk, request).await?;
+    send_some_coins(issuer_address, "0.001").await;
+
+    // 7. Nothing is burned, the other contract follows the change
+    let contract = get_contract(sk, &issue_resp.contract_id).await?;
+    assert_eq!(contract.issued_supply, 105);
+    assert_eq!(contract.max_supply, 505);
+    assert_eq!(contract.balance, 104);
+
+    let other = get_contract(sk, &other_resp.contract_id).await?;
+    assert_eq!(other.balance, 7);
+
+    Ok(())
+}
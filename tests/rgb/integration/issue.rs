@@ -1,5 +1,9 @@
 #![cfg(not(target_arch = "wasm32"))]
-use crate::rgb::integration::utils::{get_uda_data, issuer_issue_contract};
+use bitmask_core::{bitcoin::save_mnemonic, structs::SecretString};
+
+use crate::rgb::integration::utils::{
+    create_new_invoice, get_uda_data, issuer_issue_contract, OWNER_MNEMONIC,
+};
 
 #[tokio::test]
 async fn allow_issuer_issue_fungible_contract() -> anyhow::Result<()> {
@@ -16,6 +20,30 @@ async fn allow_issuer_issue_uda_contract() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn allow_issuer_issue_cfa_contract() -> anyhow::Result<()> {
+    let issuer_resp = issuer_issue_contract("RGB25", 5, false, true, None).await?;
+    assert_eq!(issuer_resp.iface, "RGB25");
+    assert_eq!(issuer_resp.supply, 5);
+
+    let owner_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+    let invoice_resp = create_new_invoice(
+        &issuer_resp.contract_id,
+        &issuer_resp.iface,
+        1,
+        owner_keys,
+        None,
+        Some(issuer_resp.contract.strict),
+    )
+    .await;
+    assert!(invoice_resp.is_ok());
+    Ok(())
+}
+
 // TODO: Review after support multi-token transfer
 // async fn _allow_issuer_issue_collectible_contract() -> anyhow::Result<()> {
 //     let collectible = Some(get_collectible_data());
@@ -0,0 +1,147 @@
+#![cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{
+        confirm_preview, freeze_utxos, get_wallet, get_wallet_data, new_mnemonic,
+        preview_drain_wallet, preview_send_sats, save_mnemonic, sync_wallet,
+    },
+    structs::{ConfirmPreviewRequest, FeeRateRequest, SecretString},
+};
+
+use crate::rgb::integration::utils::{send_some_coins, OWNER_MNEMONIC};
+
+#[tokio::test]
+pub async fn preview_and_confirm_send_sats() -> Result<()> {
+    // 1. Initial Setup
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let dest_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+
+    let descriptor = SecretString(keys.private.btc_descriptor_xprv.clone());
+    let change_descriptor = SecretString(keys.private.btc_change_descriptor_xprv.clone());
+    let wallet_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &descriptor,
+        Some(&change_descriptor),
+    )
+    .await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+
+    let dest_wallet_data = get_wallet_data(
+        &dest_keys.private.nostr_prv,
+        &SecretString(dest_keys.public.btc_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+
+    // 2. Preview
+    let preview = preview_send_sats(
+        &keys.private.nostr_prv,
+        &descriptor,
+        &change_descriptor,
+        &dest_wallet_data.address,
+        1_000_000,
+        Some(FeeRateRequest::Rate(2.0)),
+    )
+    .await?;
+
+    let inputs: u64 = preview.inputs.iter().map(|input| input.amount).sum();
+    let outputs: u64 = preview.outputs.iter().map(|output| output.amount).sum();
+    assert_eq!(inputs, outputs + preview.fee);
+    assert!(preview.fee_rate >= 2.0);
+    assert!(preview.vsize > 0);
+
+    let payment = preview
+        .outputs
+        .iter()
+        .find(|output| output.address.as_deref() == Some(dest_wallet_data.address.as_str()))
+        .expect("payment output");
+    assert!(!payment.is_change);
+    assert_eq!(payment.amount, 1_000_000);
+    assert_eq!(
+        preview
+            .outputs
+            .iter()
+            .filter(|output| output.is_change)
+            .count(),
+        1
+    );
+
+    // 3. Confirm
+    let details = confirm_preview(
+        &keys.private.nostr_prv,
+        ConfirmPreviewRequest {
+            psbt: preview.psbt,
+            descriptor,
+            change_descriptor: Some(change_descriptor),
+        },
+    )
+    .await?;
+    assert_eq!(details.txid.to_string(), preview.txid);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn preview_drain_wallet_has_no_change() -> Result<()> {
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let wallet_data = get_wallet_data(&keys.private.nostr_prv, &descriptor, None).await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+
+    let whatever_address = "bcrt1p76gtucrxhmn8s5622r859dpnmkj0kgfcel9xy0sz6yj84x6ppz2qk5hpsw";
+    let preview = preview_drain_wallet(
+        &keys.private.nostr_prv,
+        whatever_address,
+        &descriptor,
+        None,
+        Some(FeeRateRequest::Rate(1.0)),
+    )
+    .await?;
+
+    assert_eq!(preview.outputs.len(), 1);
+    assert!(!preview.outputs[0].is_change);
+    assert_eq!(
+        preview.inputs[0].amount,
+        preview.outputs[0].amount + preview.fee
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn preview_drain_wallet_skips_frozen_utxos() -> Result<()> {
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let wallet_data = get_wallet_data(&keys.private.nostr_prv, &descriptor, None).await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+    send_some_coins(&wallet_data.address, "0.2").await;
+
+    // 1. Freeze one of the two UTXOs
+    let wallet = get_wallet(&descriptor, None).await?;
+    sync_wallet(&wallet).await?;
+    let utxos = wallet.lock().await.list_unspent()?;
+    assert_eq!(utxos.len(), 2);
+    let frozen = utxos[0].outpoint.to_string();
+    freeze_utxos(&keys.private.nostr_prv, vec![frozen.clone()]).await?;
+
+    // 2. Only the other one is drained
+    let whatever_address = "bcrt1p76gtucrxhmn8s5622r859dpnmkj0kgfcel9xy0sz6yj84x6ppz2qk5hpsw";
+    let preview = preview_drain_wallet(
+        &keys.private.nostr_prv,
+        whatever_address,
+        &descriptor,
+        None,
+        Some(FeeRateRequest::Rate(1.0)),
+    )
+    .await?;
+
+    assert_eq!(preview.inputs.len(), 1);
+    assert_ne!(preview.inputs[0].outpoint, frozen);
+    assert_eq!(preview.inputs[0].outpoint, utxos[1].outpoint.to_string());
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+#![cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{
+        bump_fee, freeze_utxos, get_broadcast_queue, get_wallet, get_wallet_data, new_mnemonic,
+        send_sats, sync_wallet,
+    },
+    structs::{BroadcastQueueRequest, BroadcastStatus, FeeRateRequest, SecretString},
+};
+
+use crate::rgb::integration::utils::send_some_coins;
+
+#[tokio::test]
+pub async fn bump_fee_of_unconfirmed_payment() -> Result<()> {
+    // 1. Initial Setup
+    let sender_keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let receiver_keys = new_mnemonic(&SecretString("".to_string())).await?;
+
+    let sender_descriptor = SecretString(sender_keys.private.btc_descriptor_xprv.clone());
+    let sender_change_descriptor =
+        SecretString(sender_keys.private.btc_change_descriptor_xprv.clone());
+
+    let sender_wallet_data = get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+    send_some_coins(&sender_wallet_data.address, "0.1").await;
+
+    let receiver_wallet_data = get_wallet_data(
+        &receiver_keys.private.nostr_prv,
+        &SecretString(receiver_keys.public.btc_descriptor_xpub.clone()),
+        Some(&SecretString(
+            receiver_keys.public.btc_change_descriptor_xpub.clone(),
+        )),
+    )
+    .await?;
+
+    // 2. Send a low fee payment
+    get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+    let original = send_sats(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        &sender_change_descriptor,
+        &receiver_wallet_data.address,
+        1_000_000,
+        Some(FeeRateRequest::Rate(1.0)),
+    )
+    .await?;
+
+    // 3. Replace it with a higher fee rate
+    let bumped = bump_fee(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        &sender_change_descriptor,
+        &original.txid.to_string(),
+        5.0,
+    )
+    .await?;
+
+    assert_eq!(bumped.original_txid, original.txid.to_string());
+    assert_ne!(bumped.txid, bumped.original_txid);
+    assert_eq!(bumped.original_fee, original.fee);
+    assert!(bumped.fee > bumped.original_fee);
+
+    // 4. Original is not broadcast again
+    let queue = get_broadcast_queue(
+        &sender_keys.private.nostr_prv,
+        BroadcastQueueRequest {
+            descriptor: sender_descriptor.clone(),
+            change_descriptor: Some(sender_change_descriptor.clone()),
+        },
+    )
+    .await?;
+    let replaced = queue
+        .transactions
+        .iter()
+        .find(|tx| tx.txid == bumped.original_txid)
+        .expect("original transaction is tracked");
+    assert_eq!(
+        replaced.status,
+        BroadcastStatus::Replaced(bumped.txid.clone())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn bump_fee_skips_frozen_utxos() -> Result<()> {
+    // 1. Initial Setup
+    let sender_keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let receiver_keys = new_mnemonic(&SecretString("".to_string())).await?;
+
+    let sender_descriptor = SecretString(sender_keys.private.btc_descriptor_xprv.clone());
+    let sender_change_descriptor =
+        SecretString(sender_keys.private.btc_change_descriptor_xprv.clone());
+
+    let sender_wallet_data = get_wallet_data(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        Some(&sender_change_descriptor),
+    )
+    .await?;
+    send_some_coins(&sender_wallet_data.address, "0.01").await;
+    send_some_coins(&sender_wallet_data.address, "0.5").await;
+
+    let receiver_wallet_data = get_wallet_data(
+        &receiver_keys.private.nostr_prv,
+        &SecretString(receiver_keys.public.btc_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+
+    // 2. Freeze the larger UTXO
+    let wallet = get_wallet(&sender_descriptor, Some(&sender_change_descriptor)).await?;
+    sync_wallet(&wallet).await?;
+    let frozen = wallet
+        .lock()
+        .await
+        .list_unspent()?
+        .into_iter()
+        .find(|utxo| utxo.txout.value == 50_000_000)
+        .expect("larger utxo")
+        .outpoint
+        .to_string();
+    freeze_utxos(&sender_keys.private.nostr_prv, vec![frozen]).await?;
+
+    // 3. Send almost the whole smaller UTXO
+    let original = send_sats(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        &sender_change_descriptor,
+        &receiver_wallet_data.address,
+        999_000,
+        Some(FeeRateRequest::Rate(1.0)),
+    )
+    .await?;
+
+    // 4. The higher fee cannot be paid without the frozen UTXO
+    let bumped = bump_fee(
+        &sender_keys.private.nostr_prv,
+        &sender_descriptor,
+        &sender_change_descriptor,
+        &original.txid.to_string(),
+        50.0,
+    )
+    .await;
+    assert!(bumped.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+#![cfg(not(target_arch = "wasm32"))]
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{get_wallet, get_wallet_data, new_mnemonic, plan_vault_utxos, sync_wallet},
+    structs::{
+        AssetType, DecryptedWalletData, FeeRateRequest, SecretString, VaultSlotRequest,
+        VaultUtxoPlanRequest,
+    },
+};
+
+use crate::rgb::integration::utils::send_some_coins;
+
+fn plan_request(keys: &DecryptedWalletData, slots: Vec<VaultSlotRequest>) -> VaultUtxoPlanRequest {
+    VaultUtxoPlanRequest {
+        descriptor: SecretString(keys.private.btc_descriptor_xprv.clone()),
+        change_descriptor: SecretString(keys.private.btc_change_descriptor_xprv.clone()),
+        rgb_assets_descriptor_xpub: SecretString(keys.public.rgb_assets_descriptor_xpub.clone()),
+        rgb_udas_descriptor_xpub: SecretString(keys.public.rgb_udas_descriptor_xpub.clone()),
+        slots,
+        fee_rate: Some(FeeRateRequest::Rate(1.0)),
+    }
+}
+
+#[tokio::test]
+pub async fn plan_vault_slots_per_asset_type() -> Result<()> {
+    // 1. Initial Setup
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let wallet_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &SecretString(keys.public.btc_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+
+    // 2. Plan the vault slots
+    let plan = plan_vault_utxos(
+        &keys.private.nostr_prv,
+        plan_request(
+            &keys,
+            vec![
+                VaultSlotRequest {
+                    asset_type: AssetType::RGB20,
+                    count: 3,
+                    amount: 1_000,
+                },
+                VaultSlotRequest {
+                    asset_type: AssetType::RGB21,
+                    count: 2,
+                    amount: 800,
+                },
+            ],
+        ),
+    )
+    .await?;
+
+    let fungibles = plan
+        .slots
+        .iter()
+        .find(|slot| slot.asset_type == AssetType::RGB20)
+        .expect("fungible slots");
+    let udas = plan
+        .slots
+        .iter()
+        .find(|slot| slot.asset_type == AssetType::RGB21)
+        .expect("uda slots");
+    assert_eq!(fungibles.outpoints.len(), 3);
+    assert_eq!(udas.outpoints.len(), 2);
+
+    let outpoints: BTreeSet<_> = fungibles.outpoints.iter().chain(&udas.outpoints).collect();
+    assert_eq!(outpoints.len(), 5, "every slot has its own output");
+
+    // 3. Slots land in the vault, on distinct addresses
+    let whatever_address = "bcrt1p76gtucrxhmn8s5622r859dpnmkj0kgfcel9xy0sz6yj84x6ppz2qk5hpsw";
+    send_some_coins(whatever_address, "0.1").await;
+
+    let assets_wallet = get_wallet(
+        &SecretString(keys.public.rgb_assets_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+    sync_wallet(&assets_wallet).await?;
+    let assets_utxos = assets_wallet.lock().await.list_unspent()?;
+    let scripts: BTreeSet<_> = assets_utxos
+        .iter()
+        .filter(|utxo| fungibles.outpoints.contains(&utxo.outpoint.to_string()))
+        .map(|utxo| utxo.txout.script_pubkey.clone())
+        .collect();
+    assert_eq!(scripts.len(), 3, "no address reuse");
+
+    // 4. Planning again only tops up the missing slots
+    let top_up = vec![
+        VaultSlotRequest {
+            asset_type: AssetType::RGB20,
+            count: 4,
+            amount: 1_000,
+        },
+        VaultSlotRequest {
+            asset_type: AssetType::RGB21,
+            count: 2,
+            amount: 800,
+        },
+    ];
+    let topped =
+        plan_vault_utxos(&keys.private.nostr_prv, plan_request(&keys, top_up.clone())).await?;
+    let topped_txid = topped.txid.clone().expect("missing slots are paid");
+    let topped_fungibles = topped
+        .slots
+        .iter()
+        .find(|slot| slot.asset_type == AssetType::RGB20)
+        .expect("fungible slots");
+    assert_eq!(topped_fungibles.outpoints.len(), 4);
+    assert!(fungibles
+        .outpoints
+        .iter()
+        .all(|outpoint| topped_fungibles.outpoints.contains(outpoint)));
+    let topped_outpoints: BTreeSet<_> = topped
+        .slots
+        .iter()
+        .flat_map(|slot| slot.outpoints.iter())
+        .filter(|outpoint| outpoint.starts_with(&topped_txid))
+        .collect();
+    assert_eq!(topped_outpoints.len(), 1, "only the missing slot is paid");
+
+    // 5. A vault with enough free slots needs no transaction
+    send_some_coins(whatever_address, "0.1").await;
+    let unchanged = plan_vault_utxos(&keys.private.nostr_prv, plan_request(&keys, top_up)).await?;
+    assert!(unchanged.txid.is_none());
+    assert!(unchanged.fee.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn plan_vault_slots_rejects_dust() -> Result<()> {
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+
+    let plan = plan_vault_utxos(
+        &keys.private.nostr_prv,
+        plan_request(
+            &keys,
+            vec![VaultSlotRequest {
+                asset_type: AssetType::RGB20,
+                count: 1,
+                amount: 100,
+            }],
+        ),
+    )
+    .await;
+    assert!(plan.is_err());
+
+    Ok(())
+}
@@ -89,7 +89,7 @@ async fn check_fungible_state_after_accept_consig() -> anyhow::Result<()> {
         )]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     assert!(resp.is_ok());
     send_some_coins(whatever_address, "0.001").await;
 
@@ -192,7 +192,7 @@ async fn check_uda_state_after_accept_consig() -> anyhow::Result<()> {
         )]
         .to_vec(),
     };
-    let resp = sign_and_publish_psbt_file(request).await;
+    let resp = sign_and_publish_psbt_file(&issuer_keys.private.nostr_prv, request).await;
     assert!(resp.is_ok());
     send_some_coins(whatever_address, "0.001").await;
 
@@ -12,9 +12,9 @@ use bitmask_core::{
         create_watcher, get_contract, verify_transfers,
     },
     structs::{
-        AcceptRequest, IssueResponse, PsbtFeeRequest, RgbBidRequest, RgbBidResponse,
-        RgbOfferRequest, RgbOfferResponse, RgbSwapRequest, RgbSwapResponse, SecretString,
-        SignPsbtRequest, SignedPsbtResponse, WatcherRequest,
+        AcceptRequest, FeeRateRequest, IssueResponse, PsbtFeeRequest, RgbBidRequest,
+        RgbBidResponse, RgbOfferRequest, RgbOfferResponse, RgbSwapRequest, RgbSwapResponse,
+        SecretString, SignPsbtRequest, SignedPsbtResponse, WatcherRequest,
     },
 };
 
@@ -83,13 +83,14 @@ async fn create_scriptless_swap() -> anyhow::Result<()> {
     sync_wallet(&btc_wallet).await?;
 
     let fund_vault = fund_vault(
+        &seller_keys.private.nostr_prv,
         &btc_descriptor_xprv,
         &btc_change_descriptor_xprv,
         &assets_address_1,
         &assets_address_2,
         &uda_address_1,
         &uda_address_2,
-        Some(1.1),
+        Some(FeeRateRequest::Rate(1.1)),
     )
     .await?;
 
@@ -215,7 +216,8 @@ async fn create_scriptless_swap() -> anyhow::Result<()> {
             SecretString(seller_keys.private.rgb_assets_descriptor_xprv.clone()),
         ],
     };
-    let seller_psbt_resp = sign_and_publish_psbt_file(request).await;
+    let seller_psbt_resp =
+        sign_and_publish_psbt_file(&seller_keys.private.nostr_prv, request).await;
     assert!(seller_psbt_resp.is_ok());
 
     // 9. Accept Consig (Buyer/Seller)
@@ -317,13 +319,14 @@ async fn create_scriptless_swap_for_uda() -> anyhow::Result<()> {
     sync_wallet(&btc_wallet).await?;
 
     let fund_vault = fund_vault(
+        &seller_keys.private.nostr_prv,
         &btc_descriptor_xprv,
         &btc_change_descriptor_xprv,
         &assets_address_1,
         &assets_address_2,
         &uda_address_1,
         &uda_address_2,
-        Some(1.1),
+        Some(FeeRateRequest::Rate(1.1)),
     )
     .await?;
 
@@ -447,7 +450,8 @@ async fn create_scriptless_swap_for_uda() -> anyhow::Result<()> {
             SecretString(seller_keys.private.rgb_udas_descriptor_xprv.clone()),
         ],
     };
-    let seller_psbt_resp = sign_and_publish_psbt_file(request).await;
+    let seller_psbt_resp =
+        sign_and_publish_psbt_file(&seller_keys.private.nostr_prv, request).await;
     assert!(seller_psbt_resp.is_ok());
 
     // 9. Accept Consig (Buyer/Seller)
@@ -0,0 +1,91 @@
+#![cfg(not(target_arch = "wasm32"))]
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{
+        get_wallet_data, new_mnemonic, subscribe_sync_events, sync_wallet_descriptors,
+        sync_wallets, wallet_key,
+    },
+    structs::{SecretString, WalletSyncDescriptors, WalletSyncRequest, WalletSyncStatus},
+};
+
+use crate::rgb::integration::utils::send_some_coins;
+
+#[tokio::test]
+pub async fn sync_wallets_emits_progress() -> Result<()> {
+    // 1. Cache two funded wallets
+    let mut keys_created = BTreeSet::new();
+    for _ in 0..2 {
+        let keys = new_mnemonic(&SecretString("".to_string())).await?;
+        let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+        let change_descriptor = SecretString(keys.public.btc_change_descriptor_xpub.clone());
+
+        let wallet_data = get_wallet_data(
+            &keys.private.nostr_prv,
+            &descriptor,
+            Some(&change_descriptor),
+        )
+        .await?;
+        send_some_coins(&wallet_data.address, "0.01").await;
+        keys_created.insert(wallet_key(&descriptor, Some(&change_descriptor)));
+    }
+
+    // 2. Sync every cached wallet while listening
+    let mut events = subscribe_sync_events();
+    sync_wallets().await?;
+
+    let mut statuses = BTreeMap::new();
+    while let Ok(event) = events.try_recv() {
+        // Other tests cache wallets too
+        if !keys_created.contains(&event.wallet) {
+            continue;
+        }
+        if event.status == WalletSyncStatus::Synced {
+            assert!(event.scanned_scripts > 0);
+            assert!(event.tip_height.is_some());
+        }
+        statuses.insert(event.wallet, event.status);
+    }
+
+    // 3. Both wallets finished syncing
+    assert_eq!(statuses.len(), 2);
+    assert!(statuses
+        .values()
+        .all(|status| *status == WalletSyncStatus::Synced));
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn sync_wallet_descriptors_tags_its_events() -> Result<()> {
+    // 1. Funded wallet
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let wallet_data = get_wallet_data(&keys.private.nostr_prv, &descriptor, None).await?;
+    send_some_coins(&wallet_data.address, "0.01").await;
+    let key = wallet_key(&descriptor, None);
+
+    // 2. Sync it as a job while listening
+    let mut events = subscribe_sync_events();
+    let request = WalletSyncRequest {
+        wallets: vec![WalletSyncDescriptors {
+            descriptor,
+            change_descriptor: None,
+        }],
+    };
+    sync_wallet_descriptors(request, Some("job".to_string())).await?;
+
+    // 3. Every event of the wallet carries the job
+    let mut received = 0;
+    while let Ok(event) = events.try_recv() {
+        if event.wallet != key {
+            continue;
+        }
+        assert_eq!(event.job.as_deref(), Some("job"));
+        received += 1;
+    }
+    assert_eq!(received, 2);
+
+    Ok(())
+}
@@ -125,6 +125,7 @@ pub async fn setup_regtest(force: bool, mnemonic: Option<&str>) {
 
         // Send Coins to RGB Wallet
         let fungible_snapshot = get_wallet_data(
+            &vault_data.private.nostr_prv,
             &SecretString(vault_data.public.rgb_assets_descriptor_xpub.clone()),
             None,
         )
@@ -134,6 +135,7 @@ pub async fn setup_regtest(force: bool, mnemonic: Option<&str>) {
 
         // Send Coins to RGB Wallet
         let uda_snapshot = get_wallet_data(
+            &vault_data.private.nostr_prv,
             &SecretString(vault_data.public.rgb_udas_descriptor_xpub.clone()),
             None,
         )
@@ -220,6 +222,7 @@ pub async fn issuer_issue_contract(
         seal: issue_seal.to_owned(),
         iface: iface.to_string(),
         meta,
+        inflation: None,
     };
 
     let resp = issue_contract(sk, request).await?;
@@ -249,6 +252,7 @@ pub async fn import_new_contract(
     let contract_type = match issuer_resp.iface.as_str() {
         "RGB20" => AssetType::RGB20,
         "RGB21" => AssetType::RGB21,
+        "RGB25" => AssetType::RGB25,
         _ => AssetType::Contract,
     };
 
@@ -282,6 +286,7 @@ pub async fn create_new_invoice(
     let contract_type = match iface {
         "RGB20" => AssetType::RGB20,
         "RGB21" => AssetType::RGB21,
+        "RGB25" => AssetType::RGB25,
         _ => AssetType::Contract,
     };
 
@@ -469,6 +474,7 @@ pub async fn issuer_issue_contract_v2(
             seal: issue_seal.to_owned(),
             iface: iface.to_string(),
             meta: meta.clone(),
+            inflation: None,
         };
         let contract = issue_contract(sk, request).await?;
         contracts.push(contract);
@@ -491,6 +497,7 @@ pub async fn create_new_invoice_v2(
     let contract_type = match iface {
         "RGB20" => AssetType::RGB20,
         "RGB21" => AssetType::RGB21,
+        "RGB25" => AssetType::RGB25,
         _ => AssetType::Contract,
     };
 
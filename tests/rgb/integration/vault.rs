@@ -0,0 +1,58 @@
+#![cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{create_vault, get_wallet_data, new_mnemonic, recover_vault, BitcoinVaultError},
+    structs::{FeeRateRequest, SecretString, VaultRecoveryRequest, VaultRequest},
+};
+
+use crate::rgb::integration::utils::send_some_coins;
+
+#[tokio::test]
+pub async fn recover_timelocked_vault() -> Result<()> {
+    // 1. Initial Setup
+    let primary_keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let recovery_keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let timelock = 3;
+
+    let vault = create_vault(VaultRequest {
+        primary: SecretString(primary_keys.public.btc_descriptor_xpub.clone()),
+        recovery: SecretString(recovery_keys.public.btc_descriptor_xpub.clone()),
+        timelock,
+    })
+    .await?;
+    assert!(vault.descriptor.0.contains(&format!("older({timelock})")));
+
+    // 2. Fund the vault, 2 blocks are mined
+    send_some_coins(&vault.wallet.address, "0.1").await;
+
+    let heir_wallet = get_wallet_data(
+        &recovery_keys.private.nostr_prv,
+        &SecretString(recovery_keys.public.btc_change_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+    let recovery_request = VaultRecoveryRequest {
+        primary: SecretString(primary_keys.public.btc_descriptor_xpub.clone()),
+        recovery: SecretString(recovery_keys.private.btc_descriptor_xprv.clone()),
+        timelock,
+        destination: heir_wallet.address.clone(),
+        fee_rate: Some(FeeRateRequest::Rate(1.0)),
+    };
+
+    // 3. Recovery path is locked until the coins are 3 blocks old
+    let locked = recover_vault(&recovery_keys.private.nostr_prv, recovery_request.clone()).await;
+    assert!(matches!(
+        locked,
+        Err(BitcoinVaultError::TimelockNotMatured(1))
+    ));
+
+    // 4. Mine 2 more blocks and sweep the vault to the heir
+    send_some_coins(&heir_wallet.address, "0.01").await;
+    let details = recover_vault(&recovery_keys.private.nostr_prv, recovery_request).await?;
+
+    let fee = details.fee.expect("fee present");
+    assert_eq!(details.received, details.sent - fee);
+    assert_eq!(details.sent + fee, 10_000_000, "vault is swept");
+
+    Ok(())
+}
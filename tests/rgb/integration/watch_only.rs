@@ -0,0 +1,78 @@
+#![cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use bitmask_core::{
+    bitcoin::{
+        build_send_sats, get_wallet_data, new_mnemonic, publish_psbt_file, save_mnemonic,
+        sign_psbt_file,
+    },
+    structs::{FeeRateRequest, PublishPsbtRequest, SecretString, SignPsbtRequest},
+};
+
+use crate::rgb::integration::utils::{send_some_coins, OWNER_MNEMONIC};
+
+#[tokio::test]
+pub async fn watch_only_send_sats() -> Result<()> {
+    // 1. Initial Setup
+    let keys = new_mnemonic(&SecretString("".to_string())).await?;
+    let dest_keys = save_mnemonic(
+        &SecretString(OWNER_MNEMONIC.to_string()),
+        &SecretString("".to_string()),
+    )
+    .await?;
+
+    let descriptor = SecretString(keys.public.btc_descriptor_xpub.clone());
+    let change_descriptor = SecretString(keys.public.btc_change_descriptor_xpub.clone());
+    let wallet_data = get_wallet_data(
+        &keys.private.nostr_prv,
+        &descriptor,
+        Some(&change_descriptor),
+    )
+    .await?;
+    send_some_coins(&wallet_data.address, "0.1").await;
+
+    let dest_wallet_data = get_wallet_data(
+        &dest_keys.private.nostr_prv,
+        &SecretString(dest_keys.public.btc_descriptor_xpub.clone()),
+        None,
+    )
+    .await?;
+
+    // 2. Build unsigned PSBT with the xpub descriptors
+    let unsigned = build_send_sats(
+        &keys.private.nostr_prv,
+        &descriptor,
+        &change_descriptor,
+        &dest_wallet_data.address,
+        1_000_000,
+        Some(FeeRateRequest::Rate(2.0)),
+    )
+    .await?;
+    assert!(unsigned.fee.is_some(), "fee present");
+
+    // 3. Sign externally with the xprv descriptors
+    let signed = sign_psbt_file(SignPsbtRequest {
+        psbt: unsigned.psbt,
+        descriptors: vec![
+            SecretString(keys.private.btc_descriptor_xprv.clone()),
+            SecretString(keys.private.btc_change_descriptor_xprv.clone()),
+        ],
+    })
+    .await?;
+    assert!(signed.sign);
+
+    // 4. Finalize and broadcast with the xpub descriptors
+    let published = publish_psbt_file(
+        &keys.private.nostr_prv,
+        PublishPsbtRequest {
+            psbt: signed.psbt,
+            descriptor,
+            change_descriptor: Some(change_descriptor),
+        },
+    )
+    .await?;
+
+    assert!(published.sign);
+    assert_eq!(published.txid, unsigned.txid, "same transaction published");
+
+    Ok(())
+}
@@ -33,6 +33,7 @@ async fn issue_request_params_check() -> Result<()> {
         seal: seal.to_string(),
         iface: iface.to_string(),
         meta: None,
+        inflation: None,
     };
     assert!(rgb20.validate(ctx).is_ok());
 
@@ -45,6 +46,7 @@ async fn issue_request_params_check() -> Result<()> {
         seal: seal.to_string(),
         iface: iface.to_string(),
         meta: Some(get_uda_data()),
+        inflation: None,
     };
     assert!(rgb21.validate(ctx).is_ok());
 
@@ -78,6 +80,7 @@ async fn issue_contract_test() -> Result<()> {
         seal,
         network,
         None,
+        None,
         BTreeMap::new(),
         &mut resolver,
         &mut stock,
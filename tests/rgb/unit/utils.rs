@@ -112,6 +112,7 @@ pub fn create_fake_contract(stock: &mut Stock) -> ContractId {
         seal,
         network,
         None,
+        None,
         BTreeMap::new(),
         &mut resolver,
         stock,
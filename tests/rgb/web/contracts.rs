@@ -46,7 +46,7 @@ async fn allow_issue_and_list_contracts() {
     let mnemonic_data: SecretString = json_parse(&mnemonic_data_str);
 
     info!("Get Vault");
-    let issuer_keys: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone())).await;
+    let issuer_keys: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone(), None)).await;
 
     info!("Get Keys");
     let issuer_keys: DecryptedWalletData = json_parse(&issuer_keys);
@@ -102,6 +102,7 @@ async fn allow_issue_and_list_contracts() {
         seal: issue_seal.to_owned(),
         iface: iface.to_string(),
         meta: None,
+        inflation: None,
     };
 
     let issue_req = serde_wasm_bindgen::to_value(&issue_req).expect("");
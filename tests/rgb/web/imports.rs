@@ -48,7 +48,7 @@ async fn import_fungible_from_genesis() {
     let mnemonic_data: SecretString = json_parse(&mnemonic_data_str);
 
     info!("Get vault properties");
-    let vault_str: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone())).await;
+    let vault_str: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone(), None)).await;
     let wallet_data: DecryptedWalletData = json_parse(&vault_str);
 
     info!("Import Genesis (Fungible)");
@@ -81,7 +81,7 @@ async fn import_uda_from_genesis() {
     let mnemonic_data: SecretString = json_parse(&mnemonic_data_str);
 
     info!("Get vault properties");
-    let vault_str: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone())).await;
+    let vault_str: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone(), None)).await;
     let wallet_data: DecryptedWalletData = json_parse(&vault_str);
 
     info!("Import Genesis (UDA)");
@@ -114,7 +114,7 @@ async fn import_two_contracts() {
     let mnemonic_data: SecretString = json_parse(&mnemonic_data_str);
 
     info!("Get vault properties");
-    let vault_str: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone())).await;
+    let vault_str: JsValue = resolve(decrypt_wallet(hash, mnemonic_data.0.clone(), None)).await;
     let wallet_data: DecryptedWalletData = json_parse(&vault_str);
     let sk = &wallet_data.private.nostr_prv;
 
@@ -158,17 +158,19 @@ async fn asset_transfer() {
 
     // Get vault properties
     let wallet_data_str: JsValue =
-        resolve(decrypt_wallet(hash, mnemonic_data.0.clone().clone())).await;
+        resolve(decrypt_wallet(hash, mnemonic_data.0.clone().clone(), None)).await;
     let wallet_data: DecryptedWalletData = json_parse(&wallet_data_str);
 
     info!("Get Wallets");
     let assets_wallet = resolve(get_wallet_data(
+        wallet_data.private.nostr_prv.clone(),
         wallet_data.public.rgb_assets_descriptor_xpub.clone(),
         None,
     ))
     .await;
     let assets_wallet: WalletData = json_parse(&assets_wallet);
     let udas_wallet = resolve(get_wallet_data(
+        wallet_data.private.nostr_prv.clone(),
         wallet_data.public.rgb_udas_descriptor_xpub.clone(),
         None,
     ))
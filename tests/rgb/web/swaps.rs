@@ -188,6 +188,7 @@ async fn create_transfer_swap_flow() {
         seal: issue_seal.to_owned(),
         iface: iface.to_string(),
         meta: None,
+        inflation: None,
     };
 
     let issue_req = serde_wasm_bindgen::to_value(&issue_req).expect("");
@@ -194,6 +194,7 @@ async fn create_transfer_with_fee_value() {
         seal: issue_seal.to_owned(),
         iface: iface.to_string(),
         meta: None,
+        inflation: None,
     };
 
     let issue_req = serde_wasm_bindgen::to_value(&issue_req).expect("");
@@ -495,6 +496,7 @@ async fn create_transfer_with_fee_rate() {
         seal: issue_seal.to_owned(),
         iface: iface.to_string(),
         meta: None,
+        inflation: None,
     };
 
     let issue_req = serde_wasm_bindgen::to_value(&issue_req).expect("");
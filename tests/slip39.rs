@@ -0,0 +1,141 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use anyhow::Result;
+use bip39::Mnemonic;
+use bitmask_core::{
+    bitcoin::{recover_mnemonic, save_mnemonic, split_mnemonic},
+    structs::{SecretString, Slip39Group, Slip39SplitRequest},
+    util::init_logging,
+};
+
+const MNEMONIC: &str =
+    "empty faculty salute fortune select asthma attract question violin movie smile erupt half step lion deposit render stumble double mobile fossil height usual topple";
+const SEED_PASSWORD: &str = "";
+// Passphrase of the SLIP-39 test vectors
+const PASSPHRASE: &str = "TREZOR";
+
+fn shares(mnemonics: &[&str]) -> Vec<SecretString> {
+    mnemonics
+        .iter()
+        .map(|mnemonic| SecretString(mnemonic.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn slip39_vectors() -> Result<()> {
+    init_logging("slip39=debug");
+
+    let seed_password = SecretString(SEED_PASSWORD.to_owned());
+    let passphrase = SecretString(PASSPHRASE.to_owned());
+
+    // 1. Valid mnemonic without sharing (128 bits)
+    let wallet = recover_mnemonic(
+        &shares(&["duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard"]),
+        Some(&passphrase),
+        &seed_password,
+    )
+    .await?;
+    let master_secret = hex::decode("bb54aac4b89dc868ba37d9cc21b2cece")?;
+    assert_eq!(
+        wallet.mnemonic,
+        Mnemonic::from_entropy(&master_secret)?.to_string()
+    );
+
+    // 2. Mnemonic with invalid checksum (128 bits)
+    let invalid = recover_mnemonic(
+        &shares(&["duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision kidney"]),
+        Some(&passphrase),
+        &seed_password,
+    )
+    .await;
+    assert!(invalid.is_err(), "invalid checksum is rejected");
+
+    // 4. Basic sharing 2-of-3 (128 bits)
+    let wallet = recover_mnemonic(
+        &shares(&[
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ]),
+        Some(&passphrase),
+        &seed_password,
+    )
+    .await?;
+    let master_secret = hex::decode("b43ceb7e57a0ea8766221624d01b0864")?;
+    assert_eq!(
+        wallet.mnemonic,
+        Mnemonic::from_entropy(&master_secret)?.to_string()
+    );
+
+    // 5. Basic sharing 2-of-3 (128 bits), below the member threshold
+    let insufficient = recover_mnemonic(
+        &shares(&["shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed"]),
+        Some(&passphrase),
+        &seed_password,
+    )
+    .await;
+    assert!(insufficient.is_err(), "one share of 2-of-3 is rejected");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn slip39_split_and_recover() -> Result<()> {
+    init_logging("slip39=debug");
+
+    let seed_password = SecretString(SEED_PASSWORD.to_owned());
+    let wallet = save_mnemonic(&SecretString(MNEMONIC.to_owned()), &seed_password).await?;
+
+    // 2 of the 3 groups: 2-of-3 officers, 3-of-5 directors and 1-of-1 vault
+    let split = split_mnemonic(
+        &SecretString(MNEMONIC.to_owned()),
+        &Slip39SplitRequest {
+            group_threshold: 2,
+            groups: vec![
+                Slip39Group {
+                    member_threshold: 2,
+                    member_count: 3,
+                },
+                Slip39Group {
+                    member_threshold: 3,
+                    member_count: 5,
+                },
+                Slip39Group {
+                    member_threshold: 1,
+                    member_count: 1,
+                },
+            ],
+            passphrase: Some(SecretString(PASSPHRASE.to_owned())),
+        },
+    )?;
+    assert_eq!(split.groups.len(), 3);
+    assert_eq!(split.groups[1].len(), 5);
+
+    let quorum = vec![
+        split.groups[0][0].clone(),
+        split.groups[0][2].clone(),
+        split.groups[2][0].clone(),
+    ];
+    let recovered = recover_mnemonic(
+        &quorum,
+        Some(&SecretString(PASSPHRASE.to_owned())),
+        &seed_password,
+    )
+    .await?;
+    assert_eq!(recovered.mnemonic, wallet.mnemonic);
+    assert_eq!(
+        recovered.public.btc_descriptor_xpub,
+        wallet.public.btc_descriptor_xpub
+    );
+
+    // A wrong passphrase recovers a different wallet
+    let other = recover_mnemonic(&quorum, None, &seed_password).await?;
+    assert_ne!(other.mnemonic, wallet.mnemonic);
+
+    // One group is not enough
+    let partial = vec![split.groups[0][0].clone(), split.groups[0][1].clone()];
+    assert!(recover_mnemonic(&partial, None, &seed_password)
+        .await
+        .is_err());
+
+    Ok(())
+}
@@ -4,18 +4,22 @@ use std::env;
 use anyhow::Result;
 use bitmask_core::{
     bitcoin::{
-        decrypt_wallet, encrypt_wallet, get_wallet_data, hash_password, new_wallet, send_sats,
-        BitcoinError,
+        add_account, decrypt_wallet, encrypt_wallet, freeze_utxos, get_wallet_data, hash_password,
+        list_frozen_utxos, new_wallet, save_mnemonic, select_account, send_sats, unfreeze_utxos,
+        upgrade_wallet, BitcoinCoinsError, BitcoinError,
     },
-    constants::{get_network, switch_network},
-    structs::SecretString,
+    constants::{get_network, switch_network, DIBA_DESCRIPTOR},
+    structs::{DecryptedWalletData, FeeRateRequest, SecretString},
     util::init_logging,
     warn,
 };
 use log::info;
+use serde_encrypt::{shared_key::SharedKey, traits::SerdeEncryptSharedKey, AsSharedKey};
 
 const ENCRYPTION_PASSWORD: &str = "hunter2";
 const SEED_PASSWORD: &str = "";
+const MNEMONIC: &str =
+    "empty faculty salute fortune select asthma attract question violin movie smile erupt half step lion deposit render stumble double mobile fossil height usual topple";
 
 #[tokio::test]
 async fn error_for_bad_mnemonic() -> Result<()> {
@@ -53,6 +57,7 @@ async fn create_wallet() -> Result<()> {
     let decrypted_wallet = decrypt_wallet(&hash, &encrypted_descriptors)?;
 
     let main_btc_wallet = get_wallet_data(
+        &decrypted_wallet.private.nostr_prv,
         &SecretString(decrypted_wallet.private.btc_descriptor_xprv.clone()),
         None,
     )
@@ -69,6 +74,124 @@ async fn create_wallet() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn encrypted_descriptor_salt() -> Result<()> {
+    init_logging("wallet=info");
+
+    let hash = hash_password(&SecretString(ENCRYPTION_PASSWORD.to_owned()));
+    let seed_password = SecretString(SEED_PASSWORD.to_owned());
+    let mnemonic = SecretString(MNEMONIC.to_owned());
+
+    let encrypted_1 = encrypt_wallet(&mnemonic, &hash, &seed_password).await?;
+    let encrypted_2 = encrypt_wallet(&mnemonic, &hash, &seed_password).await?;
+
+    // DIBA magic number followed by version 1
+    assert!(encrypted_1.0.starts_with("4449424101"));
+    assert_ne!(
+        encrypted_1.0[10..42],
+        encrypted_2.0[10..42],
+        "each wallet has its own salt"
+    );
+
+    let wallet_1 = decrypt_wallet(&hash, &encrypted_1)?;
+    let wallet_2 = decrypt_wallet(&hash, &encrypted_2)?;
+    assert_eq!(wallet_1.public.xpub, wallet_2.public.xpub);
+
+    let wrong_hash = hash_password(&SecretString("hunter3".to_owned()));
+    assert!(decrypt_wallet(&wrong_hash, &encrypted_1).is_err());
+
+    // Argon2 costs above the maximum are rejected before deriving the key
+    let mut oversized = encrypted_1.0.clone();
+    oversized.replace_range(42..50, "ffffffff");
+    assert!(matches!(
+        decrypt_wallet(&hash, &SecretString(oversized)),
+        Err(BitcoinError::InvalidKdfParams(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upgrade_unsalted_descriptor() -> Result<()> {
+    init_logging("wallet=info");
+
+    let hash = hash_password(&SecretString(ENCRYPTION_PASSWORD.to_owned()));
+    let seed_password = SecretString(SEED_PASSWORD.to_owned());
+    let wallet_data = save_mnemonic(&SecretString(MNEMONIC.to_owned()), &seed_password).await?;
+
+    // Version 0 descriptors are encrypted with the password hash directly
+    let shared_key: [u8; 32] = hex::decode(&hash.0)?.try_into().unwrap();
+    let encrypted_message =
+        DecryptedWalletData::encrypt(&wallet_data, &SharedKey::from_array(shared_key))?;
+    let mut descriptor_data = DIBA_DESCRIPTOR.to_vec();
+    descriptor_data.append(&mut encrypted_message.serialize());
+    let encrypted_v0 = SecretString(hex::encode(descriptor_data));
+
+    let wallet = decrypt_wallet(&hash, &encrypted_v0)?;
+    assert_eq!(wallet.public.xpub, wallet_data.public.xpub);
+
+    let upgraded = upgrade_wallet(&hash, &encrypted_v0, &seed_password).await?;
+    assert!(upgraded.0.starts_with("4449424101"));
+
+    let wallet = decrypt_wallet(&hash, &upgraded)?;
+    assert_eq!(wallet.public.xpub, wallet_data.public.xpub);
+    assert_eq!(
+        wallet.private.btc_descriptor_xprv,
+        wallet_data.private.btc_descriptor_xprv
+    );
+
+    assert!(matches!(
+        upgrade_wallet(&hash, &upgraded, &seed_password).await,
+        Err(BitcoinError::UpgradeUnnecessary)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn named_accounts() -> Result<()> {
+    init_logging("wallet=info");
+
+    let hash = hash_password(&SecretString(ENCRYPTION_PASSWORD.to_owned()));
+    let seed_password = SecretString(SEED_PASSWORD.to_owned());
+    let encrypted =
+        encrypt_wallet(&SecretString(MNEMONIC.to_owned()), &hash, &seed_password).await?;
+
+    let encrypted = add_account(&hash, &encrypted, &seed_password, "business", 1).await?;
+    assert!(matches!(
+        add_account(&hash, &encrypted, &seed_password, "business", 2).await,
+        Err(BitcoinError::AccountExists(_, _))
+    ));
+
+    let wallet_data = decrypt_wallet(&hash, &encrypted)?;
+    assert_eq!(wallet_data.accounts.len(), 1);
+
+    let personal = select_account(&wallet_data, None)?;
+    let business = select_account(&wallet_data, Some("business"))?;
+    let by_index = select_account(&wallet_data, Some("1"))?;
+
+    assert_eq!(personal.public.xpub, wallet_data.public.xpub);
+    assert_eq!(
+        business.public.btc_descriptor_xpub,
+        by_index.public.btc_descriptor_xpub
+    );
+    assert_ne!(
+        business.public.btc_descriptor_xpub,
+        personal.public.btc_descriptor_xpub
+    );
+    assert!(business.public.btc_descriptor_xpub.contains("'/1']"));
+
+    // Each account has its own nostr key (and so its own carbonado storage) and watcher
+    assert_ne!(business.private.nostr_prv, personal.private.nostr_prv);
+    assert_ne!(business.private.xprvkh, personal.private.xprvkh);
+    assert_ne!(business.public.xpub, personal.public.xpub);
+    assert_ne!(business.public.xpubkh, personal.public.xpubkh);
+    assert_ne!(business.public.watcher_xpub, personal.public.watcher_xpub);
+    assert!(select_account(&wallet_data, Some("savings")).is_err());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn import_wallet() -> Result<()> {
     init_logging("wallet=info");
@@ -91,11 +214,13 @@ async fn import_wallet() -> Result<()> {
     let main_vault = decrypt_wallet(&hash1, &encrypted_descriptors)?;
 
     let main_btc_wallet = get_wallet_data(
+        &main_vault.private.nostr_prv,
         &SecretString(main_vault.private.btc_descriptor_xprv.clone()),
         None,
     )
     .await?;
     let main_rgb_wallet = get_wallet_data(
+        &main_vault.private.nostr_prv,
         &SecretString(main_vault.private.rgb_assets_descriptor_xprv.clone()),
         None,
     )
@@ -119,6 +244,7 @@ async fn get_wallet_balance() -> Result<()> {
     let main_vault = decrypt_wallet(&hash, &encrypted_descriptors)?;
 
     let btc_wallet = get_wallet_data(
+        &main_vault.private.nostr_prv,
         &SecretString(main_vault.private.btc_descriptor_xprv.clone()),
         None,
     )
@@ -147,11 +273,12 @@ async fn wrong_network() -> Result<()> {
     let main_vault = decrypt_wallet(&hash, &encrypted_descriptors)?;
 
     let result = send_sats(
+        &main_vault.private.nostr_prv,
         &SecretString(main_vault.private.btc_descriptor_xprv.to_owned()),
         &SecretString(main_vault.private.btc_change_descriptor_xprv.to_owned()),
         "bc1pgxpvg7cz0s3akgl9vhv687rzya7frskenukgx3gwuh6q3un5wqgq7xmnhe",
         1000,
-        Some(1.0),
+        Some(FeeRateRequest::Rate(1.0)),
     )
     .await;
 
@@ -159,3 +286,34 @@ async fn wrong_network() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn freeze_and_unfreeze_utxos() -> Result<()> {
+    init_logging("wallet=info");
+
+    let hash = hash_password(&SecretString(ENCRYPTION_PASSWORD.to_owned()));
+    let encrypted_descriptors = new_wallet(&hash, &SecretString(SEED_PASSWORD.to_owned())).await?;
+    let decrypted_wallet = decrypt_wallet(&hash, &encrypted_descriptors)?;
+    let sk = decrypted_wallet.private.nostr_prv.as_str();
+
+    let utxo = "1f8c3a4f2e0a0d2c9b1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c:1";
+    let frozen = freeze_utxos(sk, vec![utxo.to_owned()]).await?;
+    assert_eq!(frozen.frozen, vec![utxo.to_owned()]);
+    assert!(frozen.rgb.is_empty());
+
+    let frozen = list_frozen_utxos(sk).await?;
+    assert_eq!(frozen.frozen, vec![utxo.to_owned()]);
+
+    let invalid = freeze_utxos(sk, vec!["not an outpoint".to_owned()]).await;
+    assert!(matches!(
+        invalid,
+        Err(BitcoinError::BitcoinCoinsError(
+            BitcoinCoinsError::InvalidOutpoint(_)
+        ))
+    ));
+
+    let frozen = unfreeze_utxos(sk, vec![utxo.to_owned()]).await?;
+    assert!(frozen.frozen.is_empty());
+
+    Ok(())
+}
@@ -41,7 +41,7 @@ async fn create_wallet() {
     let mnemonic_data: SecretString = json_parse(&mnemonic);
 
     let encrypted_wallet_str: JsValue =
-        resolve(decrypt_wallet(hash, mnemonic_data.0.clone())).await;
+        resolve(decrypt_wallet(hash, mnemonic_data.0.clone(), None)).await;
     let encrypted_wallet_data: DecryptedWalletData = json_parse(&encrypted_wallet_str);
 
     assert_eq!(encrypted_wallet_data.mnemonic.split(' ').count(), 24);
@@ -66,7 +66,7 @@ async fn import_and_open_wallet() {
 
     info!("Get encrypted wallet properties");
     let encrypted_wallet_str: JsValue =
-        resolve(decrypt_wallet(hash, mnemonic_data.0.clone())).await;
+        resolve(decrypt_wallet(hash, mnemonic_data.0.clone(), None)).await;
     let encrypted_wallet_data: DecryptedWalletData = json_parse(&encrypted_wallet_str);
 
     assert_eq!(
@@ -84,6 +84,7 @@ async fn import_and_open_wallet() {
 
     info!("Get wallet data");
     let wallet_str: JsValue = resolve(get_wallet_data(
+        encrypted_wallet_data.private.nostr_prv.clone(),
         DESCRIPTOR.to_owned(),
         Some(CHANGE_DESCRIPTOR.to_owned()),
     ))
@@ -115,7 +116,8 @@ async fn import_test_wallet() {
     let mnemonic_data: SecretString = json_parse(&mnemonic_data_str);
 
     info!("Get vault properties");
-    let vault_str: JsValue = resolve(decrypt_wallet(hash0.clone(), mnemonic_data.0.clone())).await;
+    let vault_str: JsValue =
+        resolve(decrypt_wallet(hash0.clone(), mnemonic_data.0.clone(), None)).await;
     let _encrypted_wallet_data: DecryptedWalletData = json_parse(&vault_str);
 
     info!("Import wallet once more");
@@ -131,11 +133,12 @@ async fn import_test_wallet() {
     let mnemonic_data: SecretString = json_parse(&mnemonic_data_str);
 
     info!("Get vault properties");
-    let vault_str: JsValue = resolve(decrypt_wallet(hash1, mnemonic_data.0.clone())).await;
+    let vault_str: JsValue = resolve(decrypt_wallet(hash1, mnemonic_data.0.clone(), None)).await;
     let encrypted_wallet_data: DecryptedWalletData = json_parse(&vault_str);
 
     info!("Get wallet data");
     let wallet_str: JsValue = resolve(get_wallet_data(
+        encrypted_wallet_data.private.nostr_prv.clone(),
         encrypted_wallet_data.private.btc_descriptor_xprv.clone(),
         Some(
             encrypted_wallet_data
@@ -174,6 +177,7 @@ async fn import_test_wallet() {
 
     info!("Test sending a transaction back to itself for a thousand sats");
     let tx_details = resolve(send_sats(
+        encrypted_wallet_data.private.nostr_prv.clone(),
         encrypted_wallet_data.private.btc_descriptor_xprv.clone(),
         encrypted_wallet_data
             .private
@@ -182,6 +186,7 @@ async fn import_test_wallet() {
         wallet_data.address,
         1_000,
         Some(1.1),
+        None,
     ))
     .await;
 